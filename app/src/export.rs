@@ -0,0 +1,220 @@
+use dbmiru_db::Cell;
+
+pub fn to_tsv<'a>(columns: &[String], rows: impl IntoIterator<Item = &'a Vec<Cell>>) -> String {
+    to_delimited(columns, rows, '\t')
+}
+
+pub fn to_delimited<'a>(
+    columns: &[String],
+    rows: impl IntoIterator<Item = &'a Vec<Cell>>,
+    delimiter: char,
+) -> String {
+    let mut out = String::new();
+    push_record(&mut out, columns.iter().map(String::as_str), delimiter);
+    for row in rows {
+        push_record(
+            &mut out,
+            row.iter().map(|cell| cell.as_text().unwrap_or_default()),
+            delimiter,
+        );
+    }
+    out
+}
+
+fn push_record<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>, delimiter: char) {
+    for (idx, field) in fields.enumerate() {
+        if idx > 0 {
+            out.push(delimiter);
+        }
+        out.push_str(&escape_field(field, delimiter));
+    }
+    out.push('\n');
+}
+
+fn escape_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Pasted spreadsheet cells arrive tab-separated; otherwise prefer `;` only when
+// the header has no commas.
+pub fn detect_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or_default();
+    if first_line.contains('\t') {
+        '\t'
+    } else if first_line.contains(';') && !first_line.contains(',') {
+        ';'
+    } else {
+        ','
+    }
+}
+
+// Parses RFC 4180 style records. Unquoted empty fields come back as `None` so
+// they can be inserted as NULL, while `""` stays an empty string. Blank lines
+// are skipped.
+pub fn parse_delimited(text: &str, delimiter: char) -> Result<Vec<Vec<Option<String>>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                let start_line = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(ch) => {
+                            if ch == '\n' {
+                                line += 1;
+                            }
+                            field.push(ch);
+                        }
+                        None => {
+                            return Err(format!("line {start_line}: unterminated quoted field"));
+                        }
+                    }
+                }
+                if chars
+                    .peek()
+                    .is_some_and(|next| *next != delimiter && !matches!(next, '\r' | '\n'))
+                {
+                    return Err(format!("line {line}: unexpected text after a quoted field"));
+                }
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                push_field(&mut record, &mut field, &mut quoted);
+                if !(record.len() == 1 && record[0].is_none()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+                line += 1;
+            }
+            ch if ch == delimiter => push_field(&mut record, &mut field, &mut quoted),
+            ch => field.push(ch),
+        }
+    }
+    if !field.is_empty() || quoted || !record.is_empty() {
+        push_field(&mut record, &mut field, &mut quoted);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn push_field(record: &mut Vec<Option<String>>, field: &mut String, quoted: &mut bool) {
+    let value = std::mem::take(field);
+    record.push((*quoted || !value.is_empty()).then_some(value));
+    *quoted = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(values: &[Option<&str>]) -> Vec<Option<String>> {
+        values
+            .iter()
+            .map(|value| value.map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn quoted_fields_keep_newlines_and_doubled_quotes() {
+        let records = parse_delimited("id,note\n1,\"line one\nline \"\"two\"\"\"\n", ',').unwrap();
+        assert_eq!(
+            records,
+            [
+                fields(&[Some("id"), Some("note")]),
+                fields(&[Some("1"), Some("line one\nline \"two\"")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_unquoted_field_is_null_and_quoted_is_empty_string() {
+        let records = parse_delimited("a,\"\",,\"x\"\n,\n", ',').unwrap();
+        assert_eq!(
+            records,
+            [
+                fields(&[Some("a"), Some(""), None, Some("x")]),
+                fields(&[None, None]),
+            ]
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let records = parse_delimited("a;b\r\n\"1\r\n2\";3\r\n", ';').unwrap();
+        assert_eq!(
+            records,
+            [
+                fields(&[Some("a"), Some("b")]),
+                fields(&[Some("1\r\n2"), Some("3")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        let records = parse_delimited("a\tb\n\n1\t2\n\n\n3\t4", '\t').unwrap();
+        assert_eq!(
+            records,
+            [
+                fields(&[Some("a"), Some("b")]),
+                fields(&[Some("1"), Some("2")]),
+                fields(&[Some("3"), Some("4")]),
+            ]
+        );
+        assert_eq!(
+            parse_delimited("", ',').unwrap(),
+            Vec::<Vec<Option<String>>>::new()
+        );
+    }
+
+    #[test]
+    fn unterminated_quote_reports_its_starting_line() {
+        assert_eq!(
+            parse_delimited("a,b\n1,\"open\nstill open\n", ','),
+            Err("line 2: unterminated quoted field".into())
+        );
+        assert_eq!(
+            parse_delimited("a,b\n\"x\"y,2\n", ','),
+            Err("line 2: unexpected text after a quoted field".into())
+        );
+    }
+
+    #[test]
+    fn delimited_output_parses_back() {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![vec![
+            Cell::Text("1".into()),
+            Cell::Text("a,\"b\"\nc".into()),
+        ]];
+        let csv = to_delimited(&columns, &rows, ',');
+        assert_eq!(csv, "id,note\n1,\"a,\"\"b\"\"\nc\"\n");
+        assert_eq!(
+            parse_delimited(&csv, ',').unwrap()[1],
+            fields(&[Some("1"), Some("a,\"b\"\nc")])
+        );
+    }
+
+    #[test]
+    fn detects_delimiter_from_first_line() {
+        assert_eq!(detect_delimiter("a\tb,c\n1\t2"), '\t');
+        assert_eq!(detect_delimiter("a;b\n1,5;2"), ';');
+        assert_eq!(detect_delimiter("a;b,c\n"), ',');
+        assert_eq!(detect_delimiter("a,b\n1;2"), ',');
+        assert_eq!(detect_delimiter(""), ',');
+    }
+}
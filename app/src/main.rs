@@ -1,43 +1,119 @@
+mod export;
+mod palette;
+mod theme;
 mod widgets;
 
-use std::{borrow::Cow, fs, path::PathBuf, time::Duration};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    ops::Range,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_channel::{Receiver, Sender};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use dbmiru_core::{
     Result,
-    profiles::{ConnectionProfile, ProfileId},
+    profiles::{
+        ConnectionProfile, DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_RETRY_BACKOFF_SECS,
+        DEFAULT_ROW_LIMIT, DEFAULT_SSH_PORT, ProfileColor, ProfileId, ProfileKind, SshTunnel,
+        SslMode,
+    },
 };
 use dbmiru_db::{
-    self as db, ColumnMetadata, DbEvent, DbSessionHandle, PREVIEW_LIMIT, PostgresAdapter,
-    QueryResult, ROW_LIMIT,
+    self as db, Cell, ColumnMetadata, ColumnStats, DbEvent, DbSessionHandle, ForeignKeyMetadata,
+    IndexMetadata, PREVIEW_LIMIT, ParamValue, QueryError, QueryResult, ROW_LIMIT,
+    STATS_SAMPLE_ROWS, ServerInfo, SessionActivity, StatementTiming, ViewMetadata,
+    schema_diff::{self, DiffKind, SchemaSnapshot, TableDiff},
+    sql::{
+        format_sql, is_destructive_statement, is_read_only_statement, is_row_query, page_query,
+        parameter_count, qualified_table_name, quote_identifier, single_source_table,
+        split_statements, statement_at, statement_tag,
+    },
+    sqlstate,
+};
+use dbmiru_storage::{
+    HistoryEntry, HistoryStore, ProfileStore, RowDensity, SecretStore, SessionState, SessionStore,
+    SessionTab, Settings, SettingsStore, Snippet, SnippetId, SnippetStore, ThemeMode, WindowState,
+    WindowStateStore,
+    settings::{
+        DEFAULT_EDITOR_HEIGHT, DEFAULT_SCHEMA_LIST_WIDTH, DEFAULT_SIDEBAR_WIDTH,
+        DEFAULT_TABLE_LIST_WIDTH,
+    },
 };
-use dbmiru_storage::ProfileStore;
 use directories::BaseDirs;
 use gpui::{
-    AnyElement, App, Application, Bounds, ClipboardItem, Context, Element, EventEmitter,
-    IntoElement, KeyBinding, MouseButton, MouseUpEvent, Pixels, Render, ScrollWheelEvent,
-    SharedString, Window, WindowBounds, WindowOptions, actions, div, prelude::*, px, rgb,
+    AnyElement, App, Application, Bounds, ClickEvent, ClipboardItem, Context, Element,
+    EventEmitter, FocusHandle, Focusable, IntoElement, KeyBinding, KeyDownEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PathPromptOptions, Pixels, Render, ScrollHandle,
+    ScrollWheelEvent, SharedString, Subscription, TitlebarOptions, WeakEntity, Window,
+    WindowBounds, WindowHandle, WindowOptions, actions, div, prelude::*, px, rems,
 };
+use palette::PaletteCommand;
+use theme::{ActiveTheme, Theme};
+use unicode_segmentation::UnicodeSegmentation;
 use widgets::TextInput;
 
 const LIST_SCROLL_MAX_HEIGHT: f32 = 190.;
+const SPLITTER_WIDTH: f32 = 8.;
+const SIDEBAR_MIN_WIDTH: f32 = 240.;
+const SIDEBAR_MAX_WIDTH: f32 = 640.;
+const BROWSER_LIST_MIN_WIDTH: f32 = 140.;
+const BROWSER_LIST_MAX_WIDTH: f32 = 520.;
+const EDITOR_MIN_HEIGHT: f32 = 60.;
+const EDITOR_MAX_HEIGHT: f32 = 800.;
+// Height of the editor while results are maximized: a single line of SQL.
+const EDITOR_COLLAPSED_HEIGHT: f32 = 36.;
+// Everything on the SQL Editor tab that is not the editor input or the result rows:
+// title bar, tabs, editor buttons, results header and meta line.
+const SQL_TAB_CHROME_HEIGHT: f32 = 440.;
+const RESULTS_MIN_HEIGHT: f32 = 160.;
 const RESULT_COL_MIN_WIDTH: f32 = 160.;
+const RESULT_COL_RESIZE_MIN_WIDTH: f32 = 48.;
+const RESULT_COL_MAX_WIDTH: f32 = 800.;
+const RESULT_COL_AUTO_MIN_WIDTH: f32 = 80.;
+const RESULT_COL_AUTO_MAX_WIDTH: f32 = 400.;
+const RESULT_AUTOSIZE_SAMPLE_ROWS: usize = 50;
+const RESULTS_WINDOW_CHROME_HEIGHT: f32 = 120.;
+const TIMING_STATEMENT_CHARS: usize = 80;
+const RESULT_CHAR_WIDTH: f32 = 8.5;
+const RESULT_CELL_PADDING: f32 = 16.;
+const RESULT_RESIZE_HANDLE_WIDTH: f32 = 6.;
 const RESULT_NUMBER_WIDTH: f32 = 64.;
+const COMPACT_GRID_SCALE: f32 = 0.75;
+const WRAPPED_CELL_MAX_LINES: f32 = 4.;
+const JSON_PRETTY_MAX_BYTES: usize = 256 * 1024;
+const COMPARE_PANEL_WIDTH: f32 = 420.;
+const SNIPPETS_PANEL_WIDTH: f32 = 220.;
 const APP_FONT_FAMILY: &str = "Zed Mono";
 const CONNECTING_TICK_FRAMES: u8 = 18;
-const COLOR_CANVAS: u32 = 0x040715;
-const COLOR_PANEL: u32 = 0x0a0f1d;
-const COLOR_PANEL_MUTED: u32 = 0x11182a;
-const COLOR_PANEL_HIGHLIGHT: u32 = 0x1c2342;
-const COLOR_BORDER: u32 = 0x1f2a44;
-const COLOR_TEXT_MUTED: u32 = 0x94a3c4;
-const COLOR_ACCENT: u32 = 0x8b5cf6;
-const COLOR_ACCENT_SOFT: u32 = 0x7c3aed;
-const COLOR_SUCCESS: u32 = 0x10b981;
-const COLOR_DANGER: u32 = 0xf43f5e;
-const COLOR_DANGER_SOFT: u32 = 0xfda4af;
-const COLOR_DANGER_SURFACE: u32 = 0x3a1826;
+const QUERY_TIMER_INTERVAL: Duration = Duration::from_millis(250);
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const IDLE_WARNING: Duration = Duration::from_secs(60);
+const IDLE_DISCONNECT_PRESETS: [u64; 5] = [0, 5, 15, 30, 60];
+const ACTIVITY_LOG_LIMIT: usize = 500;
+const SESSIONS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const WINDOW_STATE_SAVE_DELAY: Duration = Duration::from_millis(500);
+// The top strip of a restored window that has to land on a display so it can
+// still be dragged: its full height and at least this much of its width.
+const TITLE_BAR_HEIGHT: f32 = 28.;
+const MIN_VISIBLE_TITLE_BAR: f32 = 120.;
+const SESSIONS_LIST_MAX_HEIGHT: f32 = 520.;
+const SESSION_QUERY_CHARS: usize = 200;
+const NOTIFICATION_LOG_LIMIT: usize = 500;
+const NOTIFICATION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const NOTIFICATIONS_LIST_MAX_HEIGHT: f32 = 320.;
+const SCHEMA_DIFF_LIST_MAX_HEIGHT: f32 = 520.;
+const PALETTE_WIDTH: f32 = 520.;
+const PALETTE_LIST_MAX_HEIGHT: f32 = 360.;
+const CSV_INPUT_HEIGHT: f32 = 160.;
+const CSV_MAX_PROBLEMS: usize = 5;
 
 trait ScrollOverflowExt {
     fn overflow_x_scroll(self) -> Self;
@@ -98,25 +174,65 @@ fn main() {
 fn run() -> Result<()> {
     init_tracing();
     let config_dir = resolve_config_dir()?;
-    let profile_store = ProfileStore::new(&config_dir);
+    let stores = AppStores {
+        profiles: ProfileStore::new(&config_dir),
+        history: HistoryStore::new(&config_dir),
+        snippets: SnippetStore::new(&config_dir),
+        settings: SettingsStore::new(&config_dir),
+        session: SessionStore::new(&config_dir),
+    };
+    let window_store = WindowStateStore::new(&config_dir);
+    let window_state = window_store.load().unwrap_or_else(|err| {
+        tracing::warn!("Failed to load window state: {err:?}");
+        None
+    });
     let (event_tx, event_rx) = async_channel::unbounded();
 
     Application::new().run({
         let mut receiver = Some(event_rx);
-        let profile_store = profile_store.clone();
         let event_tx = event_tx.clone();
         move |cx: &mut App| {
             register_zed_fonts(cx);
-            let bounds = Bounds::centered(None, gpui::size(px(1180.), px(760.)), cx);
+            let window_bounds = window_state
+                .and_then(|state| restored_window_bounds(&state, cx))
+                .unwrap_or_else(|| {
+                    WindowBounds::Windowed(Bounds::centered(
+                        None,
+                        gpui::size(px(1180.), px(760.)),
+                        cx,
+                    ))
+                });
+            let window_store = window_store.clone();
             cx.open_window(
                 WindowOptions {
-                    window_bounds: Some(WindowBounds::Windowed(bounds)),
+                    window_bounds: Some(window_bounds),
                     titlebar: Some(Default::default()),
                     ..Default::default()
                 },
-                move |_, cx| {
+                move |window, cx| {
                     let rx = receiver.take().expect("event receiver already consumed");
-                    cx.new(|cx| DbMiruApp::new(cx, profile_store.clone(), event_tx.clone(), rx))
+                    let store = window_store.clone();
+                    window.on_window_should_close(cx, move |window, _cx| {
+                        save_window_state(&store, &current_window_state(window));
+                        true
+                    });
+                    cx.new(|cx| {
+                        let store = window_store.clone();
+                        cx.observe_window_bounds(
+                            window,
+                            move |this: &mut DbMiruApp, window, cx| {
+                                this.schedule_window_state_save(
+                                    current_window_state(window),
+                                    store.clone(),
+                                    cx,
+                                );
+                            },
+                        )
+                        .detach();
+                        let app = DbMiruApp::new(cx, stores.clone(), event_tx.clone(), rx);
+                        window.focus(&app.focus_handle);
+                        app
+                    })
                 },
             )
             .unwrap();
@@ -127,6 +243,67 @@ fn run() -> Result<()> {
     Ok(())
 }
 
+#[derive(Clone)]
+struct AppStores {
+    profiles: ProfileStore,
+    history: HistoryStore,
+    snippets: SnippetStore,
+    settings: SettingsStore,
+    session: SessionStore,
+}
+
+fn restored_window_bounds(state: &WindowState, cx: &App) -> Option<WindowBounds> {
+    if state.width < 400. || state.height < 300. {
+        return None;
+    }
+    let bounds = Bounds::new(
+        gpui::point(px(state.x), px(state.y)),
+        gpui::size(px(state.width), px(state.height)),
+    );
+    let displays: Vec<_> = cx
+        .displays()
+        .iter()
+        .map(|display| display.bounds())
+        .collect();
+    if !title_bar_visible(bounds, &displays) {
+        return None;
+    }
+    Some(if state.maximized {
+        WindowBounds::Maximized(bounds)
+    } else {
+        WindowBounds::Windowed(bounds)
+    })
+}
+
+fn title_bar_visible(bounds: Bounds<Pixels>, displays: &[Bounds<Pixels>]) -> bool {
+    let title_bar = Bounds::new(
+        bounds.origin,
+        gpui::size(bounds.size.width, px(TITLE_BAR_HEIGHT)),
+    );
+    displays.iter().any(|display| {
+        let shown = display.intersect(&title_bar);
+        shown.size.width >= px(MIN_VISIBLE_TITLE_BAR) && shown.size.height >= px(TITLE_BAR_HEIGHT)
+    })
+}
+
+fn current_window_state(window: &Window) -> WindowState {
+    let window_bounds = window.window_bounds();
+    let bounds = window_bounds.get_bounds();
+    WindowState {
+        x: f32::from(bounds.origin.x),
+        y: f32::from(bounds.origin.y),
+        width: f32::from(bounds.size.width),
+        height: f32::from(bounds.size.height),
+        maximized: !matches!(window_bounds, WindowBounds::Windowed(_)),
+    }
+}
+
+fn save_window_state(store: &WindowStateStore, state: &WindowState) {
+    if let Err(err) = store.save(state) {
+        tracing::warn!("Failed to save window state: {err:?}");
+    }
+}
+
 fn register_zed_fonts(cx: &mut App) {
     let fonts: Vec<Cow<'static, [u8]>> = vec![
         Cow::Borrowed(include_bytes!("../../assets/fonts/zed-mono-regular.ttf")),
@@ -160,26 +337,88 @@ fn resolve_config_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
-actions!(app_actions, [RunQuery]);
+actions!(
+    app_actions,
+    [
+        RunQuery,
+        RunSelection,
+        SelectPrevious,
+        SelectNext,
+        ConfirmSelection,
+        FocusNextList,
+        ShowSchemaBrowser,
+        ShowSqlEditor,
+        ShowSessions,
+        ShowSchemaDiff,
+        FocusSqlEditor,
+        FormatSql,
+        ToggleCommandPalette,
+        CloseCommandPalette
+    ]
+);
 
 struct DbMiruApp {
     profile_store: ProfileStore,
+    history_store: HistoryStore,
+    secret_store: SecretStore,
     profiles: Vec<ConnectionProfile>,
+    history: Vec<HistoryEntry>,
+    history_open: bool,
+    snippet_store: SnippetStore,
+    snippets: Vec<Snippet>,
+    settings_store: SettingsStore,
+    settings: Settings,
+    session_store: SessionStore,
+    session: SessionState,
+    activity_log: VecDeque<ActivityEntry>,
+    activity_log_open: bool,
+    toast: Option<Toast>,
+    snippet_name_input: gpui::Entity<TextInput>,
+    bind_params: Vec<BindParam>,
+    snippet_naming: bool,
+    snippet_notice: Option<String>,
     selected_profile: Option<ProfileId>,
     profile_form: ProfileForm,
     profile_form_mode: ProfileFormMode,
     profile_notice: Option<String>,
     password_input: gpui::Entity<TextInput>,
     sql_input: gpui::Entity<TextInput>,
+    row_limit_input: gpui::Entity<TextInput>,
+    results_window: Option<WindowHandle<ResultsWindow>>,
+    results_maximized: bool,
+    statement_timeout_input: gpui::Entity<TextInput>,
+    result_search_input: gpui::Entity<TextInput>,
+    // One per result column, created as wider results need them.
+    column_filter_inputs: Vec<gpui::Entity<TextInput>>,
+    schema_filter_input: gpui::Entity<TextInput>,
+    table_filter_input: gpui::Entity<TextInput>,
     connection: ConnectionState,
     query_state: QueryState,
+    compare_run: CompareRunState,
+    schema_diff: SchemaDiffState,
+    schema_diff_inputs: [gpui::Entity<TextInput>; 2],
     schema_browser: SchemaBrowserState,
+    server_sessions: ServerSessionsState,
+    notifications: NotificationsState,
+    listen_channel_input: gpui::Entity<TextInput>,
+    csv_import: CsvImportState,
+    csv_input: gpui::Entity<TextInput>,
+    command_palette: CommandPaletteState,
+    palette_input: gpui::Entity<TextInput>,
+    schema_browser_focus: FocusHandle,
+    // Focused when nothing more specific is, so the app-wide shortcuts dispatch.
+    focus_handle: FocusHandle,
     active_tab: MainTab,
     event_tx: Sender<DbEvent>,
     event_rx: Receiver<DbEvent>,
     connecting_indicator: u8,
     connecting_indicator_frame: u8,
     connecting_indicator_active: bool,
+    panel_resize: Option<PanelResize>,
+    last_interaction: Instant,
+    idle_watch_active: bool,
+    idle_warned: bool,
+    window_bounds_changed_at: Option<Instant>,
 }
 
 impl EventEmitter<RunQuery> for DbMiruApp {}
@@ -187,10 +426,17 @@ impl EventEmitter<RunQuery> for DbMiruApp {}
 impl DbMiruApp {
     fn new(
         cx: &mut Context<Self>,
-        profile_store: ProfileStore,
+        stores: AppStores,
         event_tx: Sender<DbEvent>,
         event_rx: Receiver<DbEvent>,
     ) -> Self {
+        let AppStores {
+            profiles: profile_store,
+            history: history_store,
+            snippets: snippet_store,
+            settings: settings_store,
+            session: session_store,
+        } = stores;
         let profiles = match profile_store.load() {
             Ok(list) => list,
             Err(err) => {
@@ -198,39 +444,195 @@ impl DbMiruApp {
                 Vec::new()
             }
         };
+        let history = match history_store.load() {
+            Ok(entries) => entries,
+            Err(err) => {
+                tracing::error!("Failed to load query history: {err:?}");
+                Vec::new()
+            }
+        };
+        let snippets = match snippet_store.load() {
+            Ok(snippets) => snippets,
+            Err(err) => {
+                tracing::error!("Failed to load snippets: {err:?}");
+                Vec::new()
+            }
+        };
+        let settings = match settings_store.load() {
+            Ok(settings) => settings,
+            Err(err) => {
+                tracing::error!("Failed to load settings: {err:?}");
+                Settings::default()
+            }
+        };
+        let session = match session_store.load() {
+            Ok(session) => session,
+            Err(err) => {
+                tracing::error!("Failed to load session: {err:?}");
+                SessionState::default()
+            }
+        };
+        cx.set_global(Theme::for_mode(settings.theme));
+        cx.on_app_quit(|this, cx| {
+            this.save_session(cx);
+            async {}
+        })
+        .detach();
+        cx.on_release(|this, cx| this.save_session(cx)).detach();
 
         let profile_form = ProfileForm::new(cx);
         let password_input = cx.new(|cx| TextInput::new(cx, "", "Password").with_obscured(true));
-        let sql_input = cx.new(|cx| TextInput::new(cx, "", "SELECT 1;"));
+        let sql_input = cx.new(|cx| {
+            TextInput::new(cx, session.sql_for(session.editor_profile), "SELECT 1;")
+                .with_multiline(true)
+                .with_auto_pair(settings.auto_close_brackets)
+        });
+        let snippet_name_input = cx.new(|cx| TextInput::new(cx, "", "Snippet name"));
+        let row_limit_input =
+            cx.new(|cx| TextInput::new(cx, &DEFAULT_ROW_LIMIT.to_string(), "Row limit"));
+        let statement_timeout_input = cx.new(|cx| TextInput::new(cx, "0", "Seconds"));
+        let schema_filter_input = cx.new(|cx| TextInput::new(cx, "", "Filter schemas"));
+        let table_filter_input = cx.new(|cx| TextInput::new(cx, "", "Filter tables"));
+        let result_search_input = cx.new(|cx| TextInput::new(cx, "", "Find in results"));
+        let csv_input = cx.new(|cx| {
+            TextInput::new(cx, "", "Paste CSV or tab-separated rows here").with_multiline(true)
+        });
+        let palette_input = cx.new(|cx| TextInput::new(cx, "", "Type a command"));
+        let listen_channel_input = cx.new(|cx| TextInput::new(cx, "", "Channel"));
+        let schema_diff_inputs = [
+            cx.new(|cx| TextInput::new(cx, "", "Schema (default: last opened)")),
+            cx.new(|cx| TextInput::new(cx, "", "Schema (default: last opened)")),
+        ];
 
         cx.bind_keys([
             KeyBinding::new("cmd-enter", RunQuery, Some("SqlEditor")),
             KeyBinding::new("ctrl-enter", RunQuery, Some("SqlEditor")),
+            KeyBinding::new("cmd-shift-enter", RunSelection, Some("SqlEditor")),
+            KeyBinding::new("ctrl-shift-enter", RunSelection, Some("SqlEditor")),
+            KeyBinding::new("cmd-shift-f", FormatSql, Some("SqlEditor")),
+            KeyBinding::new("ctrl-shift-f", FormatSql, Some("SqlEditor")),
+            KeyBinding::new("up", SelectPrevious, Some("SchemaBrowser")),
+            KeyBinding::new("down", SelectNext, Some("SchemaBrowser")),
+            KeyBinding::new("enter", ConfirmSelection, Some("SchemaBrowser")),
+            KeyBinding::new("tab", FocusNextList, Some("SchemaBrowser")),
+            KeyBinding::new("cmd-1", ShowSchemaBrowser, Some("DbMiru")),
+            KeyBinding::new("ctrl-1", ShowSchemaBrowser, Some("DbMiru")),
+            KeyBinding::new("cmd-2", ShowSqlEditor, Some("DbMiru")),
+            KeyBinding::new("ctrl-2", ShowSqlEditor, Some("DbMiru")),
+            KeyBinding::new("cmd-3", ShowSessions, Some("DbMiru")),
+            KeyBinding::new("ctrl-3", ShowSessions, Some("DbMiru")),
+            KeyBinding::new("cmd-4", ShowSchemaDiff, Some("DbMiru")),
+            KeyBinding::new("ctrl-4", ShowSchemaDiff, Some("DbMiru")),
+            KeyBinding::new("cmd-k", FocusSqlEditor, Some("DbMiru")),
+            KeyBinding::new("ctrl-k", FocusSqlEditor, Some("DbMiru")),
+            KeyBinding::new("cmd-shift-p", ToggleCommandPalette, Some("DbMiru")),
+            KeyBinding::new("ctrl-shift-p", ToggleCommandPalette, Some("DbMiru")),
+            KeyBinding::new("escape", CloseCommandPalette, Some("CommandPalette")),
+            // The single-line palette query propagates up/down/enter, so these take over.
+            KeyBinding::new("up", SelectPrevious, Some("CommandPalette > TextInput")),
+            KeyBinding::new("down", SelectNext, Some("CommandPalette > TextInput")),
+            KeyBinding::new(
+                "enter",
+                ConfirmSelection,
+                Some("CommandPalette > TextInput"),
+            ),
         ]);
 
         let mut app = Self {
             profile_store,
+            history_store,
+            secret_store: SecretStore::new(),
             selected_profile: profiles.first().map(|p| p.id),
             profiles,
+            history,
+            history_open: false,
+            snippet_store,
+            snippets,
+            settings_store,
+            settings,
+            session_store,
+            active_tab: match session.active_tab {
+                SessionTab::SchemaBrowser => MainTab::SchemaBrowser,
+                SessionTab::SqlEditor => MainTab::SqlEditor,
+            },
+            session,
+            activity_log: VecDeque::new(),
+            activity_log_open: false,
+            toast: None,
+            snippet_name_input,
+            bind_params: Vec::new(),
+            snippet_naming: false,
+            snippet_notice: None,
             profile_form,
             profile_form_mode: ProfileFormMode::Hidden,
             profile_notice: None,
             password_input,
             sql_input,
+            row_limit_input,
+            results_window: None,
+            results_maximized: false,
+            statement_timeout_input,
+            result_search_input,
+            column_filter_inputs: Vec::new(),
+            schema_filter_input,
+            table_filter_input,
             connection: ConnectionState::default(),
             query_state: QueryState::default(),
+            compare_run: CompareRunState::default(),
+            schema_diff: SchemaDiffState::default(),
+            schema_diff_inputs,
             schema_browser: SchemaBrowserState::default(),
-            active_tab: MainTab::default(),
+            server_sessions: ServerSessionsState::default(),
+            notifications: NotificationsState::default(),
+            listen_channel_input,
+            csv_import: CsvImportState::default(),
+            csv_input,
+            command_palette: CommandPaletteState::default(),
+            palette_input,
+            schema_browser_focus: cx.focus_handle(),
+            focus_handle: cx.focus_handle(),
             event_tx,
             event_rx,
             connecting_indicator: 0,
             connecting_indicator_frame: 0,
             connecting_indicator_active: false,
+            panel_resize: None,
+            last_interaction: Instant::now(),
+            idle_watch_active: false,
+            idle_warned: false,
+            window_bounds_changed_at: None,
         };
         app.sync_form_with_selection(cx);
         app
     }
 
+    // Bounds change on every frame of a drag or resize; only the bounds the window
+    // settles at are written, once they have held for `WINDOW_STATE_SAVE_DELAY`.
+    fn schedule_window_state_save(
+        &mut self,
+        state: WindowState,
+        store: WindowStateStore,
+        cx: &mut Context<Self>,
+    ) {
+        self.window_bounds_changed_at = Some(Instant::now());
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(WINDOW_STATE_SAVE_DELAY)
+                .await;
+            this.update(cx, |this, _cx| {
+                if this
+                    .window_bounds_changed_at
+                    .is_some_and(|at| at.elapsed() >= WINDOW_STATE_SAVE_DELAY)
+                {
+                    this.window_bounds_changed_at = None;
+                    save_window_state(&store, &state);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     fn ensure_connecting_indicator(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.connecting_indicator_active {
             return;
@@ -264,69 +666,270 @@ impl DbMiruApp {
         self.connecting_indicator_frame = 0;
     }
 
+    fn ensure_query_timer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_state.timer_active {
+            return;
+        }
+        self.query_state.timer_active = true;
+        self.schedule_query_timer(window, cx);
+    }
+
+    fn schedule_query_timer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.on_next_frame(window, |this, window, cx| {
+            let Some(started_at) = this
+                .query_state
+                .started_at
+                .filter(|_| this.query_state.status == QueryStatus::Running)
+            else {
+                this.query_state.timer_active = false;
+                return;
+            };
+            let elapsed = started_at.elapsed();
+            if elapsed >= this.query_state.elapsed + QUERY_TIMER_INTERVAL {
+                this.query_state.elapsed = elapsed;
+                cx.notify();
+            }
+            this.schedule_query_timer(window, cx);
+        });
+    }
+
     fn poll_events(&mut self, cx: &mut Context<Self>) {
+        let mut handled = false;
         while let Ok(event) = self.event_rx.try_recv() {
             self.handle_db_event(event, cx);
+            handled = true;
+        }
+        // Events are drained during render, so tell observers such as the
+        // results window that the state changed.
+        if handled && self.results_window.is_some() {
+            cx.notify();
         }
     }
 
     fn handle_db_event(&mut self, event: DbEvent, cx: &mut Context<Self>) {
+        if let Some((level, message)) = self.activity_message(&event) {
+            // Failures always pop up; of the successes only those that have no
+            // inline result to look at.
+            if level != ActivityLevel::Info
+                || matches!(
+                    event,
+                    DbEvent::Connected(_)
+                        | DbEvent::StatementCompleted { .. }
+                        | DbEvent::TransactionEnded { .. }
+                        | DbEvent::BackendTerminated { .. }
+                )
+            {
+                self.show_toast(level, message.clone(), cx);
+            }
+            self.log_activity(level, message);
+        }
         match event {
             DbEvent::Connected(handle) => {
                 let profile_name = self
-                    .selected_profile
+                    .connection
+                    .profile_id
                     .and_then(|id| self.profiles.iter().find(|p| p.id == id))
                     .map(|p| p.name.clone())
                     .unwrap_or_else(|| "Unknown profile".into());
                 self.connection.status = ConnectionStatus::Connected(profile_name);
                 self.connection.session = Some(handle);
+                self.connection.connect_retry = None;
+                self.connection.server_info = None;
+                self.query_state.in_transaction = false;
                 self.connection.last_error = None;
                 self.stop_connecting_indicator();
                 self.schema_browser.start_schema_load();
+                self.server_sessions = ServerSessionsState::default();
+                self.notifications = NotificationsState::default();
+                self.csv_import = CsvImportState::default();
+                self.schema_filter_input
+                    .update(cx, |input, _| input.clear());
+                self.table_filter_input.update(cx, |input, _| input.clear());
                 self.active_tab = MainTab::SchemaBrowser;
                 if let Some(session) = self.connection.session.as_ref() {
+                    session.load_server_info();
                     session.load_schemas();
                 }
+                self.last_interaction = Instant::now();
+                self.watch_idle(cx);
+            }
+            DbEvent::ServerInfoLoaded {
+                version,
+                database,
+                user,
+            } => {
+                self.connection.server_info = Some(ServerInfo {
+                    version,
+                    database,
+                    user,
+                });
             }
             DbEvent::ConnectionFailed(error) => {
                 self.connection.status = ConnectionStatus::Disconnected;
                 self.connection.session = None;
+                self.connection.connect_retry = None;
+                self.connection.server_info = None;
                 tracing::warn!("Connection failed: {}", error.detail);
                 self.connection.last_error = Some(error.user_message);
                 self.stop_connecting_indicator();
                 self.schema_browser.reset();
+                self.server_sessions = ServerSessionsState::default();
+                self.notifications = NotificationsState::default();
+                self.csv_import = CsvImportState::default();
                 self.active_tab = MainTab::SchemaBrowser;
             }
+            DbEvent::ConnectRetrying {
+                attempt, retries, ..
+            } => {
+                self.connection.connect_retry = Some((attempt, retries));
+            }
+            DbEvent::ConnectionTested(outcome) => {
+                self.profile_notice = Some(match outcome {
+                    Ok(version) if version.is_empty() => "Connection succeeded.".into(),
+                    Ok(version) => format!("Connection succeeded: {version}"),
+                    Err(error) => {
+                        tracing::warn!("Connection test failed: {}", error.detail);
+                        format!(
+                            "Connection failed: {} ({})",
+                            error.user_message, error.detail
+                        )
+                    }
+                });
+            }
             DbEvent::ConnectionClosed(reason) => {
+                let dropped = self.connection.session.is_some();
                 self.connection.status = ConnectionStatus::Disconnected;
                 self.connection.session = None;
+                self.connection.server_info = None;
                 if let Some(reason) = reason {
                     self.connection.last_error = Some(reason);
                 }
+                if std::mem::take(&mut self.query_state.in_transaction) {
+                    let warning = "The connection closed during an open transaction; uncommitted changes were rolled back.";
+                    self.query_state.last_error = Some(warning.into());
+                    self.connection.last_error = Some(match self.connection.last_error.take() {
+                        Some(reason) => format!("{reason} {warning}"),
+                        None => warning.into(),
+                    });
+                }
                 self.stop_connecting_indicator();
                 self.schema_browser.reset();
+                self.server_sessions = ServerSessionsState::default();
+                self.notifications = NotificationsState::default();
+                self.csv_import = CsvImportState::default();
                 self.active_tab = MainTab::SchemaBrowser;
+                if dropped {
+                    self.connection.dropped = true;
+                    let auto_reconnect = self
+                        .connection
+                        .profile_id
+                        .and_then(|id| self.profiles.iter().find(|p| p.id == id))
+                        .is_some_and(|profile| profile.auto_reconnect);
+                    if auto_reconnect {
+                        tracing::info!("Connection dropped, reconnecting automatically");
+                        self.reconnect(cx);
+                    }
+                }
             }
             DbEvent::QueryFinished(result) => {
                 self.query_state.status = QueryStatus::Idle;
+                self.query_state.cancel_requested = false;
                 self.query_state.last_error = None;
+                self.query_state.last_completion = None;
+                self.query_state.timings = result.timings.clone();
                 self.query_state.last_result = Some(QueryResultView::from(result));
+                self.query_state.selected_row = None;
+                self.query_state.pretty_json.clear();
+                self.clear_column_filters(cx);
+                self.record_history();
+            }
+            DbEvent::StatementCompleted {
+                affected,
+                tag,
+                timings,
+            } => {
+                self.query_state.status = QueryStatus::Idle;
+                self.query_state.cancel_requested = false;
+                self.query_state.last_error = None;
+                self.query_state.last_result = None;
+                self.query_state.selected_row = None;
+                let completion = completion_message(affected, &tag);
+                self.query_state.last_completion = Some(match timing_breakdown(&timings) {
+                    Some(breakdown) => format!("{completion} ({breakdown})"),
+                    None => completion,
+                });
+                self.query_state.timings = timings;
+                self.record_history();
+            }
+            DbEvent::QueryFailed(error) => {
+                self.query_state.status = QueryStatus::Idle;
+                self.query_state.cancel_requested = false;
+                self.query_state.last_result = None;
+                self.query_state.timings.clear();
+                self.query_state.selected_row = None;
+                self.query_state.history_sql = None;
+                let origin = self.query_state.error_origin.take();
+                if let (Some(position), Some((text, base))) = (error.position, origin) {
+                    self.sql_input.update(cx, |input, cx| {
+                        if input.text() == text {
+                            input.mark_error_at(base + position);
+                            cx.notify();
+                        }
+                    });
+                }
+                self.query_state.last_error = Some(error);
+            }
+            DbEvent::Notice(notice) => {
+                self.query_state.notices.push(notice);
             }
-            DbEvent::QueryFailed(message) => {
+            DbEvent::QueryCancelled => {
                 self.query_state.status = QueryStatus::Idle;
+                self.query_state.cancel_requested = false;
                 self.query_state.last_result = None;
-                self.query_state.last_error = Some(message);
+                self.query_state.selected_row = None;
+                self.query_state.last_error = Some("Query cancelled.".into());
+            }
+            DbEvent::StatementTimeoutSet(timeout) => {
+                self.connection.statement_timeout = timeout;
+            }
+            DbEvent::TransactionStarted => {
+                self.query_state.in_transaction = true;
+                self.query_state.last_error = None;
+                self.query_state.last_completion = Some("Transaction started.".into());
+                self.query_state.timings.clear();
+            }
+            DbEvent::TransactionEnded { committed } => {
+                self.query_state.in_transaction = false;
+                self.query_state.last_error = None;
+                self.query_state.timings.clear();
+                self.query_state.last_completion = Some(if committed {
+                    "Transaction committed.".into()
+                } else {
+                    "Transaction rolled back.".into()
+                });
             }
             DbEvent::SchemasLoaded(schemas) => {
                 self.schema_browser.schemas_loading = false;
                 self.schema_browser.schemas = schemas;
                 self.schema_browser.last_error = None;
-                if self.schema_browser.schemas.is_empty() {
-                    self.schema_browser.selected_schema = None;
-                } else if self.schema_browser.selected_schema.is_none()
-                    && let Some(first) = self.schema_browser.schemas.first().cloned()
+                if self
+                    .schema_browser
+                    .selected_schema
+                    .as_ref()
+                    .is_some_and(|schema| !self.schema_browser.schemas.contains(schema))
                 {
-                    self.select_schema(first, cx);
+                    self.schema_browser.clear_schema_selection();
+                }
+                if self.schema_browser.selected_schema.is_none() {
+                    let remembered = self
+                        .connected_profile()
+                        .and_then(|profile| profile.last_schema.clone())
+                        .filter(|schema| self.schema_browser.schemas.contains(schema));
+                    if let Some(schema) =
+                        remembered.or_else(|| self.schema_browser.schemas.first().cloned())
+                    {
+                        self.select_schema(schema, cx);
+                    }
                 }
             }
             DbEvent::TablesLoaded { schema, tables } => {
@@ -334,14 +937,48 @@ impl DbMiruApp {
                     self.schema_browser.tables_loading = false;
                     self.schema_browser.tables = tables;
                     self.schema_browser.last_error = None;
-                    if self.schema_browser.tables.is_empty() {
-                        self.schema_browser.selected_table = None;
-                        self.schema_browser.columns.clear();
-                        self.schema_browser.preview = None;
-                    } else if self.schema_browser.selected_table.is_none()
-                        && let Some(first) = self.schema_browser.tables.first().cloned()
+                    if self.settings.show_table_sizes
+                        && let Some(session) = self.connection.session.as_ref()
+                    {
+                        session.load_table_sizes(schema.clone());
+                    }
+                    if self
+                        .schema_browser
+                        .selected_table
+                        .as_ref()
+                        .is_some_and(|table| !self.schema_browser.has_table_or_view(table))
+                    {
+                        self.schema_browser.clear_table_selection();
+                    }
+                    if self.schema_browser.selected_table.is_none() {
+                        let remembered = self
+                            .connected_profile()
+                            .filter(|profile| profile.last_schema.as_deref() == Some(&schema))
+                            .and_then(|profile| profile.last_table.clone())
+                            .filter(|table| self.schema_browser.tables.contains(table));
+                        if let Some(table) =
+                            remembered.or_else(|| self.schema_browser.tables.first().cloned())
+                        {
+                            self.select_table(table, cx);
+                        }
+                    }
+                }
+            }
+            DbEvent::ViewsLoaded { schema, views } => {
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str()) {
+                    self.schema_browser.views_loading = false;
+                    self.schema_browser.views = views;
+                    if !self.schema_browser.tables_loading
+                        && self
+                            .schema_browser
+                            .selected_table
+                            .as_ref()
+                            .is_some_and(|table| !self.schema_browser.has_table_or_view(table))
                     {
-                        self.select_table(first, cx);
+                        self.schema_browser.clear_table_selection();
+                        if let Some(first) = self.schema_browser.tables.first().cloned() {
+                            self.select_table(first, cx);
+                        }
                     }
                 }
             }
@@ -358,6 +995,56 @@ impl DbMiruApp {
                     self.schema_browser.last_error = None;
                 }
             }
+            DbEvent::IndexesLoaded {
+                schema,
+                table,
+                indexes,
+            } => {
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str())
+                    && self.schema_browser.selected_table.as_deref() == Some(table.as_str())
+                {
+                    self.schema_browser.indexes = indexes;
+                }
+            }
+            DbEvent::ForeignKeysLoaded {
+                schema,
+                table,
+                foreign_keys,
+            } => {
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str())
+                    && self.schema_browser.selected_table.as_deref() == Some(table.as_str())
+                {
+                    self.schema_browser.foreign_keys = foreign_keys;
+                }
+            }
+            DbEvent::TableSizesLoaded { schema, sizes } => {
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str()) {
+                    self.schema_browser.table_sizes = sizes.into_iter().collect();
+                }
+            }
+            DbEvent::DdlReady { schema, table, ddl } => {
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str())
+                    && self.schema_browser.selected_table.as_deref() == Some(table.as_str())
+                {
+                    self.schema_browser.ddl_loading = false;
+                    self.schema_browser.ddl = Some(ddl);
+                    self.schema_browser.last_error = None;
+                }
+            }
+            DbEvent::ColumnStatsReady {
+                schema,
+                table,
+                column,
+                stats,
+            } => {
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str())
+                    && self.schema_browser.selected_table.as_deref() == Some(table.as_str())
+                {
+                    self.schema_browser.column_stats_loading = false;
+                    self.schema_browser.column_stats = Some((column, stats));
+                    self.schema_browser.last_error = None;
+                }
+            }
             DbEvent::TablePreviewReady {
                 schema,
                 table,
@@ -371,24 +1058,375 @@ impl DbMiruApp {
                     self.schema_browser.last_error = None;
                 }
             }
+            DbEvent::BulkInserted {
+                schema,
+                table,
+                rows,
+            } => {
+                if self.csv_import.is_for(&schema, &table) {
+                    self.csv_import.running = false;
+                    self.csv_import.error = None;
+                    self.csv_import.notice =
+                        Some(format!("Inserted {rows} rows into {schema}.{table}."));
+                    self.csv_input.update(cx, |input, _| input.clear());
+                }
+                if self.schema_browser.selected_schema.as_deref() == Some(schema.as_str())
+                    && self.schema_browser.selected_table.as_deref() == Some(table.as_str())
+                    && let Some(session) = self.connection.session.as_ref()
+                {
+                    self.schema_browser.preview_loading = true;
+                    session.preview_table(schema, table, db::PREVIEW_LIMIT);
+                }
+            }
+            DbEvent::BulkInsertFailed {
+                schema,
+                table,
+                message,
+            } => {
+                if self.csv_import.is_for(&schema, &table) {
+                    self.csv_import.running = false;
+                    self.csv_import.notice = None;
+                    self.csv_import.error = Some(message);
+                }
+            }
+            DbEvent::ActivityLoaded(sessions) => {
+                let server_sessions = &mut self.server_sessions;
+                server_sessions.loading = false;
+                server_sessions.loaded_at = Some(Instant::now());
+                if server_sessions
+                    .confirm_terminate
+                    .is_some_and(|pid| !sessions.iter().any(|session| session.pid == pid))
+                {
+                    server_sessions.confirm_terminate = None;
+                }
+                server_sessions.sessions = sessions;
+                self.schedule_sessions_refresh(cx);
+            }
+            DbEvent::BackendTerminated { .. } => {
+                self.server_sessions.confirm_terminate = None;
+                self.refresh_server_sessions(cx);
+            }
+            DbEvent::Listening { channel } => {
+                let notifications = &mut self.notifications;
+                notifications.error = None;
+                if !notifications.channels.contains(&channel) {
+                    notifications.channels.push(channel);
+                    if notifications.channels.len() == 1 {
+                        self.watch_notifications(cx);
+                    }
+                }
+            }
+            DbEvent::Unlistened { channel } => {
+                self.notifications
+                    .channels
+                    .retain(|listened| *listened != channel);
+            }
+            DbEvent::ListenFailed { channel, message } => {
+                self.notifications.error = Some(format!("{channel}: {message}"));
+            }
+            DbEvent::Notification { channel, payload } => {
+                let entries = &mut self.notifications.entries;
+                if entries.len() == NOTIFICATION_LOG_LIMIT {
+                    entries.pop_front();
+                }
+                entries.push_back(NotificationEntry {
+                    at: Local::now(),
+                    channel,
+                    payload,
+                });
+            }
             DbEvent::MetadataFailed(message) => {
                 self.schema_browser.last_error = Some(message);
                 self.schema_browser.stop_loading();
+                self.server_sessions.loading = false;
             }
         }
         cx.notify();
     }
 
+    // Summarizes the events worth keeping in the activity log; routine metadata
+    // loads are left out so they do not drown the connection and query history.
+    fn activity_message(&self, event: &DbEvent) -> Option<(ActivityLevel, String)> {
+        let profile_name = || {
+            self.connection
+                .profile_id
+                .and_then(|id| self.profiles.iter().find(|p| p.id == id))
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| "Unknown profile".into())
+        };
+        let elapsed = || {
+            self.query_state
+                .started_at
+                .map(|started_at| format!(" in {} ms", started_at.elapsed().as_millis()))
+                .unwrap_or_default()
+        };
+        Some(match event {
+            DbEvent::Connected(_) => (
+                ActivityLevel::Info,
+                format!("Connected to {}.", profile_name()),
+            ),
+            DbEvent::ServerInfoLoaded { version, .. } => {
+                (ActivityLevel::Info, format!("Server version: {version}"))
+            }
+            DbEvent::ConnectionFailed(error) => (
+                ActivityLevel::Error,
+                format!(
+                    "Connection to {} failed: {} ({})",
+                    profile_name(),
+                    error.user_message,
+                    error.detail
+                ),
+            ),
+            DbEvent::ConnectRetrying {
+                attempt,
+                retries,
+                error,
+            } => (
+                ActivityLevel::Warning,
+                format!(
+                    "Connect attempt failed: {} ({}); retry {attempt}/{retries}.",
+                    error.user_message, error.detail
+                ),
+            ),
+            DbEvent::ConnectionTested(Ok(_)) => {
+                (ActivityLevel::Info, "Connection test succeeded.".into())
+            }
+            DbEvent::ConnectionTested(Err(error)) => (
+                ActivityLevel::Warning,
+                format!(
+                    "Connection test failed: {} ({})",
+                    error.user_message, error.detail
+                ),
+            ),
+            DbEvent::ConnectionClosed(reason) => (
+                ActivityLevel::Warning,
+                match reason {
+                    Some(reason) => format!("Connection closed: {reason}"),
+                    None => "Connection closed.".into(),
+                },
+            ),
+            DbEvent::QueryFinished(result) => {
+                let rows = if result.truncated {
+                    format!("{}+ rows", result.rows.len())
+                } else {
+                    format!("{} rows", result.row_count)
+                };
+                (
+                    ActivityLevel::Info,
+                    format!(
+                        "Query returned {rows} in {} ms.",
+                        result.duration.as_millis()
+                    ),
+                )
+            }
+            DbEvent::StatementCompleted { affected, tag, .. } => (
+                ActivityLevel::Info,
+                format!("{}{}", completion_message(*affected, tag), elapsed()),
+            ),
+            DbEvent::QueryFailed(error) => (
+                ActivityLevel::Error,
+                format!("Query failed{}: {}", elapsed(), error.message),
+            ),
+            DbEvent::Notice(notice) => (ActivityLevel::Info, format!("Notice: {notice}")),
+            DbEvent::QueryCancelled => (
+                ActivityLevel::Warning,
+                format!("Query cancelled{}.", elapsed()),
+            ),
+            DbEvent::StatementTimeoutSet(timeout) => (
+                ActivityLevel::Info,
+                match timeout {
+                    Some(timeout) => {
+                        format!("Statement timeout set to {} s.", timeout.as_secs())
+                    }
+                    None => "Statement timeout disabled.".into(),
+                },
+            ),
+            DbEvent::TransactionStarted => (ActivityLevel::Info, "Transaction started.".into()),
+            DbEvent::TransactionEnded { committed: true } => {
+                (ActivityLevel::Info, "Transaction committed.".into())
+            }
+            DbEvent::TransactionEnded { committed: false } => {
+                (ActivityLevel::Info, "Transaction rolled back.".into())
+            }
+            DbEvent::BulkInserted {
+                schema,
+                table,
+                rows,
+            } => (
+                ActivityLevel::Info,
+                format!("Inserted {rows} rows into {schema}.{table}."),
+            ),
+            DbEvent::BulkInsertFailed {
+                schema,
+                table,
+                message,
+            } => (
+                ActivityLevel::Error,
+                format!("Bulk insert into {schema}.{table} failed: {message}"),
+            ),
+            DbEvent::BackendTerminated {
+                pid,
+                terminated: true,
+            } => (ActivityLevel::Info, format!("Terminated session {pid}.")),
+            DbEvent::BackendTerminated {
+                pid,
+                terminated: false,
+            } => (
+                ActivityLevel::Warning,
+                format!("Session {pid} was not terminated; it may have already exited."),
+            ),
+            DbEvent::Listening { channel } => (
+                ActivityLevel::Info,
+                format!("Listening on channel {channel}."),
+            ),
+            DbEvent::Unlistened { channel } => (
+                ActivityLevel::Info,
+                format!("Stopped listening on channel {channel}."),
+            ),
+            DbEvent::ListenFailed { channel, message } => (
+                ActivityLevel::Error,
+                format!("LISTEN/UNLISTEN on channel {channel} failed: {message}"),
+            ),
+            DbEvent::MetadataFailed(message) => (
+                ActivityLevel::Error,
+                format!("Metadata request failed: {message}"),
+            ),
+            _ => return None,
+        })
+    }
+
+    fn show_toast(&mut self, level: ActivityLevel, message: String, cx: &mut Context<Self>) {
+        self.toast = Some(Toast {
+            level,
+            message,
+            shown_at: Instant::now(),
+        });
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TOAST_DURATION).await;
+            this.update(cx, |this, cx| {
+                // A newer toast restarts the clock; only its own timer clears it.
+                if this
+                    .toast
+                    .as_ref()
+                    .is_some_and(|toast| toast.shown_at.elapsed() >= TOAST_DURATION)
+                {
+                    this.toast = None;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn render_toast(&self, theme: &Theme, cx: &mut Context<Self>) -> Option<gpui::Div> {
+        let toast = self.toast.as_ref()?;
+        let color = match toast.level {
+            ActivityLevel::Info => theme.success,
+            ActivityLevel::Warning => theme.warning,
+            ActivityLevel::Error => theme.danger,
+        };
+        Some(
+            div()
+                .absolute()
+                .bottom_6()
+                .right_6()
+                .max_w(px(480.))
+                .flex()
+                .items_start()
+                .gap_2()
+                .px_4()
+                .py_3()
+                .rounded_md()
+                .bg(theme.panel)
+                .border_1()
+                .border_color(color)
+                .shadow_lg()
+                .text_sm()
+                .cursor_pointer()
+                .child(
+                    div()
+                        .w(px(8.))
+                        .h(px(8.))
+                        .mt_1p5()
+                        .flex_shrink_0()
+                        .rounded_full()
+                        .bg(color),
+                )
+                .child(
+                    div()
+                        .text_color(theme.text_bright)
+                        .child(toast.message.clone()),
+                )
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.toast = None;
+                        cx.notify();
+                    }),
+                ),
+        )
+    }
+
+    fn log_activity(&mut self, level: ActivityLevel, message: String) {
+        if self.activity_log.len() == ACTIVITY_LOG_LIMIT {
+            self.activity_log.pop_front();
+        }
+        self.activity_log.push_back(ActivityEntry {
+            at: Local::now(),
+            level,
+            message,
+        });
+    }
+
+    fn copy_activity_log(&self, cx: &mut Context<Self>) {
+        let text = self
+            .activity_log
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {} {}",
+                    entry.at.format("%Y-%m-%d %H:%M:%S"),
+                    entry.level.label(),
+                    entry.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+    }
+
     fn sync_form_with_selection(&mut self, cx: &mut Context<Self>) {
         if let Some(profile_id) = self.selected_profile
             && let Some(profile) = self.profiles.iter().find(|p| p.id == profile_id)
         {
+            let ssh = profile.ssh_tunnel.as_ref();
             let values = ProfileFormValues {
                 name: profile.name.clone(),
                 host: profile.host.clone(),
                 port: profile.port.to_string(),
                 database: profile.database.clone(),
                 username: profile.username.clone(),
+                password: String::new(),
+                remember_password: profile.remember_password,
+                auto_reconnect: profile.auto_reconnect,
+                read_only: profile.read_only,
+                confirm_destructive: profile.confirm_destructive,
+                kind: profile.kind,
+                ssl_mode: profile.ssl_mode,
+                color: profile.color,
+                connect_timeout: profile.connect_timeout_secs.to_string(),
+                row_limit: profile.default_row_limit.to_string(),
+                keepalive: profile.keepalive_secs.to_string(),
+                statement_timeout: profile.statement_timeout_secs.to_string(),
+                connect_retries: profile.connect_retries.to_string(),
+                retry_backoff: profile.retry_backoff_secs.to_string(),
+                group: profile.group.clone().unwrap_or_default(),
+                ssh_host: ssh.map(|ssh| ssh.host.clone()).unwrap_or_default(),
+                ssh_port: ssh.map_or(DEFAULT_SSH_PORT, |ssh| ssh.port).to_string(),
+                ssh_user: ssh.map(|ssh| ssh.username.clone()).unwrap_or_default(),
+                ssh_key: ssh.and_then(|ssh| ssh.key_path.clone()).unwrap_or_default(),
             };
             self.profile_form.set_values(&values, cx);
             return;
@@ -412,6 +1450,48 @@ impl DbMiruApp {
         }
     }
 
+    fn duplicate_selected_profile(&mut self, cx: &mut Context<Self>) {
+        let Some(index) = self
+            .selected_profile
+            .and_then(|profile_id| self.profile_index(profile_id))
+        else {
+            return;
+        };
+        let source = &self.profiles[index];
+        let mut copy = ConnectionProfile::new(
+            format!("{} (copy)", source.name),
+            source.host.clone(),
+            source.port,
+            source.database.clone(),
+            source.username.clone(),
+            false,
+        );
+        copy.kind = source.kind;
+        copy.group = source.group.clone();
+        copy.ssl_mode = source.ssl_mode;
+        copy.color = source.color;
+        copy.connect_timeout_secs = source.connect_timeout_secs;
+        copy.default_row_limit = source.default_row_limit;
+        copy.keepalive_secs = source.keepalive_secs;
+        copy.statement_timeout_secs = source.statement_timeout_secs;
+        copy.connect_retries = source.connect_retries;
+        copy.retry_backoff_secs = source.retry_backoff_secs;
+        copy.auto_reconnect = source.auto_reconnect;
+        copy.read_only = source.read_only;
+        copy.confirm_destructive = source.confirm_destructive;
+        copy.ssh_tunnel = source.ssh_tunnel.clone();
+        let copy_id = copy.id;
+        self.profiles.insert(index + 1, copy);
+        self.selected_profile = Some(copy_id);
+        self.profile_form_mode = ProfileFormMode::Editing(copy_id);
+        self.sync_form_with_selection(cx);
+        self.profile_notice = Some(match self.profile_store.save(&self.profiles) {
+            Ok(()) => "Profile duplicated. The password is not copied.".into(),
+            Err(err) => format!("Failed to save: {err}"),
+        });
+        cx.notify();
+    }
+
     fn cancel_profile_form(&mut self, cx: &mut Context<Self>) {
         self.profile_form_mode = ProfileFormMode::Hidden;
         self.profile_notice = None;
@@ -419,33 +1499,115 @@ impl DbMiruApp {
         cx.notify();
     }
 
-    fn save_profile(&mut self, cx: &mut Context<Self>) {
-        let values = self.profile_form.values(cx);
-        if values.name.trim().is_empty()
-            || values.host.trim().is_empty()
-            || values.database.trim().is_empty()
-            || values.username.trim().is_empty()
-        {
-            self.profile_notice = Some("Please fill out every field.".into());
+    fn set_profile_kind(&mut self, kind: ProfileKind, cx: &mut Context<Self>) {
+        let previous = self.profile_form.kind;
+        if previous == kind {
+            return;
+        }
+        let port = self.profile_form.port.read(cx).text();
+        if port.trim().is_empty() || port.trim() == previous.default_port().to_string() {
+            self.profile_form.port.update(cx, |input, _| {
+                input.set_text(&kind.default_port().to_string())
+            });
+        }
+        self.profile_form.kind = kind;
+        cx.notify();
+    }
+
+    fn apply_connection_string(&mut self, cx: &mut Context<Self>) {
+        let raw = self.profile_form.connection_string.read(cx).text();
+        if raw.trim().is_empty() {
+            self.profile_notice = Some("Paste a connection string first.".into());
             cx.notify();
             return;
         }
-        let port: u16 = match values.port.trim().parse() {
-            Ok(port) => port,
-            Err(_) => {
-                self.profile_notice = Some("Invalid port number.".into());
+
+        match ConnectionProfile::from_connection_string(&raw) {
+            Ok((profile, password)) => {
+                let current = self.profile_form.values(cx);
+                let values = ProfileFormValues {
+                    name: if current.name.trim().is_empty() {
+                        profile.name
+                    } else {
+                        current.name
+                    },
+                    host: profile.host,
+                    port: profile.port.to_string(),
+                    database: profile.database,
+                    username: profile.username,
+                    remember_password: current.remember_password || password.is_some(),
+                    password: password.unwrap_or(current.password),
+                    auto_reconnect: current.auto_reconnect,
+                    read_only: current.read_only,
+                    confirm_destructive: current.confirm_destructive,
+                    kind: profile.kind,
+                    ssl_mode: profile.ssl_mode,
+                    color: current.color,
+                    connect_timeout: profile.connect_timeout_secs.to_string(),
+                    row_limit: current.row_limit,
+                    keepalive: current.keepalive,
+                    statement_timeout: current.statement_timeout,
+                    connect_retries: current.connect_retries,
+                    retry_backoff: current.retry_backoff,
+                    group: current.group,
+                    ssh_host: current.ssh_host,
+                    ssh_port: current.ssh_port,
+                    ssh_user: current.ssh_user,
+                    ssh_key: current.ssh_key,
+                };
+                self.profile_form.set_values(&values, cx);
+                self.profile_form
+                    .connection_string
+                    .update(cx, |input, _| input.clear());
+                self.profile_notice = None;
+            }
+            Err(err) => {
+                self.profile_notice = Some(format!("Invalid connection string: {err}"));
+            }
+        }
+        cx.notify();
+    }
+
+    fn profile_from_form(
+        &mut self,
+        values: &ProfileFormValues,
+        cx: &mut Context<Self>,
+    ) -> Option<ConnectionProfile> {
+        match profile_from_form_values(values) {
+            Ok(profile) => Some(profile),
+            Err(message) => {
+                self.profile_notice = Some(message);
                 cx.notify();
-                return;
+                None
             }
+        }
+    }
+
+    fn test_connection(&mut self, cx: &mut Context<Self>) {
+        let values = self.profile_form.values(cx);
+        let Some(profile) = self.profile_from_form(&values, cx) else {
+            return;
         };
-        let mut updated_profile = ConnectionProfile::new(
-            values.name.trim().to_string(),
-            values.host.trim().to_string(),
-            port,
-            values.database.trim().to_string(),
-            values.username.trim().to_string(),
-            false,
-        );
+        let mut password = values.password;
+        if password.is_empty()
+            && let ProfileFormMode::Editing(profile_id) = self.profile_form_mode
+            && let Some(existing) = self.profiles.iter().find(|p| p.id == profile_id)
+            && existing.remember_password
+            && let Some(saved) = self.stored_password(existing)
+        {
+            password = saved;
+        }
+        self.profile_notice = Some(format!("Testing connection to {}...", profile.host));
+        db::test_profile_connection(profile, password, self.event_tx.clone());
+        cx.notify();
+    }
+
+    fn save_profile(&mut self, cx: &mut Context<Self>) {
+        let values = self.profile_form.values(cx);
+        let Some(mut updated_profile) = self.profile_from_form(&values, cx) else {
+            return;
+        };
+        let mut previous_username = None;
 
         match self.profile_form_mode {
             ProfileFormMode::Creating => {
@@ -455,11 +1617,27 @@ impl DbMiruApp {
             }
             ProfileFormMode::Editing(profile_id) => {
                 if let Some(profile) = self.profiles.iter_mut().find(|p| p.id == profile_id) {
+                    previous_username = Some(profile.username.clone());
                     profile.name = updated_profile.name.clone();
+                    profile.group = updated_profile.group.clone();
                     profile.host = updated_profile.host.clone();
                     profile.port = updated_profile.port;
                     profile.database = updated_profile.database.clone();
                     profile.username = updated_profile.username.clone();
+                    profile.remember_password = updated_profile.remember_password;
+                    profile.kind = updated_profile.kind;
+                    profile.ssl_mode = updated_profile.ssl_mode;
+                    profile.color = updated_profile.color;
+                    profile.connect_timeout_secs = updated_profile.connect_timeout_secs;
+                    profile.default_row_limit = updated_profile.default_row_limit;
+                    profile.keepalive_secs = updated_profile.keepalive_secs;
+                    profile.statement_timeout_secs = updated_profile.statement_timeout_secs;
+                    profile.connect_retries = updated_profile.connect_retries;
+                    profile.retry_backoff_secs = updated_profile.retry_backoff_secs;
+                    profile.auto_reconnect = updated_profile.auto_reconnect;
+                    profile.read_only = updated_profile.read_only;
+                    profile.confirm_destructive = updated_profile.confirm_destructive;
+                    profile.ssh_tunnel = updated_profile.ssh_tunnel.clone();
                     updated_profile.id = profile_id;
                 }
                 self.selected_profile = Some(profile_id);
@@ -469,6 +1647,12 @@ impl DbMiruApp {
 
         if let Err(err) = self.profile_store.save(&self.profiles) {
             self.profile_notice = Some(format!("Failed to save: {err}"));
+        } else if let Err(err) =
+            self.sync_saved_password(&updated_profile, previous_username, &values.password)
+        {
+            tracing::warn!("Failed to update saved password: {err}");
+            self.profile_notice = Some(format!("Saved, but the password was not stored: {err}"));
+            self.profile_form_mode = ProfileFormMode::Hidden;
         } else {
             self.profile_notice = Some("Saved.".into());
             self.profile_form_mode = ProfileFormMode::Hidden;
@@ -477,9 +1661,54 @@ impl DbMiruApp {
         cx.notify();
     }
 
+    fn sync_saved_password(
+        &self,
+        profile: &ConnectionProfile,
+        previous_username: Option<String>,
+        password: &str,
+    ) -> Result<()> {
+        let previous_username = previous_username.unwrap_or_else(|| profile.username.clone());
+        if !profile.remember_password {
+            self.secret_store
+                .delete_password(profile.id, &previous_username)?;
+            if previous_username != profile.username {
+                self.secret_store
+                    .delete_password(profile.id, &profile.username)?;
+            }
+            return Ok(());
+        }
+
+        let password = if password.is_empty() {
+            self.secret_store
+                .read_password(profile.id, &previous_username)?
+        } else {
+            Some(password.to_string())
+        };
+        if previous_username != profile.username {
+            self.secret_store
+                .delete_password(profile.id, &previous_username)?;
+        }
+        if let Some(password) = password {
+            self.secret_store
+                .write_password(profile.id, &profile.username, &password)?;
+        }
+        Ok(())
+    }
+
     fn delete_selected_profile(&mut self, cx: &mut Context<Self>) {
         if let Some(profile_id) = self.selected_profile {
+            if let Some(profile) = self.profiles.iter().find(|p| p.id == profile_id)
+                && let Err(err) = self
+                    .secret_store
+                    .delete_password(profile.id, &profile.username)
+            {
+                tracing::warn!("Failed to delete saved password: {err}");
+            }
             self.profiles.retain(|p| p.id != profile_id);
+            self.session.profile_sql.remove(&profile_id);
+            if self.session.editor_profile == Some(profile_id) {
+                self.session.editor_profile = None;
+            }
             if let Err(err) = self.profile_store.save(&self.profiles) {
                 self.profile_notice = Some(format!("Failed to delete: {err}"));
             } else {
@@ -499,6 +1728,39 @@ impl DbMiruApp {
         }
     }
 
+    fn save_session(&mut self, cx: &App) {
+        let sql = self.sql_input.read(cx).text();
+        self.session.set_sql(self.session.editor_profile, sql);
+        self.session.active_tab = match self.active_tab {
+            MainTab::SchemaBrowser | MainTab::Sessions | MainTab::SchemaDiff => {
+                SessionTab::SchemaBrowser
+            }
+            MainTab::SqlEditor => SessionTab::SqlEditor,
+        };
+        if let Err(err) = self.session_store.save(&self.session) {
+            tracing::warn!("Failed to save session: {err:?}");
+        }
+    }
+
+    // Each profile keeps its own editor text; SQL typed before the first
+    // connect carries over unless the profile already has some.
+    fn switch_editor_profile(&mut self, profile_id: ProfileId, cx: &mut Context<Self>) {
+        let previous = self.session.editor_profile;
+        if previous == Some(profile_id) {
+            return;
+        }
+        let current = self.sql_input.read(cx).text();
+        let saved = self.session.sql_for(Some(profile_id)).to_string();
+        self.session.set_sql(previous, current);
+        self.session.editor_profile = Some(profile_id);
+        if previous.is_some() || !saved.is_empty() {
+            self.sql_input.update(cx, |input, cx| {
+                input.set_text(&saved);
+                cx.notify();
+            });
+        }
+    }
+
     fn profile_index(&self, profile_id: ProfileId) -> Option<usize> {
         self.profiles
             .iter()
@@ -509,18 +1771,73 @@ impl DbMiruApp {
         let Some(current_index) = self.profile_index(profile_id) else {
             return;
         };
-        let target_index = match delta {
-            -1 => current_index.checked_sub(1),
-            1 if current_index + 1 < self.profiles.len() => Some(current_index + 1),
+        if let Some(target_index) = self.group_neighbor(current_index, delta) {
+            self.move_profile_to(profile_id, target_index, cx);
+        }
+    }
+
+    fn group_neighbor(&self, index: usize, delta: isize) -> Option<usize> {
+        let group = &self.profiles.get(index)?.group;
+        let same_group = |candidate: &usize| self.profiles[*candidate].group == *group;
+        match delta {
+            -1 => (0..index).rev().find(same_group),
+            1 => (index + 1..self.profiles.len()).find(same_group),
             _ => None,
+        }
+    }
+
+    fn drop_profile_on(
+        &mut self,
+        profile_id: ProfileId,
+        target_id: ProfileId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(target_index) = self.profile_index(target_id) else {
+            return;
         };
-        let Some(target_index) = target_index else {
+        let group = self.profiles[target_index].group.clone();
+        if let Some(profile) = self.profiles.iter_mut().find(|p| p.id == profile_id) {
+            profile.group = group;
+        }
+        self.move_profile_to(profile_id, target_index, cx);
+    }
+
+    fn toggle_profile_group(&mut self, group: Option<String>, cx: &mut Context<Self>) {
+        let key = group.unwrap_or_default();
+        if let Some(position) = self
+            .settings
+            .collapsed_groups
+            .iter()
+            .position(|g| *g == key)
+        {
+            self.settings.collapsed_groups.remove(position);
+        } else {
+            self.settings.collapsed_groups.push(key);
+        }
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        cx.notify();
+    }
+
+    fn move_profile_to(
+        &mut self,
+        profile_id: ProfileId,
+        target_index: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(current_index) = self.profile_index(profile_id) else {
             return;
         };
+        if current_index == target_index || target_index >= self.profiles.len() {
+            return;
+        }
 
-        self.profiles.swap(current_index, target_index);
+        let profile = self.profiles.remove(current_index);
+        self.profiles.insert(target_index, profile);
         if let Err(err) = self.profile_store.save(&self.profiles) {
-            self.profiles.swap(current_index, target_index);
+            let profile = self.profiles.remove(target_index);
+            self.profiles.insert(current_index, profile);
             self.profile_notice = Some(format!("Failed to save: {err}"));
         } else {
             self.profile_notice = None;
@@ -529,11 +1846,26 @@ impl DbMiruApp {
         cx.notify();
     }
 
-    fn select_profile(&mut self, profile_id: ProfileId, cx: &mut Context<Self>) {
+    fn select_profile(
+        &mut self,
+        profile_id: ProfileId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         self.selected_profile = Some(profile_id);
         self.profile_form_mode = ProfileFormMode::Hidden;
         self.profile_notice = None;
         self.sync_form_with_selection(cx);
+        let connected = self.connection.profile_id == Some(profile_id)
+            && (self.connection.is_connected() || self.connection.is_busy());
+        let needs_password = self
+            .profiles
+            .iter()
+            .find(|p| p.id == profile_id)
+            .is_some_and(|profile| !profile.remember_password);
+        if needs_password && !connected {
+            window.focus(&self.password_input.focus_handle(cx));
+        }
         cx.notify();
     }
 
@@ -546,20 +1878,80 @@ impl DbMiruApp {
             cx.notify();
             return;
         };
-        let Some(profile) = self.profiles.iter().find(|p| p.id == profile_id).cloned() else {
+        self.connect_profile(profile_id, None, cx);
+    }
+
+    fn reconnect(&mut self, cx: &mut Context<Self>) {
+        if self.connection.is_busy() || self.connection.is_connected() {
+            return;
+        }
+        let Some(profile_id) = self.connection.profile_id else {
+            return;
+        };
+        let password = self.connection.last_password.clone();
+        self.connect_profile(profile_id, password, cx);
+    }
+
+    fn connect_profile(
+        &mut self,
+        profile_id: ProfileId,
+        fallback_password: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(mut profile) = self.profiles.iter().find(|p| p.id == profile_id).cloned() else {
             self.connection.last_error = Some("Profile not found.".into());
             cx.notify();
             return;
         };
-        let password = self.password_input.read(cx).text();
+        let mut password = self.password_input.read(cx).text();
+        if password.is_empty()
+            && let Some(fallback) = fallback_password
+        {
+            password = fallback;
+        }
+        if password.is_empty()
+            && profile.remember_password
+            && let Some(saved) = self.stored_password(&profile)
+        {
+            password = saved;
+        }
+
+        // A reconnect keeps the editor's statement timeout override; a fresh connect
+        // starts from the profile's value.
+        let reconnecting =
+            self.connection.dropped && self.connection.profile_id == Some(profile.id);
+        let statement_timeout = if reconnecting {
+            parse_statement_timeout(&self.statement_timeout_input.read(cx).text())
+        } else {
+            None
+        };
+        match statement_timeout {
+            Some(secs) => profile.statement_timeout_secs = secs,
+            None => {
+                let secs = profile.statement_timeout_secs.to_string();
+                self.statement_timeout_input
+                    .update(cx, |input, _| input.set_text(&secs));
+            }
+        }
+        self.connection.statement_timeout = profile.statement_timeout();
+        self.switch_editor_profile(profile.id, cx);
 
         self.connection.status = ConnectionStatus::Connecting(profile.name.clone());
+        self.connection.connect_retry = None;
+        self.connection.kind = profile.kind;
+        self.connection.read_only = profile.read_only;
+        self.connection.confirm_destructive = profile.confirm_destructive;
+        self.connection.profile_id = Some(profile.id);
+        self.connection.last_password = Some(password.clone());
+        self.connection.dropped = false;
+        let row_limit = profile.default_row_limit.to_string();
+        self.row_limit_input
+            .update(cx, |input, _| input.set_text(&row_limit));
         self.connection.last_error = None;
         self.connecting_indicator = 1;
         self.connecting_indicator_frame = 0;
         self.connecting_indicator_active = false;
-        let adapter = PostgresAdapter::new(profile, password);
-        db::spawn_session(adapter, self.event_tx.clone());
+        db::spawn_profile_session(profile, password, self.event_tx.clone());
         self.password_input.update(cx, |input, _| input.clear());
         cx.notify();
     }
@@ -569,954 +1961,5876 @@ impl DbMiruApp {
             session.disconnect();
         }
         self.connection.status = ConnectionStatus::Disconnected;
+        self.connection.server_info = None;
+        self.connection.last_password = None;
+        self.connection.dropped = false;
+        self.query_state.in_transaction = false;
         self.schema_browser.reset();
+        self.server_sessions = ServerSessionsState::default();
+        self.notifications = NotificationsState::default();
+        self.csv_import = CsvImportState::default();
         self.active_tab = MainTab::SchemaBrowser;
         self.stop_connecting_indicator();
         cx.notify();
     }
 
-    fn execute_query(&mut self, cx: &mut Context<Self>) {
+    fn editor_sql(&mut self, cx: &mut Context<Self>) -> Option<String> {
         if self.connection.session.is_none() {
             self.query_state.last_error = Some("Connect to a database first.".into());
             cx.notify();
-            return;
+            return None;
         }
         if matches!(self.connection.status, ConnectionStatus::Connecting(_)) {
             self.query_state.last_error = Some("Please wait for the connection to finish.".into());
             cx.notify();
-            return;
+            return None;
         }
         if self.query_state.status == QueryStatus::Running {
-            return;
+            return None;
         }
         let sql = self.sql_input.read(cx).text();
         if sql.trim().is_empty() {
             self.query_state.last_error = Some("Enter a SQL statement.".into());
             cx.notify();
+            return None;
+        }
+        Some(sql)
+    }
+
+    fn ensure_bind_params(&mut self, count: usize, cx: &mut Context<Self>) {
+        while self.bind_params.len() < count {
+            let input = cx.new(|cx| TextInput::new(cx, "", "Value"));
+            self.bind_params.push(BindParam {
+                input,
+                kind: ParamKind::default(),
+            });
+        }
+    }
+
+    fn bind_param_values(
+        &mut self,
+        sql: &str,
+        cx: &mut Context<Self>,
+    ) -> std::result::Result<Vec<ParamValue>, String> {
+        let kind = self.connection.kind;
+        let count = parameter_count(kind, sql);
+        self.ensure_bind_params(count, cx);
+        self.bind_params[..count]
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let text = param.input.read(cx).text();
+                let label = param_label(kind, index);
+                match param.kind {
+                    ParamKind::Null => Ok(ParamValue::Null),
+                    ParamKind::Text => Ok(ParamValue::Text(text)),
+                    ParamKind::Int => text
+                        .trim()
+                        .parse()
+                        .map(ParamValue::Int)
+                        .map_err(|_| format!("Parameter {label}: enter a whole number.")),
+                    ParamKind::Bool => match text.trim().to_ascii_lowercase().as_str() {
+                        "true" | "t" | "yes" | "1" => Ok(ParamValue::Bool(true)),
+                        "false" | "f" | "no" | "0" => Ok(ParamValue::Bool(false)),
+                        _ => Err(format!("Parameter {label}: enter true or false.")),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn start_query(&mut self, sql: String, limit: usize, cx: &mut Context<Self>) {
+        if self.connection.read_only
+            && let Some((index, statement)) = split_statements(self.connection.kind, &sql)
+                .into_iter()
+                .enumerate()
+                .find(|(_, statement)| !is_read_only_statement(statement))
+        {
+            self.query_state.last_error = Some(QueryError::new(format!(
+                "Read-only connection: statement {} ({}) was not run. Only SELECT and EXPLAIN statements are allowed.",
+                index + 1,
+                statement_tag(statement)
+            )));
+            self.query_state.last_result = None;
+            self.query_state.last_completion = None;
+            self.query_state.timings.clear();
+            self.query_state.selected_row = None;
+            cx.notify();
             return;
         }
+        let params = match self.bind_param_values(&sql, cx) {
+            Ok(params) => params,
+            Err(message) => {
+                self.query_state.last_error = Some(message.into());
+                cx.notify();
+                return;
+            }
+        };
+        let Some(statement_timeout) =
+            parse_statement_timeout(&self.statement_timeout_input.read(cx).text())
+        else {
+            self.query_state.last_error = Some("Invalid statement timeout.".into());
+            cx.notify();
+            return;
+        };
+        let statement_timeout =
+            (statement_timeout > 0).then(|| Duration::from_secs(statement_timeout));
         if let Some(session) = self.connection.session.as_ref() {
             self.query_state.status = QueryStatus::Running;
+            self.query_state.row_limit = limit;
+            self.query_state.started_at = Some(Instant::now());
+            self.last_interaction = Instant::now();
+            self.query_state.elapsed = Duration::ZERO;
+            self.query_state.confirm_explain_analyze = false;
+            self.query_state.confirm_destructive = None;
             self.query_state.last_error = None;
+            self.query_state.last_completion = None;
             self.query_state.last_result = None;
-            session.execute(sql);
+            self.query_state.selected_row = None;
+            self.query_state.history_sql = None;
+            self.query_state.error_origin = None;
+            self.query_state.timings.clear();
+            self.query_state.notices.clear();
+            self.sql_input.update(cx, |input, _cx| input.clear_error());
+            if statement_timeout != self.connection.statement_timeout {
+                session.set_statement_timeout(statement_timeout);
+            }
+            session.execute(sql, limit, params);
             cx.notify();
         }
     }
 
-    fn copy_to_clipboard(&mut self, value: String, cx: &mut Context<Self>) {
-        cx.write_to_clipboard(ClipboardItem::new_string(value));
+    fn editor_row_limit(&mut self, cx: &mut Context<Self>) -> Option<usize> {
+        let limit = parse_row_limit(&self.row_limit_input.read(cx).text());
+        if limit.is_none() {
+            self.query_state.last_error = Some("Invalid row limit.".into());
+            cx.notify();
+        }
+        limit
     }
 
-    fn select_schema(&mut self, schema: String, cx: &mut Context<Self>) {
-        let Some(session) = self.connection.session.as_ref() else {
-            self.schema_browser.last_error =
-                Some("Load schemas after establishing a connection.".into());
+    fn execute_query(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = self.editor_sql(cx) else {
+            return;
+        };
+        let range = 0..text.len();
+        self.run_editor_range(text, range, false, cx);
+    }
+
+    fn format_editor_sql(&mut self, cx: &mut Context<Self>) {
+        let (text, cursor) = {
+            let input = self.sql_input.read(cx);
+            (input.text(), input.selected_range().end)
+        };
+        match format_sql(self.connection.kind, &text) {
+            Ok(formatted) if formatted == text => {}
+            Ok(formatted) => {
+                let cursor = offset_in_reformatted(&text, cursor, &formatted);
+                self.sql_input.update(cx, |input, cx| {
+                    input.set_text(&formatted);
+                    input.set_cursor(cursor);
+                    cx.notify();
+                });
+            }
+            Err(err) => {
+                self.show_toast(
+                    ActivityLevel::Warning,
+                    format!("SQL left unformatted: {err}."),
+                    cx,
+                );
+            }
+        }
+    }
+
+    fn execute_selection(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = self.editor_sql(cx) else {
+            return;
+        };
+        let selection = self.sql_input.read(cx).selected_range();
+        let range = if !text[selection.clone()].trim().is_empty() {
+            selection
+        } else {
+            statement_at(self.connection.kind, &text, selection.start).unwrap_or(0..text.len())
+        };
+        self.run_editor_range(text, range, false, cx);
+    }
+
+    fn run_editor_range(
+        &mut self,
+        text: String,
+        range: Range<usize>,
+        confirmed: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(limit) = self.editor_row_limit(cx) else {
+            return;
+        };
+        let sql = text[range.clone()].to_string();
+        let kind = self.connection.kind;
+        let statements = split_statements(kind, &sql);
+        if self.connection.confirm_destructive
+            && !confirmed
+            && statements
+                .iter()
+                .any(|statement| is_destructive_statement(kind, statement))
+        {
+            self.query_state.confirm_destructive = Some((text, range));
             cx.notify();
             return;
+        }
+        self.query_state.paged_sql = match statements.as_slice() {
+            [statement] if is_row_query(statement) => Some(statement.to_string()),
+            _ => None,
         };
-        self.schema_browser.selected_schema = Some(schema.clone());
-        self.schema_browser.selected_table = None;
-        self.schema_browser.tables.clear();
-        self.schema_browser.columns.clear();
-        self.schema_browser.preview = None;
-        self.schema_browser.tables_loading = true;
-        self.schema_browser.columns_loading = false;
-        self.schema_browser.preview_loading = false;
-        session.load_tables(schema);
+        self.query_state.page = 0;
+        self.query_state.plan_view = false;
+        self.start_query(sql.clone(), limit, cx);
+        self.query_state.history_sql = Some(sql);
+        let base = text[..range.start].chars().count();
+        self.query_state.error_origin = Some((text, base));
+    }
+
+    fn record_history(&mut self) {
+        let Some(sql) = self.query_state.history_sql.take() else {
+            return;
+        };
+        let Some(profile_id) = self.connection.profile_id else {
+            return;
+        };
+        if let Err(err) = self
+            .history_store
+            .append(&mut self.history, profile_id, &sql)
+        {
+            tracing::error!("Failed to save query history: {err:?}");
+        }
+    }
+
+    fn snippet_profile(&self) -> Option<ProfileId> {
+        self.connection.profile_id.or(self.selected_profile)
+    }
+
+    fn begin_save_snippet(&mut self, cx: &mut Context<Self>) {
+        if self.snippet_profile().is_none() {
+            self.snippet_notice = Some("Select a profile first.".into());
+        } else {
+            self.snippet_naming = true;
+            self.snippet_notice = None;
+            self.snippet_name_input.update(cx, |input, _| input.clear());
+        }
         cx.notify();
     }
 
-    fn select_table(&mut self, table: String, cx: &mut Context<Self>) {
-        let Some(schema) = self.schema_browser.selected_schema.clone() else {
+    fn save_snippet(&mut self, cx: &mut Context<Self>) {
+        let Some(profile_id) = self.snippet_profile() else {
             return;
         };
-        let Some(session) = self.connection.session.as_ref() else {
+        let name = self.snippet_name_input.read(cx).text().trim().to_string();
+        let sql = self.sql_input.read(cx).text();
+        if name.is_empty() {
+            self.snippet_notice = Some("Enter a snippet name.".into());
+        } else if sql.trim().is_empty() {
+            self.snippet_notice = Some("Enter a SQL statement.".into());
+        } else {
+            let snippet = Snippet::new(profile_id, name, sql);
+            match self.snippet_store.add(&mut self.snippets, snippet) {
+                Ok(()) => {
+                    self.snippet_naming = false;
+                    self.snippet_notice = None;
+                }
+                Err(err) => self.snippet_notice = Some(format!("Failed to save snippet: {err}")),
+            }
+        }
+        cx.notify();
+    }
+
+    fn load_snippet(&mut self, id: SnippetId, cx: &mut Context<Self>) {
+        let Some(snippet) = self.snippets.iter().find(|snippet| snippet.id == id) else {
             return;
         };
-        self.schema_browser.selected_table = Some(table.clone());
-        self.schema_browser.columns.clear();
-        self.schema_browser.preview = None;
-        self.schema_browser.columns_loading = true;
-        self.schema_browser.preview_loading = true;
-        session.load_columns(schema.clone(), table.clone());
-        session.preview_table(schema, table, db::PREVIEW_LIMIT);
+        let sql = snippet.sql.clone();
+        self.sql_input.update(cx, |input, cx| {
+            input.set_text(&sql);
+            cx.notify();
+        });
         cx.notify();
     }
-}
 
-impl Render for DbMiruApp {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        self.poll_events(cx);
-        window.set_window_title("DbMiru");
-        if self.connection.is_busy() {
-            self.ensure_connecting_indicator(window, cx);
-        } else if self.connecting_indicator_active {
-            self.stop_connecting_indicator();
+    fn update_snippet(&mut self, id: SnippetId, cx: &mut Context<Self>) {
+        let Some(snippet) = self.snippets.iter().find(|snippet| snippet.id == id) else {
+            return;
+        };
+        let name = snippet.name.clone();
+        let sql = self.sql_input.read(cx).text();
+        if sql.trim().is_empty() {
+            self.snippet_notice = Some("Enter a SQL statement.".into());
+        } else if let Err(err) = self
+            .snippet_store
+            .update(&mut self.snippets, id, &name, &sql)
+        {
+            self.snippet_notice = Some(format!("Failed to update snippet: {err}"));
+        } else {
+            self.snippet_notice = Some(format!("Updated \"{name}\"."));
         }
-        div()
-            .flex()
-            .gap_6()
-            .font_family(APP_FONT_FAMILY)
-            .size_full()
-            .bg(rgb(COLOR_CANVAS))
-            .text_color(rgb(0xf4f5fb))
-            .p_6()
-            .child(self.render_sidebar(cx))
-            .child(self.render_workspace(cx))
+        cx.notify();
     }
-}
 
-impl DbMiruApp {
-    fn render_sidebar(&mut self, cx: &mut Context<Self>) -> impl Element {
-        let selected = self.selected_profile;
-        let total_profiles = self.profiles.len();
-        let mut profile_items = Vec::new();
-        for (index, profile) in self.profiles.iter().cloned().enumerate() {
-            let is_selected = selected == Some(profile.id);
-            let profile_id = profile.id;
-            let name = profile.name.clone();
-            let can_move_up = index > 0;
-            let can_move_down = index + 1 < total_profiles;
+    fn delete_snippet(&mut self, id: SnippetId, cx: &mut Context<Self>) {
+        if let Err(err) = self.snippet_store.delete(&mut self.snippets, id) {
+            self.snippet_notice = Some(format!("Failed to delete snippet: {err}"));
+        }
+        cx.notify();
+    }
 
-            let reorder_controls = if is_selected {
-                div()
-                    .flex()
-                    .flex_col()
-                    .items_end()
-                    .gap_1()
-                    .flex_shrink_0()
-                    .child({
-                        let mut node = div()
-                            .flex()
-                            .items_center()
-                            .justify_center()
-                            .w(px(20.))
-                            .h(px(18.))
-                            .rounded_md()
-                            .bg(rgb(COLOR_PANEL_HIGHLIGHT))
+    fn export_snippets(&mut self, cx: &mut Context<Self>) {
+        let Some(profile_id) = self.snippet_profile() else {
+            self.snippet_notice = Some("Select a profile first.".into());
+            cx.notify();
+            return;
+        };
+        let directory = BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or_default();
+        let receiver = cx.prompt_for_new_path(&directory, Some("snippets.json"));
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = receiver.await else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.snippet_notice = Some(
+                    match this.snippet_store.export(&this.snippets, profile_id, &path) {
+                        Ok(count) => format!("Exported {count} snippet(s)."),
+                        Err(err) => format!("Failed to export snippets: {err}"),
+                    },
+                );
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn import_snippets(&mut self, cx: &mut Context<Self>) {
+        let Some(profile_id) = self.snippet_profile() else {
+            self.snippet_notice = Some("Select a profile first.".into());
+            cx.notify();
+            return;
+        };
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import snippets".into()),
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = receiver.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.snippet_notice = Some(
+                    match this
+                        .snippet_store
+                        .import(&mut this.snippets, profile_id, &path)
+                    {
+                        Ok(count) => format!("Imported {count} snippet(s)."),
+                        Err(err) => format!("Failed to import snippets: {err}"),
+                    },
+                );
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn export_profiles(&mut self, cx: &mut Context<Self>) {
+        if self.profiles.is_empty() {
+            self.profile_notice = Some("No profiles to export.".into());
+            cx.notify();
+            return;
+        }
+        let directory = BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or_default();
+        let receiver = cx.prompt_for_new_path(&directory, Some("profiles.json"));
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = receiver.await else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.profile_notice =
+                    Some(match this.profile_store.export(&this.profiles, &path) {
+                        Ok(count) => format!("Exported {count} profile(s) without passwords."),
+                        Err(err) => format!("Failed to export profiles: {err}"),
+                    });
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn import_profiles(&mut self, cx: &mut Context<Self>) {
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import profiles".into()),
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = receiver.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.profile_notice =
+                    Some(match this.profile_store.import(&mut this.profiles, &path) {
+                        Ok(import) if import.errors.is_empty() => {
+                            format!("Imported {} profile(s).", import.imported)
+                        }
+                        Ok(import) => format!(
+                            "Imported {} profile(s), skipped {}: {}",
+                            import.imported,
+                            import.errors.len(),
+                            import.errors.join("; ")
+                        ),
+                        Err(err) => format!("Failed to import profiles: {err}"),
+                    });
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn toggle_history(&mut self, cx: &mut Context<Self>) {
+        self.history_open = !self.history_open;
+        cx.notify();
+    }
+
+    fn load_history_entry(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.history.get(index) else {
+            return;
+        };
+        let sql = entry.sql.clone();
+        self.sql_input.update(cx, |input, cx| {
+            input.set_text(&sql);
+            cx.notify();
+        });
+        self.history_open = false;
+        cx.notify();
+    }
+
+    fn go_to_page(&mut self, page: usize, cx: &mut Context<Self>) {
+        if self.query_state.status == QueryStatus::Running {
+            return;
+        }
+        let Some(sql) = self.page_sql(page) else {
+            return;
+        };
+        self.query_state.page = page;
+        self.start_query(sql, self.query_state.row_limit, cx);
+    }
+
+    // The statement that fetches `page` of the last single row query, if the
+    // dialect can page it.
+    fn page_sql(&self, page: usize) -> Option<String> {
+        let statement = self.query_state.paged_sql.as_deref()?;
+        page_query(
+            self.connection.kind,
+            statement,
+            self.query_state.row_limit,
+            page,
+        )
+    }
+
+    fn explain_query(&mut self, analyze: bool, confirmed: bool, cx: &mut Context<Self>) {
+        let Some(sql) = self.editor_sql(cx) else {
+            return;
+        };
+        let statements = split_statements(self.connection.kind, &sql);
+        let [statement] = statements.as_slice() else {
+            self.query_state.last_error = Some("Explain works on a single statement.".into());
+            cx.notify();
+            return;
+        };
+        if analyze && !confirmed && !is_row_query(statement) {
+            self.query_state.confirm_explain_analyze = true;
+            cx.notify();
+            return;
+        }
+        let explain = match (self.connection.kind, analyze) {
+            (ProfileKind::Postgres, false) => format!("EXPLAIN (FORMAT TEXT) {statement}"),
+            (ProfileKind::Postgres, true) => format!("EXPLAIN (ANALYZE, FORMAT TEXT) {statement}"),
+            (ProfileKind::MySql, false) => format!("EXPLAIN FORMAT=TREE {statement}"),
+            (ProfileKind::MySql, true) => format!("EXPLAIN ANALYZE {statement}"),
+        };
+        self.query_state.paged_sql = None;
+        self.query_state.page = 0;
+        self.query_state.plan_view = true;
+        self.start_query(explain, ROW_LIMIT, cx);
+    }
+
+    fn begin_transaction(&mut self, cx: &mut Context<Self>) {
+        if self.query_state.in_transaction || self.query_state.status == QueryStatus::Running {
+            return;
+        }
+        if let Some(session) = self.connection.session.as_ref() {
+            session.begin_transaction();
+        } else {
+            self.query_state.last_error = Some("Connect to a database first.".into());
+        }
+        cx.notify();
+    }
+
+    fn end_transaction(&mut self, commit: bool, cx: &mut Context<Self>) {
+        if !self.query_state.in_transaction || self.query_state.status == QueryStatus::Running {
+            return;
+        }
+        if let Some(session) = self.connection.session.as_ref() {
+            if commit {
+                session.commit_transaction();
+            } else {
+                session.rollback_transaction();
+            }
+        }
+        cx.notify();
+    }
+
+    fn cancel_query(&mut self, cx: &mut Context<Self>) {
+        if self.query_state.status != QueryStatus::Running || self.query_state.cancel_requested {
+            return;
+        }
+        if let Some(session) = self.connection.session.as_ref() {
+            self.query_state.cancel_requested = true;
+            session.cancel_query();
+            cx.notify();
+        }
+    }
+
+    fn toggle_compare_picker(&mut self, cx: &mut Context<Self>) {
+        self.compare_run.picker_open = !self.compare_run.picker_open;
+        self.compare_run.confirm_destructive = None;
+        cx.notify();
+    }
+
+    fn toggle_compare_profile(&mut self, profile_id: ProfileId, cx: &mut Context<Self>) {
+        let selected = &mut self.compare_run.selected_profiles;
+        if let Some(index) = selected.iter().position(|id| *id == profile_id) {
+            selected.remove(index);
+        } else {
+            selected.push(profile_id);
+        }
+        cx.notify();
+    }
+
+    fn start_compare_run(&mut self, confirmed: bool, cx: &mut Context<Self>) {
+        if self.compare_run.is_running() {
+            return;
+        }
+        let sql = self.sql_input.read(cx).text();
+        if sql.trim().is_empty() {
+            self.query_state.last_error = Some("Enter a SQL statement.".into());
+            cx.notify();
+            return;
+        }
+        let Some(row_limit) = self.editor_row_limit(cx) else {
+            return;
+        };
+        let params = match self.bind_param_values(&sql, cx) {
+            Ok(params) => params,
+            Err(message) => {
+                self.query_state.last_error = Some(message.into());
+                cx.notify();
+                return;
+            }
+        };
+        let targets: Vec<CompareTarget> = self
+            .profiles
+            .iter()
+            .filter(|profile| self.compare_run.selected_profiles.contains(&profile.id))
+            .map(|profile| CompareTarget {
+                profile_id: profile.id,
+                profile_name: profile.name.clone(),
+                status: CompareTargetStatus::Pending,
+            })
+            .collect();
+        if targets.is_empty() {
+            self.query_state.last_error = Some("Select at least one profile to run on.".into());
+            cx.notify();
+            return;
+        }
+        // Read-only profiles refuse the statements on their own, so only the
+        // profiles that would actually run them ask for confirmation.
+        let mut destructive = Vec::new();
+        let mut confirm_profiles = Vec::new();
+        for profile in self.profiles.iter().filter(|profile| {
+            self.compare_run.selected_profiles.contains(&profile.id)
+                && profile.confirm_destructive
+                && !profile.read_only
+        }) {
+            let tags = split_statements(profile.kind, &sql)
+                .into_iter()
+                .filter(|statement| is_destructive_statement(profile.kind, statement))
+                .map(statement_tag)
+                .collect::<Vec<_>>();
+            if !tags.is_empty() {
+                confirm_profiles.push(profile.name.clone());
+            }
+            for tag in tags {
+                if !destructive.contains(&tag) {
+                    destructive.push(tag);
+                }
+            }
+        }
+        if !confirmed && !confirm_profiles.is_empty() {
+            self.compare_run.confirm_destructive = Some(format!(
+                "This run contains destructive statements ({}) and will run them on {}.",
+                destructive.join(", "),
+                confirm_profiles.join(", ")
+            ));
+            cx.notify();
+            return;
+        }
+
+        self.query_state.last_error = None;
+        self.compare_run.confirm_destructive = None;
+        self.compare_run.picker_open = false;
+        self.compare_run.sql = sql;
+        self.compare_run.row_limit = row_limit;
+        self.compare_run.params = params;
+        self.compare_run.targets = targets;
+        self.advance_compare_run();
+        cx.notify();
+    }
+
+    fn advance_compare_run(&mut self) {
+        self.compare_run.session = None;
+        self.compare_run.events = None;
+        while let Some(index) = self.compare_run.next_pending() {
+            let profile_id = self.compare_run.targets[index].profile_id;
+            let Some(profile) = self.profiles.iter().find(|p| p.id == profile_id).cloned() else {
+                self.compare_run.targets[index].status =
+                    CompareTargetStatus::Failed("Profile not found.".into());
+                continue;
+            };
+            if profile.read_only
+                && let Some((statement_index, statement)) =
+                    split_statements(profile.kind, &self.compare_run.sql)
+                        .into_iter()
+                        .enumerate()
+                        .find(|(_, statement)| !is_read_only_statement(statement))
+            {
+                self.compare_run.targets[index].status = CompareTargetStatus::Failed(format!(
+                    "Read-only profile: statement {} ({}) was not run.",
+                    statement_index + 1,
+                    statement_tag(statement)
+                ));
+                continue;
+            }
+            let Some(password) = self.saved_password(&profile) else {
+                self.compare_run.targets[index].status = CompareTargetStatus::Failed(
+                    "Password required: save this profile's password to include it.".into(),
+                );
+                continue;
+            };
+            let (event_tx, event_rx) = async_channel::unbounded();
+            self.compare_run.targets[index].status = CompareTargetStatus::Connecting;
+            self.compare_run.events = Some(event_rx);
+            db::spawn_profile_session(profile, password, event_tx);
+            return;
+        }
+    }
+
+    // Sessions opened in the background only use the password saved for that
+    // profile; the Password field belongs to the profile being connected.
+    fn saved_password(&self, profile: &ConnectionProfile) -> Option<String> {
+        if profile.remember_password {
+            self.stored_password(profile)
+        } else {
+            None
+        }
+    }
+
+    fn stored_password(&self, profile: &ConnectionProfile) -> Option<String> {
+        match self
+            .secret_store
+            .read_password(profile.id, &profile.username)
+        {
+            Ok(password) => password,
+            Err(err) => {
+                tracing::warn!("Failed to read saved password: {err}");
+                None
+            }
+        }
+    }
+
+    fn ensure_compare_ticker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.compare_run.ticking {
+            return;
+        }
+        self.compare_run.ticking = true;
+        self.schedule_compare_ticker(window, cx);
+    }
+
+    fn schedule_compare_ticker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.on_next_frame(window, |this, window, cx| {
+            if !this.compare_run.is_running() {
+                this.compare_run.ticking = false;
+                cx.notify();
+                return;
+            }
+            if this.poll_compare_events() {
+                cx.notify();
+            }
+            this.schedule_compare_ticker(window, cx);
+        });
+    }
+
+    fn poll_compare_events(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(event) = self
+            .compare_run
+            .events
+            .as_ref()
+            .and_then(|events| events.try_recv().ok())
+        {
+            self.handle_compare_event(event);
+            changed = true;
+        }
+        changed
+    }
+
+    fn handle_compare_event(&mut self, event: DbEvent) {
+        let Some(index) = self.compare_run.active_target() else {
+            return;
+        };
+        let target = &mut self.compare_run.targets[index];
+        match event {
+            DbEvent::Connected(handle) => {
+                target.status = CompareTargetStatus::Running;
+                handle.execute(
+                    self.compare_run.sql.clone(),
+                    self.compare_run.row_limit,
+                    self.compare_run.params.clone(),
+                );
+                self.compare_run.session = Some(handle);
+            }
+            DbEvent::ConnectionFailed(error) => {
+                tracing::warn!(
+                    "Comparison connection to {} failed: {}",
+                    target.profile_name,
+                    error.detail
+                );
+                target.status = CompareTargetStatus::Failed(error.user_message);
+                self.advance_compare_run();
+            }
+            DbEvent::ConnectionClosed(reason) => {
+                target.status = CompareTargetStatus::Failed(
+                    reason.unwrap_or_else(|| "Connection closed.".into()),
+                );
+                self.advance_compare_run();
+            }
+            DbEvent::QueryFinished(result) => {
+                target.status = CompareTargetStatus::Finished(QueryResultView::from(result));
+                self.advance_compare_run();
+            }
+            DbEvent::StatementCompleted { affected, tag, .. } => {
+                target.status = CompareTargetStatus::Completed(completion_message(affected, &tag));
+                self.advance_compare_run();
+            }
+            DbEvent::QueryFailed(error) => {
+                target.status = CompareTargetStatus::Failed(error.message);
+                self.advance_compare_run();
+            }
+            _ => {}
+        }
+    }
+
+    fn close_compare_run(&mut self, cx: &mut Context<Self>) {
+        self.compare_run.session = None;
+        self.compare_run.events = None;
+        self.compare_run.targets.clear();
+        cx.notify();
+    }
+
+    fn set_schema_diff_profile(
+        &mut self,
+        side: usize,
+        profile_id: ProfileId,
+        cx: &mut Context<Self>,
+    ) {
+        self.schema_diff.sides[side].profile_id = Some(profile_id);
+        cx.notify();
+    }
+
+    fn start_schema_diff(&mut self, cx: &mut Context<Self>) {
+        if self.schema_diff.is_running() {
+            return;
+        }
+        let mut targets = Vec::new();
+        for side in 0..2 {
+            let profile = self.schema_diff.sides[side]
+                .profile_id
+                .and_then(|id| self.profiles.iter().find(|profile| profile.id == id))
+                .cloned();
+            let Some(profile) = profile else {
+                self.show_toast(
+                    ActivityLevel::Warning,
+                    "Pick a profile for both sides of the diff.".into(),
+                    cx,
+                );
+                return;
+            };
+            let schema = self.schema_diff_inputs[side]
+                .read(cx)
+                .text()
+                .trim()
+                .to_string();
+            let schema = if schema.is_empty() {
+                default_diff_schema(&profile)
+            } else {
+                schema
+            };
+            let Some(password) = self.saved_password(&profile) else {
+                self.show_toast(
+                    ActivityLevel::Warning,
+                    format!(
+                        "Password required: save the password for {} to compare its schema.",
+                        profile.name
+                    ),
+                    cx,
+                );
+                return;
+            };
+            targets.push((profile, schema, password));
+        }
+
+        self.schema_diff.result = None;
+        for (side, (profile, schema, password)) in targets.into_iter().enumerate() {
+            let (event_tx, event_rx) = async_channel::unbounded();
+            self.schema_diff.sides[side] = SchemaDiffSide {
+                profile_id: Some(profile.id),
+                profile_name: profile.name.clone(),
+                schema,
+                status: SchemaDiffStatus::Connecting,
+                events: Some(event_rx),
+                ..SchemaDiffSide::default()
+            };
+            db::spawn_profile_session(profile, password, event_tx);
+        }
+        cx.notify();
+    }
+
+    fn ensure_schema_diff_ticker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.schema_diff.ticking {
+            return;
+        }
+        self.schema_diff.ticking = true;
+        self.schedule_schema_diff_ticker(window, cx);
+    }
+
+    fn schedule_schema_diff_ticker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.on_next_frame(window, |this, window, cx| {
+            if !this.schema_diff.is_running() {
+                this.schema_diff.ticking = false;
+                cx.notify();
+                return;
+            }
+            if this.poll_schema_diff_events() {
+                cx.notify();
+            }
+            this.schedule_schema_diff_ticker(window, cx);
+        });
+    }
+
+    fn poll_schema_diff_events(&mut self) -> bool {
+        let mut changed = false;
+        for side in 0..2 {
+            while let Some(event) = self.schema_diff.sides[side]
+                .events
+                .as_ref()
+                .and_then(|events| events.try_recv().ok())
+            {
+                self.handle_schema_diff_event(side, event);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn handle_schema_diff_event(&mut self, side: usize, event: DbEvent) {
+        let state = &mut self.schema_diff.sides[side];
+        match event {
+            DbEvent::Connected(handle) => {
+                state.status = SchemaDiffStatus::Loading;
+                handle.load_tables(state.schema.clone());
+                state.session = Some(handle);
+            }
+            DbEvent::ConnectionFailed(error) => {
+                tracing::warn!(
+                    "Schema diff connection to {} failed: {}",
+                    state.profile_name,
+                    error.detail
+                );
+                self.stop_schema_diff_side(side, Some(error.user_message));
+            }
+            DbEvent::ConnectionClosed(reason) => {
+                let reason = reason.unwrap_or_else(|| "Connection closed.".into());
+                self.stop_schema_diff_side(side, Some(reason));
+            }
+            DbEvent::MetadataFailed(message) => {
+                self.stop_schema_diff_side(side, Some(message));
+            }
+            DbEvent::TablesLoaded { schema, tables } => {
+                state.pending_tables = tables.len();
+                if let Some(session) = state.session.as_ref() {
+                    for table in tables {
+                        session.load_columns(schema.clone(), table);
+                    }
+                }
+                if state.pending_tables == 0 {
+                    self.finish_schema_diff_side(side);
+                }
+            }
+            DbEvent::ColumnsLoaded { table, columns, .. } => {
+                state.tables.insert(table, columns);
+                state.pending_tables = state.pending_tables.saturating_sub(1);
+                if state.pending_tables == 0 {
+                    self.finish_schema_diff_side(side);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish_schema_diff_side(&mut self, side: usize) {
+        self.schema_diff.sides[side].status = SchemaDiffStatus::Loaded;
+        self.stop_schema_diff_side(side, None);
+        let [left, right] = &self.schema_diff.sides;
+        if matches!(left.status, SchemaDiffStatus::Loaded)
+            && matches!(right.status, SchemaDiffStatus::Loaded)
+        {
+            self.schema_diff.result = Some(schema_diff::diff_schemas(&left.tables, &right.tables));
+        }
+    }
+
+    // Closes the side's session off the UI thread: dropping a handle waits for
+    // the worker, which first works through any column fetches still queued.
+    fn stop_schema_diff_side(&mut self, side: usize, failure: Option<String>) {
+        let state = &mut self.schema_diff.sides[side];
+        state.events = None;
+        if let Some(message) = failure {
+            state.status = SchemaDiffStatus::Failed(message);
+        }
+        if let Some(session) = state.session.take() {
+            thread::spawn(move || drop(session));
+        }
+    }
+
+    fn stop_schema_diff(&mut self, cx: &mut Context<Self>) {
+        for side in 0..2 {
+            if self.schema_diff.sides[side].events.is_some() {
+                self.stop_schema_diff_side(side, Some("Stopped.".into()));
+            }
+        }
+        cx.notify();
+    }
+
+    fn copy_to_clipboard(&mut self, value: String, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(value));
+    }
+
+    fn copy_cell_value(&mut self, row: usize, column: usize, cx: &mut Context<Self>) {
+        let Some(cell) = self
+            .query_state
+            .last_result
+            .as_ref()
+            .and_then(|result| result.rows.get(row))
+            .and_then(|cells| cells.get(column))
+        else {
+            return;
+        };
+        let value = match cell {
+            Cell::Text(value) => value.clone(),
+            Cell::Null if self.settings.copy_null_as_empty => String::new(),
+            Cell::Null => return,
+        };
+        self.copy_to_clipboard(value, cx);
+        self.show_toast(ActivityLevel::Info, "Copied cell value.".into(), cx);
+    }
+
+    fn copy_row_as_insert(&mut self, row: usize, cx: &mut Context<Self>) {
+        let Some(result) = self.query_state.last_result.as_ref() else {
+            return;
+        };
+        if row >= result.rows.len() {
+            return;
+        }
+        let table = self
+            .query_state
+            .paged_sql
+            .as_deref()
+            .and_then(single_source_table)
+            .unwrap_or("<table>");
+        let statement = row_insert_statement(self.connection.kind, table, result, row);
+        self.copy_to_clipboard(statement, cx);
+    }
+
+    fn select_result_row(&mut self, row: usize, cx: &mut Context<Self>) {
+        self.query_state.selected_row = if self.query_state.selected_row == Some(row) {
+            None
+        } else {
+            Some(row)
+        };
+        cx.notify();
+    }
+
+    fn toggle_pretty_json(&mut self, column: usize, cx: &mut Context<Self>) {
+        if !self.query_state.pretty_json.remove(&column) {
+            self.query_state.pretty_json.insert(column);
+        }
+        cx.notify();
+    }
+
+    fn toggle_result_sort(&mut self, grid: ResultGrid, column: usize, cx: &mut Context<Self>) {
+        let view = match grid {
+            ResultGrid::Query => self.query_state.last_result.as_mut(),
+            ResultGrid::Preview => self.schema_browser.preview.as_mut(),
+        };
+        if let Some(result) = view {
+            result.toggle_sort(column);
+            cx.notify();
+        }
+    }
+
+    fn start_column_resize(
+        &mut self,
+        column: usize,
+        event: &MouseDownEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(result) = self.query_state.last_result.as_mut() else {
+            return;
+        };
+        if event.click_count >= 2 {
+            self.query_state.column_resize = None;
+            result.fit_column(column);
+            cx.notify();
+            return;
+        }
+        self.query_state.column_resize = Some(ColumnResize {
+            column,
+            start_x: event.position.x,
+            start_width: result.column_width(column),
+        });
+    }
+
+    fn update_column_resize(&mut self, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        let Some(resize) = self.query_state.column_resize else {
+            return;
+        };
+        if !event.dragging() {
+            self.query_state.column_resize = None;
+            return;
+        }
+        if let Some(result) = self.query_state.last_result.as_mut() {
+            let delta = f32::from(event.position.x - resize.start_x);
+            result.set_column_width(resize.column, resize.start_width + delta);
+            cx.notify();
+        }
+    }
+
+    fn render_splitter(
+        &self,
+        theme: &Theme,
+        splitter: Splitter,
+        cx: &mut Context<Self>,
+    ) -> gpui::Div {
+        let active = self
+            .panel_resize
+            .is_some_and(|resize| resize.splitter == splitter);
+        let line = div()
+            .bg(if active { theme.accent } else { theme.border })
+            .group_hover("splitter", |style| style.bg(theme.accent));
+        let (bar, line) = if splitter.is_vertical() {
+            (
+                div().h(px(SPLITTER_WIDTH)).w_full().cursor_row_resize(),
+                line.h(px(1.)).w_full(),
+            )
+        } else {
+            (
+                div().w(px(SPLITTER_WIDTH)).cursor_col_resize(),
+                line.w(px(1.)).h_full(),
+            )
+        };
+        bar.flex()
+            .items_center()
+            .justify_center()
+            .flex_shrink_0()
+            .group("splitter")
+            .child(line)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event: &MouseDownEvent, _window, cx| {
+                    cx.stop_propagation();
+                    this.start_panel_resize(splitter, event, cx);
+                }),
+            )
+    }
+
+    fn panel_size(&self, splitter: Splitter) -> f32 {
+        match splitter {
+            Splitter::Sidebar => self.settings.sidebar_width,
+            Splitter::SchemaList => self.settings.schema_list_width,
+            Splitter::TableList => self.settings.table_list_width,
+            Splitter::Editor => self.settings.editor_height,
+        }
+    }
+
+    fn start_panel_resize(
+        &mut self,
+        splitter: Splitter,
+        event: &MouseDownEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if event.click_count >= 2 {
+            let size = match splitter {
+                Splitter::Sidebar => DEFAULT_SIDEBAR_WIDTH,
+                Splitter::SchemaList => DEFAULT_SCHEMA_LIST_WIDTH,
+                Splitter::TableList => DEFAULT_TABLE_LIST_WIDTH,
+                Splitter::Editor => DEFAULT_EDITOR_HEIGHT,
+            };
+            self.set_panel_size(splitter, size);
+            self.panel_resize = None;
+            self.save_layout();
+            cx.notify();
+            return;
+        }
+        self.panel_resize = Some(PanelResize {
+            splitter,
+            start_position: splitter.axis_position(event.position),
+            start_size: self.panel_size(splitter),
+        });
+        cx.notify();
+    }
+
+    fn set_panel_size(&mut self, splitter: Splitter, size: f32) {
+        let (min, max) = splitter.size_range();
+        let size = size.clamp(min, max);
+        match splitter {
+            Splitter::Sidebar => self.settings.sidebar_width = size,
+            Splitter::SchemaList => self.settings.schema_list_width = size,
+            Splitter::TableList => self.settings.table_list_width = size,
+            Splitter::Editor => self.settings.editor_height = size,
+        }
+    }
+
+    fn update_panel_resize(&mut self, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        let Some(resize) = self.panel_resize else {
+            return;
+        };
+        if !event.dragging() {
+            self.finish_panel_resize();
+            cx.notify();
+            return;
+        }
+        let delta =
+            f32::from(resize.splitter.axis_position(event.position) - resize.start_position);
+        self.set_panel_size(resize.splitter, resize.start_size + delta);
+        cx.notify();
+    }
+
+    fn finish_panel_resize(&mut self) {
+        if self.panel_resize.take().is_some() {
+            self.save_layout();
+        }
+    }
+
+    fn save_layout(&self) {
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+    }
+
+    fn toggle_column_filters(&mut self, cx: &mut Context<Self>) {
+        self.query_state.column_filters_open = !self.query_state.column_filters_open;
+        if !self.query_state.column_filters_open {
+            self.clear_column_filters(cx);
+        }
+        cx.notify();
+    }
+
+    fn clear_column_filters(&mut self, cx: &mut Context<Self>) {
+        for input in &self.column_filter_inputs {
+            input.update(cx, |input, _| input.set_text(""));
+        }
+    }
+
+    fn sync_column_filters(&mut self, cx: &mut Context<Self>) {
+        let Some(result) = self.query_state.last_result.as_mut() else {
+            return;
+        };
+        if !self.query_state.column_filters_open {
+            result.column_filters.clear();
+            return;
+        }
+        while self.column_filter_inputs.len() < result.columns.len() {
+            self.column_filter_inputs
+                .push(cx.new(|cx| TextInput::new(cx, "", "Filter")));
+        }
+        result.column_filters = self.column_filter_inputs[..result.columns.len()]
+            .iter()
+            .map(|input| input.read(cx).text())
+            .collect();
+    }
+
+    fn copy_result_as_tsv(&mut self, selected_only: bool, cx: &mut Context<Self>) {
+        let Some(result) = self.query_state.last_result.as_ref() else {
+            return;
+        };
+        let rows = if selected_only {
+            self.query_state
+                .selected_row
+                .filter(|row| *row < result.rows.len())
+                .into_iter()
+                .collect()
+        } else {
+            result.display_order()
+        };
+        let tsv = export::to_tsv(&result.columns, rows.iter().map(|row| &result.rows[*row]));
+        self.copy_to_clipboard(tsv, cx);
+    }
+
+    fn export_result_as_csv(&mut self, cx: &mut Context<Self>) {
+        let Some(result) = self.query_state.last_result.as_ref() else {
+            self.show_toast(ActivityLevel::Warning, "No results to export.".into(), cx);
+            return;
+        };
+        let csv = export::to_delimited(
+            &result.columns,
+            result.display_order().iter().map(|row| &result.rows[*row]),
+            ',',
+        );
+        let directory = BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or_default();
+        let receiver = cx.prompt_for_new_path(&directory, Some("results.csv"));
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = receiver.await else {
+                return;
+            };
+            this.update(cx, |this, cx| match fs::write(&path, csv) {
+                Ok(()) => this.show_toast(
+                    ActivityLevel::Info,
+                    format!("Exported results to {}.", path.display()),
+                    cx,
+                ),
+                Err(err) => this.show_toast(
+                    ActivityLevel::Error,
+                    format!("Failed to export results: {err}"),
+                    cx,
+                ),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn copy_result_as_json(&mut self, native_types: bool, cx: &mut Context<Self>) {
+        if let Some(result) = self.query_state.last_result.as_ref() {
+            let json = result_to_json(result, native_types);
+            self.copy_to_clipboard(json, cx);
+        }
+    }
+
+    fn select_schema(&mut self, schema: String, cx: &mut Context<Self>) {
+        let Some(session) = self.connection.session.as_ref() else {
+            self.schema_browser.last_error =
+                Some("Load schemas after establishing a connection.".into());
+            cx.notify();
+            return;
+        };
+        self.schema_browser.selected_schema = Some(schema.clone());
+        self.schema_browser.selected_table = None;
+        self.schema_browser.tables.clear();
+        self.schema_browser.table_sizes.clear();
+        self.schema_browser.views.clear();
+        self.schema_browser.table_menu = None;
+        self.schema_browser.column_cursor = None;
+        self.schema_browser.columns.clear();
+        self.schema_browser.indexes.clear();
+        self.schema_browser.foreign_keys.clear();
+        self.schema_browser.preview = None;
+        self.schema_browser.tables_loading = true;
+        self.schema_browser.views_loading = true;
+        self.schema_browser.columns_loading = false;
+        self.schema_browser.preview_loading = false;
+        session.load_tables(schema.clone());
+        session.load_views(schema.clone());
+        let table = self
+            .connected_profile()
+            .filter(|profile| profile.last_schema.as_deref() == Some(&schema))
+            .and_then(|profile| profile.last_table.clone());
+        self.remember_browser_selection(schema, table);
+        cx.notify();
+    }
+
+    fn select_table(&mut self, table: String, cx: &mut Context<Self>) {
+        let Some(schema) = self.schema_browser.selected_schema.clone() else {
+            return;
+        };
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        self.schema_browser.selected_table = Some(table.clone());
+        self.schema_browser.column_cursor = None;
+        self.schema_browser.columns.clear();
+        self.schema_browser.indexes.clear();
+        self.schema_browser.foreign_keys.clear();
+        self.schema_browser.preview = None;
+        self.schema_browser.ddl = None;
+        self.schema_browser.ddl_loading = false;
+        self.schema_browser.column_stats = None;
+        self.schema_browser.column_stats_loading = false;
+        self.schema_browser.columns_loading = true;
+        self.schema_browser.preview_loading = true;
+        session.load_columns(schema.clone(), table.clone());
+        session.load_indexes(schema.clone(), table.clone());
+        session.load_foreign_keys(schema.clone(), table.clone());
+        session.preview_table(schema.clone(), table.clone(), db::PREVIEW_LIMIT);
+        self.remember_browser_selection(schema, Some(table));
+        cx.notify();
+    }
+
+    fn connected_profile(&self) -> Option<&ConnectionProfile> {
+        let profile_id = self.connection.profile_id?;
+        self.profiles
+            .iter()
+            .find(|profile| profile.id == profile_id)
+    }
+
+    fn remember_browser_selection(&mut self, schema: String, table: Option<String>) {
+        let Some(profile_id) = self.connection.profile_id else {
+            return;
+        };
+        let Some(profile) = self.profiles.iter_mut().find(|p| p.id == profile_id) else {
+            return;
+        };
+        if profile.last_schema.as_ref() == Some(&schema) && profile.last_table == table {
+            return;
+        }
+        profile.last_schema = Some(schema);
+        profile.last_table = table;
+        if let Err(err) = self.profile_store.save(&self.profiles) {
+            tracing::warn!("Failed to remember schema browser selection: {err:?}");
+        }
+    }
+
+    fn toggle_table_menu(&mut self, table: String, cx: &mut Context<Self>) {
+        if self.schema_browser.table_menu.as_deref() == Some(table.as_str()) {
+            self.schema_browser.table_menu = None;
+        } else {
+            if self.schema_browser.selected_table.as_deref() != Some(table.as_str()) {
+                self.select_table(table.clone(), cx);
+            }
+            self.schema_browser.table_menu = Some(table);
+        }
+        cx.notify();
+    }
+
+    fn refresh_schemas(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        self.schema_browser.schemas_loading = true;
+        session.load_schemas();
+        cx.notify();
+    }
+
+    fn refresh_tables(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        let Some(schema) = self.schema_browser.selected_schema.clone() else {
+            return;
+        };
+        self.schema_browser.tables_loading = true;
+        self.schema_browser.views_loading = true;
+        session.load_tables(schema.clone());
+        session.load_views(schema);
+        cx.notify();
+    }
+
+    fn refresh_columns(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        let (Some(schema), Some(table)) = (
+            self.schema_browser.selected_schema.clone(),
+            self.schema_browser.selected_table.clone(),
+        ) else {
+            return;
+        };
+        self.schema_browser.columns_loading = true;
+        self.schema_browser.column_cursor = None;
+        session.load_columns(schema.clone(), table.clone());
+        session.load_indexes(schema.clone(), table.clone());
+        session.load_foreign_keys(schema, table);
+        cx.notify();
+    }
+
+    fn refresh_schema_browser(&mut self, cx: &mut Context<Self>) {
+        self.refresh_schemas(cx);
+        self.refresh_tables(cx);
+        self.refresh_columns(cx);
+        if let (Some(session), Some(schema), Some(table)) = (
+            self.connection.session.as_ref(),
+            self.schema_browser.selected_schema.clone(),
+            self.schema_browser.selected_table.clone(),
+        ) {
+            self.schema_browser.preview_loading = true;
+            session.preview_table(schema, table, db::PREVIEW_LIMIT);
+        }
+    }
+
+    // The editor tab takes focus so typing goes straight into the SQL input;
+    // leaving it hands focus back to the app so shortcuts keep dispatching.
+    fn show_tab(&mut self, tab: MainTab, window: &mut Window, cx: &mut Context<Self>) {
+        match tab {
+            MainTab::SqlEditor => self.focus_sql_editor(window, cx),
+            MainTab::SchemaBrowser | MainTab::SchemaDiff => {
+                self.active_tab = tab;
+                window.focus(&self.focus_handle);
+                cx.notify();
+            }
+            MainTab::Sessions => {
+                self.active_tab = tab;
+                window.focus(&self.focus_handle);
+                self.refresh_server_sessions(cx);
+                cx.notify();
+            }
+        }
+    }
+
+    fn focus_sql_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.active_tab = MainTab::SqlEditor;
+        window.focus(&self.sql_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn focus_browser_list(&mut self, list: BrowserList, window: &mut Window) {
+        self.schema_browser.focused_list = list;
+        window.focus(&self.schema_browser_focus);
+    }
+
+    fn move_browser_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        match self.schema_browser.focused_list {
+            BrowserList::Schemas => {
+                let names = self
+                    .schema_browser
+                    .visible_schemas()
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let current = self.schema_browser.selected_schema.as_deref();
+                if let Some(schema) = step_selection(&names, current, delta) {
+                    self.select_schema(schema, cx);
+                }
+            }
+            BrowserList::Tables => {
+                let names = self
+                    .schema_browser
+                    .visible_tables()
+                    .into_iter()
+                    .cloned()
+                    .chain(
+                        self.schema_browser
+                            .visible_views()
+                            .into_iter()
+                            .map(|view| view.name.clone()),
+                    )
+                    .collect::<Vec<_>>();
+                let current = self.schema_browser.selected_table.as_deref();
+                if let Some(table) = step_selection(&names, current, delta) {
+                    self.select_table(table, cx);
+                }
+            }
+            BrowserList::Columns => {
+                let len = self.schema_browser.columns.len();
+                if len == 0 {
+                    return;
+                }
+                self.schema_browser.column_cursor = Some(match self.schema_browser.column_cursor {
+                    Some(idx) => idx.saturating_add_signed(delta).min(len - 1),
+                    None if delta < 0 => len - 1,
+                    None => 0,
+                });
+                cx.notify();
+            }
+        }
+    }
+
+    fn confirm_browser_selection(&mut self, cx: &mut Context<Self>) {
+        match self.schema_browser.focused_list {
+            BrowserList::Schemas => {
+                if self.schema_browser.selected_schema.is_some() {
+                    self.schema_browser.focused_list = BrowserList::Tables;
+                }
+            }
+            BrowserList::Tables => {
+                if self.schema_browser.selected_table.is_some() {
+                    self.schema_browser.focused_list = BrowserList::Columns;
+                }
+            }
+            BrowserList::Columns => {
+                if let Some(column) = self
+                    .schema_browser
+                    .column_cursor
+                    .and_then(|idx| self.schema_browser.columns.get(idx))
+                {
+                    let name = column.name.clone();
+                    self.copy_to_clipboard(name, cx);
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    fn generate_select(&mut self, table: String, cx: &mut Context<Self>) {
+        let Some(schema) = self.schema_browser.selected_schema.clone() else {
+            return;
+        };
+        let kind = self.connection.kind;
+        let columns = if self.schema_browser.selected_table.as_deref() == Some(table.as_str())
+            && !self.schema_browser.columns.is_empty()
+        {
+            self.schema_browser
+                .columns
+                .iter()
+                .map(|column| quote_identifier(kind, &column.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            "*".to_string()
+        };
+        let sql = format!(
+            "SELECT {columns} FROM {} LIMIT 100;",
+            qualified_table_name(kind, &schema, &table)
+        );
+        self.sql_input.update(cx, |input, cx| {
+            input.set_text(&sql);
+            cx.notify();
+        });
+        self.schema_browser.table_menu = None;
+        self.active_tab = MainTab::SqlEditor;
+        cx.notify();
+    }
+
+    fn view_table_ddl(&mut self, table: String, cx: &mut Context<Self>) {
+        let Some(schema) = self.schema_browser.selected_schema.clone() else {
+            return;
+        };
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        self.schema_browser.table_menu = None;
+        self.schema_browser.ddl = None;
+        self.schema_browser.ddl_loading = true;
+        session.load_create_table(schema, table);
+        cx.notify();
+    }
+
+    fn load_column_stats(&mut self, column: String, sample: bool, cx: &mut Context<Self>) {
+        let (Some(schema), Some(table)) = (
+            self.schema_browser.selected_schema.clone(),
+            self.schema_browser.selected_table.clone(),
+        ) else {
+            return;
+        };
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        self.schema_browser.column_stats = None;
+        self.schema_browser.column_stats_loading = true;
+        session.load_column_stats(schema, table, column, sample);
+        cx.notify();
+    }
+
+    fn open_ddl_in_editor(&mut self, cx: &mut Context<Self>) {
+        let Some(ddl) = self.schema_browser.ddl.clone() else {
+            return;
+        };
+        self.sql_input.update(cx, |input, cx| {
+            input.set_text(&ddl);
+            cx.notify();
+        });
+        self.active_tab = MainTab::SqlEditor;
+        cx.notify();
+    }
+
+    fn open_preview_in_editor(&mut self, cx: &mut Context<Self>) {
+        let (Some(schema), Some(table)) = (
+            self.schema_browser.selected_schema.as_ref(),
+            self.schema_browser.selected_table.as_ref(),
+        ) else {
+            return;
+        };
+        let limit =
+            parse_row_limit(&self.row_limit_input.read(cx).text()).unwrap_or(DEFAULT_ROW_LIMIT);
+        let sql = format!(
+            "SELECT * FROM {} LIMIT {limit};",
+            qualified_table_name(self.connection.kind, schema, table)
+        );
+        self.sql_input.update(cx, |input, cx| {
+            input.set_text(&sql);
+            cx.notify();
+        });
+        self.active_tab = MainTab::SqlEditor;
+        cx.notify();
+    }
+
+    fn copy_table_template(&mut self, table: String, update: bool, cx: &mut Context<Self>) {
+        let Some(schema) = self.schema_browser.selected_schema.clone() else {
+            return;
+        };
+        if self.schema_browser.selected_table.as_deref() != Some(table.as_str())
+            || self.schema_browser.columns.is_empty()
+        {
+            return;
+        }
+        let kind = self.connection.kind;
+        let columns = &self.schema_browser.columns;
+        let template = if update {
+            let keys = self
+                .schema_browser
+                .indexes
+                .iter()
+                .find(|index| index.primary)
+                .map(|index| index.columns.clone())
+                .unwrap_or_default();
+            update_template(kind, &schema, &table, columns, &keys)
+        } else {
+            insert_template(kind, &schema, &table, columns)
+        };
+        self.schema_browser.table_menu = None;
+        self.copy_to_clipboard(template, cx);
+        cx.notify();
+    }
+
+    fn open_table(&mut self, schema: String, table: String, cx: &mut Context<Self>) {
+        self.schema_filter_input
+            .update(cx, |input, _| input.clear());
+        self.table_filter_input.update(cx, |input, _| input.clear());
+        self.schema_browser.set_schema_filter(String::new());
+        self.schema_browser.set_table_filter(String::new());
+        if self.schema_browser.selected_schema.as_deref() != Some(schema.as_str()) {
+            self.select_schema(schema, cx);
+        }
+        self.select_table(table, cx);
+    }
+}
+
+impl Render for DbMiruApp {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = *cx.theme();
+        self.poll_events(cx);
+        window.set_window_title("DbMiru");
+        if self.connection.is_busy() {
+            self.ensure_connecting_indicator(window, cx);
+        } else if self.connecting_indicator_active {
+            self.stop_connecting_indicator();
+        }
+        if self.compare_run.is_running() {
+            self.ensure_compare_ticker(window, cx);
+        }
+        if self.schema_diff.is_running() {
+            self.ensure_schema_diff_ticker(window, cx);
+        }
+        if self.query_state.status == QueryStatus::Running {
+            self.ensure_query_timer(window, cx);
+        }
+        div()
+            .track_focus(&self.focus_handle)
+            .key_context("DbMiru")
+            .capture_key_down(
+                cx.listener(|this, _: &KeyDownEvent, _window, cx| this.note_interaction(cx)),
+            )
+            .capture_any_mouse_down(
+                cx.listener(|this, _: &MouseDownEvent, _window, cx| this.note_interaction(cx)),
+            )
+            .on_action(cx.listener(|this, _: &ShowSchemaBrowser, window, cx| {
+                this.show_tab(MainTab::SchemaBrowser, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ShowSqlEditor, window, cx| {
+                this.show_tab(MainTab::SqlEditor, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ShowSessions, window, cx| {
+                this.show_tab(MainTab::Sessions, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ShowSchemaDiff, window, cx| {
+                this.show_tab(MainTab::SchemaDiff, window, cx)
+            }))
+            .on_action(
+                cx.listener(|this, _: &FocusSqlEditor, window, cx| {
+                    this.focus_sql_editor(window, cx)
+                }),
+            )
+            .on_action(cx.listener(|this, _: &ToggleCommandPalette, window, cx| {
+                this.toggle_command_palette(window, cx)
+            }))
+            .flex()
+            .gap_2()
+            .font_family(APP_FONT_FAMILY)
+            .size_full()
+            .bg(theme.canvas)
+            .text_color(theme.text)
+            .p_6()
+            .when_some(self.panel_resize, |node, resize| {
+                if resize.splitter.is_vertical() {
+                    node.cursor_row_resize()
+                } else {
+                    node.cursor_col_resize()
+                }
+            })
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _window, cx| {
+                this.update_panel_resize(event, cx)
+            }))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, _cx| this.finish_panel_resize()),
+            )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, _cx| this.finish_panel_resize()),
+            )
+            .child(self.render_sidebar(cx))
+            .child(self.render_splitter(&theme, Splitter::Sidebar, cx))
+            .child(self.render_workspace(window, cx))
+            .children(self.render_toast(&theme, cx))
+            .children(self.render_command_palette(&theme, cx))
+    }
+}
+
+impl DbMiruApp {
+    fn render_sidebar(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let selected = self.selected_profile;
+        let grouped = self.profiles.iter().any(|profile| profile.group.is_some());
+        let mut groups: Vec<(Option<String>, Vec<AnyElement>)> = Vec::new();
+        for (index, profile) in self.profiles.iter().cloned().enumerate() {
+            let is_selected = selected == Some(profile.id);
+            let profile_id = profile.id;
+            let name = profile.name.clone();
+            let can_move_up = self.group_neighbor(index, -1).is_some();
+            let can_move_down = self.group_neighbor(index, 1).is_some();
+
+            let reorder_controls = if is_selected {
+                div()
+                    .flex()
+                    .flex_col()
+                    .items_end()
+                    .gap_1()
+                    .flex_shrink_0()
+                    .child({
+                        let mut node = div()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .w(px(20.))
+                            .h(px(18.))
+                            .rounded_md()
+                            .bg(theme.panel_highlight)
                             .border_1()
-                            .border_color(rgb(COLOR_BORDER))
+                            .border_color(theme.border)
                             .text_xs()
                             .child("↑");
                         if can_move_up {
                             node = node
                                 .cursor_pointer()
-                                .hover(|style| style.bg(rgb(COLOR_PANEL_MUTED)))
+                                .hover(|style| style.bg(theme.panel_muted))
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.move_profile_by(profile_id, -1, cx)
+                                    }),
+                                );
+                        } else {
+                            node = node.text_color(theme.text_muted);
+                        }
+                        node
+                    })
+                    .child({
+                        let mut node = div()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .w(px(20.))
+                            .h(px(18.))
+                            .rounded_md()
+                            .bg(theme.panel_highlight)
+                            .border_1()
+                            .border_color(theme.border)
+                            .text_xs()
+                            .child("↓");
+                        if can_move_down {
+                            node = node
+                                .cursor_pointer()
+                                .hover(|style| style.bg(theme.panel_muted))
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.move_profile_by(profile_id, 1, cx)
+                                    }),
+                                );
+                        } else {
+                            node = node.text_color(theme.text_muted);
+                        }
+                        node
+                    })
+            } else {
+                div()
+            };
+            let dragged = DraggedProfile {
+                id: profile_id,
+                name: name.clone(),
+            };
+            let item = div()
+                .id(("profile_item", index))
+                .flex()
+                .flex_col()
+                .gap_2()
+                .p_3()
+                .rounded_lg()
+                .bg(if is_selected {
+                    theme.panel_highlight
+                } else {
+                    theme.panel_muted
+                })
+                .border_1()
+                .border_color(if is_selected {
+                    theme.accent
+                } else {
+                    theme.border
+                })
+                .cursor_pointer()
+                .child(
+                    div()
+                        .flex()
+                        .justify_between()
+                        .items_start()
+                        .gap_2()
+                        .child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap_2()
+                                .when_some(profile.color, |node, color| {
+                                    node.child(profile_color_dot(&theme, color))
+                                })
+                                .child(div().text_sm().text_color(theme.text_bright).child(name)),
+                        )
+                        .child(reorder_controls),
+                )
+                .child(div().text_xs().text_color(theme.text_muted).child(format!(
+                    "{}@{}:{}",
+                    profile.username, profile.host, profile.port
+                )))
+                .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                    this.select_profile(profile_id, window, cx)
+                }))
+                .on_drag(dragged, |dragged, _offset, _window, cx| {
+                    cx.new(|_| dragged.clone())
+                })
+                .drag_over::<DraggedProfile>(move |style, _, _, _| {
+                    style.border_color(theme.accent).bg(theme.accent_soft)
+                })
+                .on_drop(
+                    cx.listener(move |this, dragged: &DraggedProfile, _window, cx| {
+                        this.drop_profile_on(dragged.id, profile_id, cx)
+                    }),
+                );
+            match groups.iter_mut().find(|(group, _)| *group == profile.group) {
+                Some((_, items)) => items.push(item.into_any_element()),
+                None => groups.push((profile.group.clone(), vec![item.into_any_element()])),
+            }
+        }
+
+        let mut profile_items = Vec::new();
+        for (group, items) in groups {
+            if !grouped {
+                profile_items.extend(items);
+                continue;
+            }
+            let collapsed = self
+                .settings
+                .collapsed_groups
+                .contains(&group.clone().unwrap_or_default());
+            let label = format!(
+                "{} {} ({})",
+                if collapsed { "▸" } else { "▾" },
+                group.as_deref().unwrap_or("Profiles"),
+                items.len()
+            );
+            profile_items.push(
+                div()
+                    .px_1()
+                    .pt_1()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .hover(|style| style.text_color(theme.text_bright))
+                    .cursor_pointer()
+                    .child(label)
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            this.toggle_profile_group(group.clone(), cx)
+                        }),
+                    )
+                    .into_any_element(),
+            );
+            if !collapsed {
+                profile_items.extend(items);
+            }
+        }
+
+        let form = self.render_profile_form(cx);
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_shrink_0()
+            .w(px(self.settings.sidebar_width))
+            .p_5()
+            .gap_4()
+            .bg(theme.panel)
+            .border_1()
+            .border_color(theme.border)
+            .rounded_lg()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(theme.text_bright)
+                            .child("Connection Profiles"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .px_3()
+                            .py_1()
+                            .rounded_full()
+                            .bg(theme.accent)
+                            .hover(|style| style.bg(theme.accent_soft))
+                            .text_color(theme.on_accent)
+                            .cursor_pointer()
+                            .child("New")
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.begin_create_profile(cx)
+                                }),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .max_h(px(260.))
+                    .p_1()
+                    .rounded_lg()
+                    .bg(theme.panel_muted)
+                    .border_1()
+                    .border_color(theme.border)
+                    .overflow_y_scroll()
+                    .children(profile_items),
+            )
+            .child(form)
+            .child(self.render_profile_actions(cx))
+    }
+
+    fn render_profile_actions(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        div()
+            .flex()
+            .gap_2()
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .rounded_full()
+                    .bg(theme.panel_highlight)
+                    .border_1()
+                    .border_color(theme.accent)
+                    .text_sm()
+                    .child("Edit")
+                    .cursor_pointer()
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.begin_edit_profile(cx)
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .rounded_full()
+                    .bg(theme.panel_highlight)
+                    .border_1()
+                    .border_color(theme.border)
+                    .text_sm()
+                    .child("Duplicate")
+                    .cursor_pointer()
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.duplicate_selected_profile(cx)
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .rounded_full()
+                    .bg(theme.danger_surface)
+                    .border_1()
+                    .border_color(theme.danger)
+                    .text_sm()
+                    .text_color(theme.danger_soft)
+                    .hover(|style| style.bg(theme.danger_hover))
+                    .child("Delete")
+                    .cursor_pointer()
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.delete_selected_profile(cx)
+                        }),
+                    ),
+            )
+            .child(div().flex_1())
+            .child(result_action_button(&theme, "Import").on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, cx| this.import_profiles(cx)),
+            ))
+            .child(result_action_button(&theme, "Export").on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, cx| this.export_profiles(cx)),
+            ))
+    }
+
+    fn render_profile_form(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let form_visible = !matches!(self.profile_form_mode, ProfileFormMode::Hidden);
+        let notice = self.profile_notice.clone();
+        let remember_password = self.profile_form.remember_password;
+
+        if !form_visible {
+            let mut node = div();
+            if let Some(text) = notice {
+                node = node.child(div().text_xs().text_color(theme.warning).child(text));
+            }
+            return node;
+        }
+        let current_group = self.profile_form.group.read(cx).text();
+        let mut existing_groups: Vec<String> = Vec::new();
+        for group in self
+            .profiles
+            .iter()
+            .filter_map(|profile| profile.group.clone())
+        {
+            if !existing_groups.contains(&group) {
+                existing_groups.push(group);
+            }
+        }
+
+        let mut node = div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child("Profile Details"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(self.profile_form.connection_string.clone()),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_2()
+                            .bg(theme.panel_highlight)
+                            .rounded_full()
+                            .text_sm()
+                            .child("Apply")
+                            .cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.apply_connection_string(cx)
+                                }),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.text_muted).child("Type"))
+                    .children(ProfileKind::ALL.into_iter().map(|kind| {
+                        selector_pill(&theme, kind.label(), self.profile_form.kind == kind)
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    this.set_profile_kind(kind, cx)
+                                }),
+                            )
+                    })),
+            )
+            .child(self.profile_form.name.clone())
+            .child(self.profile_form.group.clone())
+            .when(!existing_groups.is_empty(), |node| {
+                node.child(div().flex().flex_wrap().gap_1().children(
+                    existing_groups.into_iter().map(|group| {
+                        let is_active = current_group.trim() == group;
+                        selector_pill(&theme, group.clone(), is_active).on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                this.profile_form
+                                    .group
+                                    .update(cx, |input, _| input.set_text(&group));
+                                cx.notify();
+                            }),
+                        )
+                    }),
+                ))
+            })
+            .child(self.profile_form.host.clone())
+            .child(self.profile_form.port.clone())
+            .child(self.profile_form.connect_timeout.clone())
+            .child(self.profile_form.row_limit.clone())
+            .child(self.profile_form.keepalive.clone())
+            .child(self.profile_form.statement_timeout.clone())
+            .child(self.profile_form.connect_retries.clone())
+            .child(self.profile_form.retry_backoff.clone())
+            .child(self.profile_form.database.clone())
+            .child(self.profile_form.username.clone())
+            .child(self.profile_form.ssh_host.clone())
+            .child(self.profile_form.ssh_port.clone())
+            .child(self.profile_form.ssh_user.clone())
+            .child(self.profile_form.ssh_key.clone())
+            .child(
+                form_checkbox(&theme, "Remember password", remember_password).on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.profile_form.remember_password = !this.profile_form.remember_password;
+                        cx.notify();
+                    }),
+                ),
+            )
+            .when(remember_password, |node| {
+                node.child(self.profile_form.password.clone())
+            })
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.text_muted).child("SSL"))
+                    .children(SslMode::ALL.into_iter().map(|mode| {
+                        selector_pill(&theme, mode.label(), self.profile_form.ssl_mode == mode)
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    this.profile_form.ssl_mode = mode;
+                                    cx.notify();
+                                }),
+                            )
+                    })),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.text_muted).child("Color"))
+                    .child(
+                        selector_pill(&theme, "None", self.profile_form.color.is_none())
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.profile_form.color = None;
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                    .children(ProfileColor::ALL.into_iter().map(|color| {
+                        selector_pill(
+                            &theme,
+                            color.label(),
+                            self.profile_form.color == Some(color),
+                        )
+                        .flex()
+                        .items_center()
+                        .gap_1()
+                        .child(profile_color_dot(&theme, color))
+                        .on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                this.profile_form.color = Some(color);
+                                cx.notify();
+                            }),
+                        )
+                    })),
+            )
+            .child(
+                form_checkbox(
+                    &theme,
+                    "Reconnect automatically",
+                    self.profile_form.auto_reconnect,
+                )
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.profile_form.auto_reconnect = !this.profile_form.auto_reconnect;
+                        cx.notify();
+                    }),
+                ),
+            )
+            .child(
+                form_checkbox(&theme, "Read-only", self.profile_form.read_only).on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.profile_form.read_only = !this.profile_form.read_only;
+                        cx.notify();
+                    }),
+                ),
+            )
+            .child(
+                form_checkbox(
+                    &theme,
+                    "Confirm destructive statements",
+                    self.profile_form.confirm_destructive,
+                )
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.profile_form.confirm_destructive =
+                            !this.profile_form.confirm_destructive;
+                        cx.notify();
+                    }),
+                ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .px_3()
+                            .py_2()
+                            .bg(theme.accent)
+                            .hover(|style| style.bg(theme.accent_soft))
+                            .text_color(theme.on_accent)
+                            .rounded_full()
+                            .text_sm()
+                            .child("Save")
+                            .cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.save_profile(cx)
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_2()
+                            .bg(theme.panel_highlight)
+                            .rounded_full()
+                            .text_sm()
+                            .child("Test Connection")
+                            .cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.test_connection(cx)
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_2()
+                            .bg(theme.panel_highlight)
+                            .rounded_full()
+                            .text_sm()
+                            .child("Cancel")
+                            .cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.cancel_profile_form(cx)
+                                }),
+                            ),
+                    ),
+            );
+
+        if let Some(text) = notice {
+            node = node.child(div().text_xs().text_color(theme.warning).child(text));
+        }
+
+        node
+    }
+
+    fn render_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl Element {
+        div()
+            .flex()
+            .flex_col()
+            .flex_grow()
+            .min_w(px(0.))
+            .h_full()
+            .overflow_y_scroll()
+            .id("workspace_scroll")
+            .p_5()
+            .gap_5()
+            .child(self.render_connection_panel(cx))
+            .child(self.render_main_tabs(window, cx))
+            .child(self.render_activity_log(cx))
+    }
+
+    fn render_activity_log(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let arrow = if self.activity_log_open { "▾" } else { "▸" };
+        let header = div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(
+                div()
+                    .flex_1()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .cursor_pointer()
+                    .child(format!("{arrow} Log ({})", self.activity_log.len()))
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.activity_log_open = !this.activity_log_open;
+                            cx.notify();
+                        }),
+                    ),
+            )
+            .when(self.activity_log_open, |node| {
+                node.child(result_action_button(&theme, "Copy").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| this.copy_activity_log(cx)),
+                ))
+                .child(result_action_button(&theme, "Clear").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.activity_log.clear();
+                        cx.notify();
+                    }),
+                ))
+            });
+
+        let panel = div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(header);
+        if !self.activity_log_open {
+            return panel;
+        }
+
+        let list = if self.activity_log.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Nothing has happened yet.")
+                .into_any()
+        } else {
+            div()
+                .id("activity-log")
+                .flex()
+                .flex_col()
+                .gap_1()
+                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
+                .overflow_y_scroll()
+                .children(self.activity_log.iter().rev().map(|entry| {
+                    let color = match entry.level {
+                        ActivityLevel::Info => theme.text,
+                        ActivityLevel::Warning => theme.warning,
+                        ActivityLevel::Error => theme.danger_soft,
+                    };
+                    div()
+                        .flex()
+                        .gap_3()
+                        .text_xs()
+                        .child(
+                            div()
+                                .flex_shrink_0()
+                                .text_color(theme.text_muted)
+                                .child(entry.at.format("%H:%M:%S").to_string()),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w(px(0.))
+                                .text_color(color)
+                                .child(entry.message.clone()),
+                        )
+                }))
+                .into_any()
+        };
+        panel.child(list)
+    }
+
+    fn render_connection_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let dot_count = if self.connection.is_busy() {
+            self.connecting_indicator as usize
+        } else {
+            0
+        };
+        let status_text = self.connection.status_text(dot_count);
+        let error = self.connection.last_error.clone();
+        let is_connected = self.connection.is_connected();
+        let read_only = is_connected && self.connection.read_only;
+        let server_info = self
+            .connection
+            .server_info
+            .as_ref()
+            .filter(|_| is_connected)
+            .map(|info| {
+                let database = if info.database.is_empty() {
+                    "(no database)"
+                } else {
+                    info.database.as_str()
+                };
+                format!("{database} as {} · {}", info.user, info.version)
+            });
+        let profile_color = self
+            .connected_profile()
+            .filter(|_| is_connected)
+            .and_then(|profile| profile.color);
+        let can_reconnect = self.connection.dropped
+            && self.connection.profile_id.is_some()
+            && matches!(self.connection.status, ConnectionStatus::Disconnected);
+        let idle_minutes = self.settings.idle_disconnect_minutes;
+        let idle_label = if idle_minutes > 0 {
+            format!("Disconnect when idle: {idle_minutes} min")
+        } else {
+            "Disconnect when idle: off".to_string()
+        };
+        let idle_remaining = self
+            .idle_timeout()
+            .filter(|_| is_connected && self.idle_warned)
+            .map(|timeout| timeout.saturating_sub(self.last_interaction.elapsed()));
+        let button_label = if is_connected {
+            "Disconnect"
+        } else {
+            "Connect"
+        };
+
+        let mut panel = div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_3()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .when_some(profile_color, |node, color| {
+                node.border_2().border_color(theme.profile_color(color))
+            })
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .flex_grow()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .when_some(profile_color, |node, color| {
+                                node.child(profile_color_dot(&theme, color))
+                            })
+                            .child(div().text_sm().text_color(theme.text_muted).child("Status")),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(div().text_xl().child(status_text))
+                            .when(read_only, |node| node.child(badge(&theme, "🔒 Read-only"))),
+                    )
+                    .when_some(server_info, |node, server_info| {
+                        node.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child(server_info),
+                        )
+                    })
+                    .child(
+                        div().flex().items_center().gap_2().child(
+                            selector_pill(&theme, idle_label, idle_minutes > 0).on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.cycle_idle_disconnect(cx)
+                                }),
+                            ),
+                        ),
+                    )
+                    .when_some(idle_remaining, |node, remaining| {
+                        node.child(div().text_xs().text_color(theme.warning).child(format!(
+                            "Disconnecting in {} s due to inactivity; press a key or click to stay connected.",
+                            remaining.as_secs()
+                        )))
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .w(px(220.))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child("Password"),
+                    )
+                    .child(self.password_input.clone()),
+            )
+            .when(can_reconnect, |node| {
+                node.child(
+                    div()
+                        .align_self_end()
+                        .flex()
+                        .flex_shrink_0()
+                        .items_center()
+                        .h(px(36.))
+                        .px_4()
+                        .rounded_lg()
+                        .text_sm()
+                        .bg(theme.panel_highlight)
+                        .border_1()
+                        .border_color(theme.border)
+                        .hover(|style| style.bg(theme.panel))
+                        .cursor_pointer()
+                        .child("Reconnect")
+                        .on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| this.reconnect(cx)),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .align_self_end()
+                    .flex()
+                    .flex_shrink_0()
+                    .items_center()
+                    .justify_center()
+                    .h(px(36.))
+                    .px_4()
+                    .rounded_lg()
+                    .text_sm()
+                    .text_color(if is_connected {
+                        theme.danger_soft
+                    } else {
+                        theme.on_accent
+                    })
+                    .bg(if is_connected {
+                        theme.danger_surface
+                    } else {
+                        theme.accent
+                    })
+                    .border_1()
+                    .border_color(if is_connected {
+                        theme.danger
+                    } else {
+                        theme.accent_soft
+                    })
+                    .hover(|style| {
+                        if is_connected {
+                            style.bg(theme.danger_hover)
+                        } else {
+                            style.bg(theme.accent_soft)
+                        }
+                    })
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(connection_action_icon(&theme, &self.connection.status))
+                            .child(button_label),
+                    )
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            if this.connection.is_connected() {
+                                this.disconnect(cx);
+                            } else {
+                                this.connect_selected(cx);
+                            }
+                        }),
+                    ),
+            );
+
+        if let Some(text) = error {
+            panel = panel.child(error_banner(&theme, &text).align_self_end());
+        }
+
+        panel
+    }
+
+    fn render_main_tabs(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let tabs = [
+            (MainTab::SchemaBrowser, "Schema Browser"),
+            (MainTab::SqlEditor, "SQL Editor"),
+            (MainTab::Sessions, "Sessions"),
+            (MainTab::SchemaDiff, "Schema Diff"),
+        ];
+        let mut tab_buttons = Vec::new();
+        for (tab, label) in tabs {
+            let is_active = self.active_tab == tab;
+            let tab_value = tab;
+            tab_buttons.push(
+                div()
+                    .px_3()
+                    .py_2()
+                    .rounded_full()
+                    .text_sm()
+                    .text_color(if is_active {
+                        theme.on_accent
+                    } else {
+                        theme.text_muted
+                    })
+                    .bg(if is_active {
+                        theme.accent
+                    } else {
+                        theme.panel_muted
+                    })
+                    .border_1()
+                    .border_color(if is_active {
+                        theme.accent_soft
+                    } else {
+                        theme.border
+                    })
+                    .hover(|style| {
+                        if is_active {
+                            style.bg(theme.accent_hover)
+                        } else {
+                            style.bg(theme.panel_highlight)
+                        }
+                    })
+                    .cursor_pointer()
+                    .child(label)
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, window, cx| {
+                            this.show_tab(tab_value, window, cx)
+                        }),
+                    ),
+            );
+        }
+
+        let content: AnyElement = match self.active_tab {
+            MainTab::SchemaBrowser => self.render_schema_browser(window, cx).into_any(),
+            MainTab::SchemaDiff => self.render_schema_diff_panel(cx).into_any(),
+            MainTab::Sessions => {
+                let supports_listen = self
+                    .connected_profile()
+                    .is_some_and(|profile| profile.kind == ProfileKind::Postgres);
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_6()
+                    .child(self.render_sessions_panel(cx))
+                    .children(supports_listen.then(|| self.render_notifications_panel(cx)))
+                    .into_any()
+            }
+            MainTab::SqlEditor => div()
+                .flex()
+                .gap_4()
+                .child(self.render_snippets_panel(cx))
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .flex_1()
+                        .min_w(px(0.))
+                        .gap_4()
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap_1()
+                                .child(self.render_editor_panel(cx))
+                                .when(!self.results_maximized, |node| {
+                                    node.child(self.render_splitter(&theme, Splitter::Editor, cx))
+                                })
+                                .child(self.render_results_panel(window, cx)),
+                        )
+                        .when(!self.compare_run.targets.is_empty(), |node| {
+                            node.child(self.render_compare_panel(cx))
+                        }),
+                )
+                .into_any(),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.panel)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_2()
+                    .child(div().flex().gap_2().children(tab_buttons))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(
+                                editor_button(
+                                    &theme,
+                                    match self.settings.row_density {
+                                        RowDensity::Comfortable => "Compact rows",
+                                        RowDensity::Compact => "Comfortable rows",
+                                    },
+                                )
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                        this.toggle_row_density(cx);
+                                    }),
+                                ),
+                            )
+                            .child(
+                                editor_button(
+                                    &theme,
+                                    match self.settings.theme {
+                                        ThemeMode::Dark => "Light theme",
+                                        ThemeMode::Light => "Dark theme",
+                                    },
+                                )
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                        this.toggle_theme(cx);
+                                    }),
+                                ),
+                            ),
+                    ),
+            )
+            .child(content)
+    }
+
+    fn toggle_auto_close_brackets(&mut self, cx: &mut Context<Self>) {
+        self.settings.auto_close_brackets = !self.settings.auto_close_brackets;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        let enabled = self.settings.auto_close_brackets;
+        self.sql_input
+            .update(cx, |input, _| input.set_auto_pair(enabled));
+        cx.notify();
+    }
+
+    fn toggle_table_sizes(&mut self, cx: &mut Context<Self>) {
+        self.settings.show_table_sizes = !self.settings.show_table_sizes;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        self.schema_browser.table_sizes.clear();
+        if self.settings.show_table_sizes
+            && let Some(session) = self.connection.session.as_ref()
+            && let Some(schema) = self.schema_browser.selected_schema.clone()
+        {
+            session.load_table_sizes(schema);
+        }
+        cx.notify();
+    }
+
+    fn refresh_server_sessions(&mut self, cx: &mut Context<Self>) {
+        if let Some(session) = self.connection.session.as_ref() {
+            self.server_sessions.loading = true;
+            session.load_activity();
+            cx.notify();
+        }
+    }
+
+    // Each load schedules the next one; timers left over from an earlier load
+    // see a newer `loaded_at` and do nothing.
+    fn schedule_sessions_refresh(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(SESSIONS_REFRESH_INTERVAL)
+                .await;
+            this.update(cx, |this, cx| {
+                let state = &this.server_sessions;
+                if this.active_tab == MainTab::Sessions
+                    && !state.paused
+                    && !state.loading
+                    && state
+                        .loaded_at
+                        .is_some_and(|at| at.elapsed() >= SESSIONS_REFRESH_INTERVAL)
+                {
+                    this.refresh_server_sessions(cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn listen_channel(&mut self, cx: &mut Context<Self>) {
+        let channel = self.listen_channel_input.read(cx).text().trim().to_string();
+        if channel.is_empty() || self.notifications.channels.contains(&channel) {
+            return;
+        }
+        if let Some(session) = self.connection.session.as_ref() {
+            session.listen(channel);
+            self.listen_channel_input
+                .update(cx, |input, _| input.clear());
+            cx.notify();
+        }
+    }
+
+    fn unlisten_channel(&mut self, channel: String, cx: &mut Context<Self>) {
+        if let Some(session) = self.connection.session.as_ref() {
+            session.unlisten(channel);
+            cx.notify();
+        }
+    }
+
+    // Events are only drained while rendering; notifications arrive without any
+    // UI activity, so wake up to show them for as long as a channel is listened on.
+    fn watch_notifications(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(NOTIFICATION_POLL_INTERVAL)
+                    .await;
+                let listening = this
+                    .update(cx, |this, cx| {
+                        if !this.event_rx.is_empty() {
+                            cx.notify();
+                        }
+                        !this.notifications.channels.is_empty()
+                    })
+                    .unwrap_or(false);
+                if !listening {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn toggle_sessions_paused(&mut self, cx: &mut Context<Self>) {
+        self.server_sessions.paused = !self.server_sessions.paused;
+        if !self.server_sessions.paused {
+            self.refresh_server_sessions(cx);
+        }
+        cx.notify();
+    }
+
+    fn terminate_server_session(&mut self, pid: i64, cx: &mut Context<Self>) {
+        self.server_sessions.confirm_terminate = None;
+        if let Some(session) = self.connection.session.as_ref() {
+            session.terminate_backend(pid);
+        }
+        cx.notify();
+    }
+
+    fn toggle_results_maximized(&mut self, cx: &mut Context<Self>) {
+        self.results_maximized = !self.results_maximized;
+        cx.notify();
+    }
+
+    fn editor_height(&self) -> f32 {
+        if self.results_maximized {
+            EDITOR_COLLAPSED_HEIGHT
+        } else {
+            self.settings.editor_height
+        }
+    }
+
+    // Result rows take whatever height the window has left below the editor.
+    fn results_body_height(&self, window: &Window) -> Pixels {
+        let available =
+            window.viewport_size().height - px(self.editor_height() + SQL_TAB_CHROME_HEIGHT);
+        available.max(px(RESULTS_MIN_HEIGHT))
+    }
+
+    fn toggle_type_colors(&mut self, cx: &mut Context<Self>) {
+        self.settings.type_colors = !self.settings.type_colors;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        cx.notify();
+    }
+
+    fn toggle_wrap_cells(&mut self, cx: &mut Context<Self>) {
+        self.settings.wrap_cells = !self.settings.wrap_cells;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        cx.notify();
+    }
+
+    fn toggle_thousands_separators(&mut self, cx: &mut Context<Self>) {
+        self.settings.thousands_separators = !self.settings.thousands_separators;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        cx.notify();
+    }
+
+    fn cycle_idle_disconnect(&mut self, cx: &mut Context<Self>) {
+        let current = self.settings.idle_disconnect_minutes;
+        self.settings.idle_disconnect_minutes = IDLE_DISCONNECT_PRESETS
+            .into_iter()
+            .find(|minutes| *minutes > current)
+            .unwrap_or(0);
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        self.last_interaction = Instant::now();
+        self.idle_warned = false;
+        self.watch_idle(cx);
+        cx.notify();
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        let minutes = self.settings.idle_disconnect_minutes;
+        (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+    }
+
+    fn note_interaction(&mut self, cx: &mut Context<Self>) {
+        self.last_interaction = Instant::now();
+        if self.idle_warned {
+            self.idle_warned = false;
+            cx.notify();
+        }
+    }
+
+    // Ticks while connected with an idle timeout set; a running query counts as
+    // activity, so long statements are never cut off.
+    fn watch_idle(&mut self, cx: &mut Context<Self>) {
+        if self.idle_watch_active {
+            return;
+        }
+        self.idle_watch_active = true;
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(IDLE_CHECK_INTERVAL).await;
+                let watching = this
+                    .update(cx, |this, cx| {
+                        let Some(timeout) = this
+                            .idle_timeout()
+                            .filter(|_| this.connection.is_connected())
+                        else {
+                            this.idle_watch_active = false;
+                            this.idle_warned = false;
+                            return false;
+                        };
+                        if this.query_state.status == QueryStatus::Running {
+                            this.last_interaction = Instant::now();
+                        }
+                        let idle = this.last_interaction.elapsed();
+                        if idle >= timeout {
+                            this.disconnect_idle(timeout, cx);
+                            this.idle_watch_active = false;
+                            return false;
+                        }
+                        if idle + IDLE_WARNING >= timeout {
+                            if !this.idle_warned {
+                                this.idle_warned = true;
+                                let message = if this.query_state.in_transaction {
+                                    "Disconnecting in 1 minute due to inactivity. \
+                                     Uncommitted changes in the open transaction will be rolled back."
+                                } else {
+                                    "Disconnecting in 1 minute due to inactivity."
+                                };
+                                this.show_toast(ActivityLevel::Warning, message.into(), cx);
+                            }
+                            cx.notify();
+                        }
+                        true
+                    })
+                    .unwrap_or(false);
+                if !watching {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn disconnect_idle(&mut self, timeout: Duration, cx: &mut Context<Self>) {
+        let profile_name = self
+            .connected_profile()
+            .map(|profile| profile.name.clone())
+            .unwrap_or_else(|| "the database".into());
+        let mut message = format!(
+            "Disconnected from {profile_name} after {} minutes of inactivity.",
+            timeout.as_secs() / 60
+        );
+        if self.query_state.in_transaction {
+            message.push_str(" Uncommitted changes in the open transaction were rolled back.");
+        }
+        self.idle_warned = false;
+        self.disconnect(cx);
+        self.password_input.update(cx, |input, _| input.clear());
+        self.log_activity(ActivityLevel::Warning, message.clone());
+        self.show_toast(ActivityLevel::Warning, message, cx);
+    }
+
+    fn toggle_row_density(&mut self, cx: &mut Context<Self>) {
+        self.settings.row_density = match self.settings.row_density {
+            RowDensity::Comfortable => RowDensity::Compact,
+            RowDensity::Compact => RowDensity::Comfortable,
+        };
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        cx.notify();
+    }
+
+    fn toggle_theme(&mut self, cx: &mut Context<Self>) {
+        self.settings.theme = match self.settings.theme {
+            ThemeMode::Dark => ThemeMode::Light,
+            ThemeMode::Light => ThemeMode::Dark,
+        };
+        cx.set_global(Theme::for_mode(self.settings.theme));
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            tracing::warn!("Failed to save settings: {err:?}");
+        }
+        cx.refresh_windows();
+    }
+
+    fn open_csv_import(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(schema), Some(table)) = (
+            self.schema_browser.selected_schema.clone(),
+            self.schema_browser.selected_table.clone(),
+        ) else {
+            return;
+        };
+        self.csv_import = CsvImportState {
+            target: Some((schema, table)),
+            has_header: true,
+            ..CsvImportState::default()
+        };
+        self.csv_input.update(cx, |input, _| input.clear());
+        window.focus(&self.csv_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn sync_csv_import(&mut self, cx: &mut Context<Self>) {
+        if self.csv_import.target.is_none() {
+            return;
+        }
+        let text = self.csv_input.read(cx).text();
+        if text == self.csv_import.source {
+            return;
+        }
+        match export::parse_delimited(&text, export::detect_delimiter(&text)) {
+            Ok(records) => {
+                self.csv_import.records = records;
+                self.csv_import.parse_error = None;
+            }
+            Err(err) => {
+                self.csv_import.records.clear();
+                self.csv_import.parse_error = Some(format!("Cannot parse the CSV: {err}"));
+            }
+        }
+        self.csv_import.source = text;
+        self.csv_import.notice = None;
+        self.csv_import.error = None;
+        self.csv_import.remap(&self.schema_browser.columns);
+    }
+
+    fn run_csv_import(&mut self, cx: &mut Context<Self>) {
+        let columns = &self.schema_browser.columns;
+        if self.csv_import.running || !self.csv_import.problems(columns).is_empty() {
+            return;
+        }
+        if self.connection.read_only {
+            self.csv_import.error = Some(
+                "Read-only connection: the rows were not inserted. Only SELECT and EXPLAIN statements are allowed."
+                    .into(),
+            );
+            cx.notify();
+            return;
+        }
+        let Some((schema, table)) = self.csv_import.target.clone() else {
+            return;
+        };
+        let Some(session) = self.connection.session.as_ref() else {
+            return;
+        };
+        let mapped = self.csv_import.mapped_columns();
+        let names = mapped
+            .iter()
+            .map(|(_, column)| columns[*column].name.clone())
+            .collect();
+        let rows = self
+            .csv_import
+            .data_rows()
+            .iter()
+            .map(|record| {
+                mapped
+                    .iter()
+                    .map(|(field, _)| record.get(*field).cloned().flatten())
+                    .collect()
+            })
+            .collect();
+        session.bulk_insert(schema, table, names, rows, self.query_state.in_transaction);
+        self.csv_import.running = true;
+        self.csv_import.notice = None;
+        self.csv_import.error = None;
+        cx.notify();
+    }
+
+    fn toggle_command_palette(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.command_palette.open {
+            self.close_command_palette(window, cx);
+            return;
+        }
+        self.palette_input.update(cx, |input, _| input.clear());
+        self.command_palette = CommandPaletteState {
+            open: true,
+            matches: palette::matching_commands(""),
+            return_focus: window.focused(cx),
+            ..CommandPaletteState::default()
+        };
+        window.focus(&self.palette_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn close_command_palette(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.command_palette.open = false;
+        match self.command_palette.return_focus.take() {
+            Some(handle) => window.focus(&handle),
+            None => window.focus(&self.focus_handle),
+        }
+        cx.notify();
+    }
+
+    fn sync_command_palette(&mut self, cx: &mut Context<Self>) {
+        let query = self.palette_input.read(cx).text();
+        if query != self.command_palette.query {
+            self.command_palette.matches = palette::matching_commands(&query);
+            self.command_palette.query = query;
+            self.command_palette.selected = 0;
+            self.command_palette.scroll.scroll_to_item(0);
+        }
+    }
+
+    fn move_palette_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        self.sync_command_palette(cx);
+        let count = self.command_palette.matches.len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.command_palette.selected as isize + delta;
+        self.command_palette.selected = selected.rem_euclid(count as isize) as usize;
+        self.command_palette
+            .scroll
+            .scroll_to_item(self.command_palette.selected);
+        cx.notify();
+    }
+
+    fn confirm_palette_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.sync_command_palette(cx);
+        let Some(command) = self
+            .command_palette
+            .matches
+            .get(self.command_palette.selected)
+            .copied()
+        else {
+            return;
+        };
+        self.run_palette_command(command, window, cx);
+    }
+
+    fn run_palette_command(
+        &mut self,
+        command: PaletteCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.close_command_palette(window, cx);
+        match command {
+            PaletteCommand::RunQuery => self.execute_query(cx),
+            PaletteCommand::RunSelection => self.execute_selection(cx),
+            PaletteCommand::CancelQuery => self.cancel_query(cx),
+            PaletteCommand::FormatSql => self.format_editor_sql(cx),
+            PaletteCommand::Connect => self.connect_selected(cx),
+            PaletteCommand::Reconnect => self.reconnect(cx),
+            PaletteCommand::Disconnect => self.disconnect(cx),
+            PaletteCommand::ShowSchemaBrowser => self.show_tab(MainTab::SchemaBrowser, window, cx),
+            PaletteCommand::ShowSqlEditor => self.show_tab(MainTab::SqlEditor, window, cx),
+            PaletteCommand::ShowSessions => self.show_tab(MainTab::Sessions, window, cx),
+            PaletteCommand::ShowSchemaDiff => self.show_tab(MainTab::SchemaDiff, window, cx),
+            PaletteCommand::RefreshSchemas => self.refresh_schemas(cx),
+            PaletteCommand::ExportCsv => self.export_result_as_csv(cx),
+            PaletteCommand::CopyResultsTsv => self.copy_result_as_tsv(false, cx),
+            PaletteCommand::CopyResultsJson => self.copy_result_as_json(true, cx),
+            PaletteCommand::OpenResultsWindow => self.open_results_window(cx),
+            PaletteCommand::ToggleResultsMaximized => self.toggle_results_maximized(cx),
+            PaletteCommand::ToggleHistory => self.toggle_history(cx),
+            PaletteCommand::ToggleActivityLog => {
+                self.activity_log_open = !self.activity_log_open;
+                cx.notify();
+            }
+            PaletteCommand::NewProfile => self.begin_create_profile(cx),
+            PaletteCommand::EditProfile => self.begin_edit_profile(cx),
+            PaletteCommand::DuplicateProfile => self.duplicate_selected_profile(cx),
+            PaletteCommand::ToggleTheme => self.toggle_theme(cx),
+            PaletteCommand::ToggleRowDensity => self.toggle_row_density(cx),
+            PaletteCommand::ToggleThousandsSeparators => self.toggle_thousands_separators(cx),
+            PaletteCommand::ToggleTypeColors => self.toggle_type_colors(cx),
+            PaletteCommand::ToggleWrapCells => self.toggle_wrap_cells(cx),
+        }
+    }
+
+    fn render_command_palette(
+        &mut self,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> Option<gpui::Div> {
+        if !self.command_palette.open {
+            return None;
+        }
+        self.sync_command_palette(cx);
+        let selected = self.command_palette.selected;
+        let rows = self
+            .command_palette
+            .matches
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(index, command)| {
+                let is_selected = index == selected;
+                div()
+                    .id(("palette_command", index))
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_3()
+                    .px_3()
+                    .py_1p5()
+                    .rounded_md()
+                    .text_sm()
+                    .cursor_pointer()
+                    .when(is_selected, |node| {
+                        node.bg(theme.panel_highlight).text_color(theme.text_bright)
+                    })
+                    .hover(|style| style.bg(theme.panel_highlight))
+                    .child(command.label())
+                    .when_some(command.shortcut(), |node, shortcut| {
+                        node.child(
+                            div()
+                                .flex_shrink_0()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child(shortcut),
+                        )
+                    })
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, window, cx| {
+                            cx.stop_propagation();
+                            this.run_palette_command(command, window, cx)
+                        }),
+                    )
+            })
+            .collect::<Vec<_>>();
+        let list = if rows.is_empty() {
+            div()
+                .px_3()
+                .py_2()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("No matching commands.")
+                .into_any_element()
+        } else {
+            div()
+                .id("palette-commands")
+                .flex()
+                .flex_col()
+                .max_h(px(PALETTE_LIST_MAX_HEIGHT))
+                .overflow_y_scroll()
+                .track_scroll(&self.command_palette.scroll)
+                .children(rows)
+                .into_any_element()
+        };
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .justify_center()
+                .items_start()
+                .pt(px(96.))
+                .occlude()
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, window, cx| {
+                        this.close_command_palette(window, cx)
+                    }),
+                )
+                .child(
+                    div()
+                        .key_context("CommandPalette")
+                        .on_action(cx.listener(|this, _: &SelectPrevious, _, cx| {
+                            this.move_palette_selection(-1, cx)
+                        }))
+                        .on_action(cx.listener(|this, _: &SelectNext, _, cx| {
+                            this.move_palette_selection(1, cx)
+                        }))
+                        .on_action(cx.listener(|this, _: &ConfirmSelection, window, cx| {
+                            this.confirm_palette_selection(window, cx)
+                        }))
+                        .on_action(cx.listener(|this, _: &CloseCommandPalette, window, cx| {
+                            this.close_command_palette(window, cx)
+                        }))
+                        .w(px(PALETTE_WIDTH))
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .p_3()
+                        .rounded_lg()
+                        .bg(theme.panel)
+                        .border_1()
+                        .border_color(theme.border)
+                        .shadow_lg()
+                        .on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|_, _: &MouseUpEvent, _window, cx| cx.stop_propagation()),
+                        )
+                        .child(self.palette_input.clone())
+                        .child(list),
+                ),
+        )
+    }
+
+    fn sync_schema_filters(&mut self, cx: &mut Context<Self>) {
+        let schema_filter = self.schema_filter_input.read(cx).text();
+        if schema_filter != self.schema_browser.schema_filter {
+            self.schema_browser.set_schema_filter(schema_filter);
+        }
+        let table_filter = self.table_filter_input.read(cx).text();
+        if table_filter != self.schema_browser.table_filter {
+            self.schema_browser.set_table_filter(table_filter);
+        }
+    }
+
+    fn render_schema_browser(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl Element {
+        let theme = *cx.theme();
+        self.sync_schema_filters(cx);
+        self.sync_csv_import(cx);
+        let browser_focused = self.schema_browser_focus.is_focused(window);
+        let focused_list = self.schema_browser.focused_list;
+        let list_label = |list: BrowserList, label: &'static str, refresh: gpui::Div| {
+            let focused = browser_focused && focused_list == list;
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .gap_2()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(if focused {
+                            theme.accent
+                        } else {
+                            theme.text_muted
+                        })
+                        .child(label),
+                )
+                .child(refresh)
+        };
+        let schemas_label = list_label(
+            BrowserList::Schemas,
+            "Schemas",
+            refresh_icon(&theme).on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, cx| this.refresh_schemas(cx)),
+            ),
+        );
+        let tables_label = list_label(
+            BrowserList::Tables,
+            "Tables",
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    selector_pill(&theme, "≈ rows", self.settings.show_table_sizes).on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.toggle_table_sizes(cx)
+                        }),
+                    ),
+                )
+                .child(refresh_icon(&theme).on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| this.refresh_tables(cx)),
+                )),
+        );
+        let columns_label = list_label(
+            BrowserList::Columns,
+            "Columns",
+            refresh_icon(&theme).on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, cx| this.refresh_columns(cx)),
+            ),
+        );
+        let visible_schemas = self.schema_browser.visible_schemas();
+        let visible_tables = self.schema_browser.visible_tables();
+        let visible_views = self.schema_browser.visible_views();
+        let schema_list: AnyElement = if self.schema_browser.schemas_loading {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Loading schemas...")
+                .into_any()
+        } else if self.schema_browser.schemas.is_empty() {
+            let message = if self.connection.is_connected() {
+                "No schemas available."
+            } else {
+                "Connect to load schemas."
+            };
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child(message)
+                .into_any()
+        } else if visible_schemas.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("No matching schemas")
+                .into_any()
+        } else {
+            let items = visible_schemas.into_iter().map(|schema| {
+                let schema_name = schema.clone();
+                let schema_name_for_copy = schema_name.clone();
+                let is_selected = self
+                    .schema_browser
+                    .selected_schema
+                    .as_ref()
+                    .map(|current| current == schema)
+                    .unwrap_or(false);
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .p_2()
+                    .rounded_md()
+                    .bg(if is_selected {
+                        theme.panel_highlight
+                    } else {
+                        theme.panel_muted
+                    })
+                    .border_1()
+                    .border_color(if is_selected {
+                        theme.accent
+                    } else {
+                        theme.border
+                    })
+                    .hover(|style| style.bg(theme.panel_highlight))
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_bright)
+                            .child(schema.clone()),
+                    )
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, window, cx| {
+                            this.focus_browser_list(BrowserList::Schemas, window);
+                            this.select_schema(schema_name.clone(), cx);
+                        }),
+                    )
+                    .on_mouse_up(
+                        MouseButton::Right,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            this.copy_to_clipboard(schema_name_for_copy.clone(), cx);
+                        }),
+                    )
+            });
+            let scroll = div()
+                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
+                .min_w(px(0.))
+                .overflow_y_scroll()
+                .restrict_scroll_to_axis()
+                .id("schema_list_scroll")
+                .p_1()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.border)
+                .child(div().flex().flex_col().gap_1().children(items));
+            div().absorb_vertical_scroll().child(scroll).into_any()
+        };
+
+        let table_list: AnyElement = if self.schema_browser.tables_loading {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Loading tables...")
+                .into_any()
+        } else if self.schema_browser.selected_schema.is_none() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Select a schema")
+                .into_any()
+        } else if self.schema_browser.tables.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("No tables found")
+                .into_any()
+        } else if visible_tables.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("No matching tables")
+                .into_any()
+        } else {
+            let has_columns = !self.schema_browser.columns.is_empty();
+            let items = visible_tables.into_iter().map(|table| {
+                let table_name = table.clone();
+                let table_name_for_copy = table_name.clone();
+                let table_name_for_menu = table_name.clone();
+                let table_name_for_select = table_name.clone();
+                let table_name_for_ddl = table_name.clone();
+                let table_name_for_insert = table_name.clone();
+                let table_name_for_update = table_name.clone();
+                let menu_open = self.schema_browser.table_menu.as_deref() == Some(table.as_str());
+                let size = self.schema_browser.table_sizes.get(table).copied();
+                let is_selected = self
+                    .schema_browser
+                    .selected_table
+                    .as_ref()
+                    .map(|current| current == table)
+                    .unwrap_or(false);
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .p_2()
+                    .rounded_md()
+                    .bg(if is_selected {
+                        theme.panel_highlight
+                    } else {
+                        theme.panel_muted
+                    })
+                    .border_1()
+                    .border_color(if is_selected {
+                        theme.accent
+                    } else {
+                        theme.border
+                    })
+                    .hover(|style| style.bg(theme.panel_highlight))
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(theme.text_bright)
+                                    .child(table.clone()),
+                            )
+                            .when_some(size, |node, size| {
+                                node.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.text_muted)
+                                        .child(format!("~{} rows", format_row_estimate(size))),
+                                )
+                            }),
+                    )
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, window, cx| {
+                            this.focus_browser_list(BrowserList::Tables, window);
+                            this.select_table(table_name.clone(), cx);
+                        }),
+                    )
+                    .on_mouse_up(
+                        MouseButton::Right,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            this.toggle_table_menu(table_name_for_menu.clone(), cx);
+                        }),
+                    )
+                    .when(menu_open, |node| {
+                        node.child(
+                            div()
+                                .flex()
+                                .flex_wrap()
+                                .justify_end()
+                                .gap_2()
+                                .child(result_action_button(&theme, "Copy name").on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.schema_browser.table_menu = None;
+                                        this.copy_to_clipboard(table_name_for_copy.clone(), cx);
+                                        cx.notify();
+                                    }),
+                                ))
+                                .child(result_action_button(&theme, "Generate SELECT").on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.generate_select(table_name_for_select.clone(), cx);
+                                    }),
+                                ))
+                                .child(result_action_button(&theme, "View DDL").on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.view_table_ddl(table_name_for_ddl.clone(), cx);
+                                    }),
+                                ))
+                                .when(is_selected && has_columns, |node| {
+                                    node.child(
+                                        result_action_button(&theme, "Copy INSERT template")
+                                            .on_mouse_up(
+                                                MouseButton::Left,
+                                                cx.listener(
+                                                    move |this, _: &MouseUpEvent, _window, cx| {
+                                                        cx.stop_propagation();
+                                                        this.copy_table_template(
+                                                            table_name_for_insert.clone(),
+                                                            false,
+                                                            cx,
+                                                        );
+                                                    },
+                                                ),
+                                            ),
+                                    )
+                                    .child(
+                                        result_action_button(&theme, "Copy UPDATE template")
+                                            .on_mouse_up(
+                                                MouseButton::Left,
+                                                cx.listener(
+                                                    move |this, _: &MouseUpEvent, _window, cx| {
+                                                        cx.stop_propagation();
+                                                        this.copy_table_template(
+                                                            table_name_for_update.clone(),
+                                                            true,
+                                                            cx,
+                                                        );
+                                                    },
+                                                ),
+                                            ),
+                                    )
+                                }),
+                        )
+                    })
+            });
+            let scroll = div()
+                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
+                .min_w(px(0.))
+                .overflow_y_scroll()
+                .restrict_scroll_to_axis()
+                .id("table_list_scroll")
+                .p_1()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.border)
+                .child(div().flex().flex_col().gap_1().children(items));
+            div().absorb_vertical_scroll().child(scroll).into_any()
+        };
+
+        let view_list: Option<AnyElement> = if self.schema_browser.views_loading {
+            Some(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child("Loading views...")
+                    .into_any(),
+            )
+        } else if visible_views.is_empty() {
+            None
+        } else {
+            let items = visible_views.into_iter().map(|view| {
+                let view_name = view.name.clone();
+                let view_name_for_copy = view_name.clone();
+                let is_selected = self.schema_browser.selected_table.as_ref() == Some(&view.name);
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .gap_2()
+                    .p_2()
+                    .rounded_md()
+                    .bg(if is_selected {
+                        theme.panel_highlight
+                    } else {
+                        theme.panel_muted
+                    })
+                    .border_1()
+                    .border_color(if is_selected {
+                        theme.accent
+                    } else {
+                        theme.border
+                    })
+                    .hover(|style| style.bg(theme.panel_highlight))
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_bright)
+                            .child(view.name.clone()),
+                    )
+                    .child(badge(
+                        &theme,
+                        if view.materialized {
+                            "mat. view"
+                        } else {
+                            "view"
+                        },
+                    ))
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, window, cx| {
+                            this.focus_browser_list(BrowserList::Tables, window);
+                            this.select_table(view_name.clone(), cx);
+                        }),
+                    )
+                    .on_mouse_up(
+                        MouseButton::Right,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            this.copy_to_clipboard(view_name_for_copy.clone(), cx);
+                        }),
+                    )
+            });
+            let scroll = div()
+                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
+                .min_w(px(0.))
+                .overflow_y_scroll()
+                .restrict_scroll_to_axis()
+                .id("view_list_scroll")
+                .p_1()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.border)
+                .child(div().flex().flex_col().gap_1().children(items));
+            Some(div().absorb_vertical_scroll().child(scroll).into_any())
+        };
+
+        let column_list: AnyElement = if self.schema_browser.columns_loading {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Loading columns...")
+                .into_any()
+        } else if self.schema_browser.selected_table.is_none() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Select a table")
+                .into_any()
+        } else if self.schema_browser.columns.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("No columns found")
+                .into_any()
+        } else {
+            let is_view = self
+                .schema_browser
+                .selected_table
+                .as_ref()
+                .is_some_and(|table| {
+                    self.schema_browser
+                        .views
+                        .iter()
+                        .any(|view| &view.name == table)
+                });
+            let items = self
+                .schema_browser
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(idx, column)| {
+                    let column_name = column.name.clone();
+                    let stats_column = column.name.clone();
+                    let sample_column = column.name.clone();
+                    let is_cursor = self.schema_browser.column_cursor == Some(idx);
+                    let is_primary_key = self.schema_browser.is_primary_key(&column.name);
+                    let references = self
+                        .schema_browser
+                        .foreign_keys
+                        .iter()
+                        .filter(|foreign_key| foreign_key.column == column.name)
+                        .map(|foreign_key| {
+                            let target_schema = foreign_key.target_schema.clone();
+                            let target_table = foreign_key.target_table.clone();
+                            div()
+                                .text_xs()
+                                .text_color(theme.accent)
+                                .hover(|style| style.underline())
+                                .cursor_pointer()
+                                .child(format!(
+                                    "→ {}.{}",
+                                    foreign_key.target_schema, foreign_key.target_table
+                                ))
                                 .on_mouse_up(
                                     MouseButton::Left,
                                     cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
                                         cx.stop_propagation();
-                                        this.move_profile_by(profile_id, -1, cx)
+                                        this.open_table(
+                                            target_schema.clone(),
+                                            target_table.clone(),
+                                            cx,
+                                        );
                                     }),
-                                );
+                                )
+                        })
+                        .collect::<Vec<_>>();
+                    div()
+                        .flex()
+                        .justify_between()
+                        .items_center()
+                        .p_2()
+                        .rounded_md()
+                        .bg(if is_cursor {
+                            theme.panel_highlight
                         } else {
-                            node = node.text_color(rgb(COLOR_TEXT_MUTED));
-                        }
-                        node
-                    })
-                    .child({
-                        let mut node = div()
+                            theme.panel_muted
+                        })
+                        .border_1()
+                        .border_color(if is_cursor {
+                            theme.accent
+                        } else {
+                            theme.border
+                        })
+                        .hover(|style| style.bg(theme.panel_highlight))
+                        .cursor_pointer()
+                        .child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(theme.text_bright)
+                                        .child(column.name.clone()),
+                                )
+                                .when(is_primary_key, |node| node.child(badge(&theme, "PK")))
+                                .children(references)
+                                .when(is_cursor, |node| {
+                                    node.child(result_action_button(&theme, "Stats").on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                            cx.stop_propagation();
+                                            this.load_column_stats(stats_column.clone(), false, cx);
+                                        }),
+                                    ))
+                                    .when(!is_view, |node| {
+                                        node.child(
+                                            result_action_button(&theme, "Sample").on_mouse_up(
+                                                MouseButton::Left,
+                                                cx.listener(
+                                                    move |this, _: &MouseUpEvent, _window, cx| {
+                                                        cx.stop_propagation();
+                                                        this.load_column_stats(
+                                                            sample_column.clone(),
+                                                            true,
+                                                            cx,
+                                                        );
+                                                    },
+                                                ),
+                                            ),
+                                        )
+                                    })
+                                }),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child(column_type_label(column)),
+                        )
+                        .on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(move |this, _: &MouseUpEvent, window, cx| {
+                                this.focus_browser_list(BrowserList::Columns, window);
+                                this.schema_browser.column_cursor = Some(idx);
+                                this.copy_to_clipboard(column_name.clone(), cx);
+                            }),
+                        )
+                });
+            let scroll = div()
+                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
+                .min_w(px(0.))
+                .overflow_y_scroll()
+                .restrict_scroll_to_axis()
+                .id("column_list_scroll")
+                .p_1()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.border)
+                .child(div().flex().flex_col().gap_1().children(items));
+            div().absorb_vertical_scroll().child(scroll).into_any()
+        };
+
+        let index_list: Option<AnyElement> = if self.schema_browser.indexes.is_empty() {
+            None
+        } else {
+            let items = self.schema_browser.indexes.iter().map(|index| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_2()
+                    .rounded_md()
+                    .bg(theme.panel_muted)
+                    .border_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(theme.text_bright)
+                                    .child(index.name.clone()),
+                            )
+                            .when(index.primary, |node| node.child(badge(&theme, "PRIMARY")))
+                            .when(index.unique && !index.primary, |node| {
+                                node.child(badge(&theme, "UNIQUE"))
+                            }),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.text_muted)
+                            .child(format!("({})", index.columns.join(", "))),
+                    )
+            });
+            let scroll = div()
+                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
+                .min_w(px(0.))
+                .overflow_y_scroll()
+                .restrict_scroll_to_axis()
+                .id("index_list_scroll")
+                .p_1()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.border)
+                .child(div().flex().flex_col().gap_1().children(items));
+            Some(div().absorb_vertical_scroll().child(scroll).into_any())
+        };
+
+        let mut panel =
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .p_4()
+                .rounded_lg()
+                .bg(theme.panel)
+                .border_1()
+                .border_color(theme.border)
+                .track_focus(&self.schema_browser_focus)
+                .key_context("SchemaBrowser")
+                .on_action(cx.listener(|this, _: &SelectPrevious, _, cx| {
+                    this.move_browser_selection(-1, cx)
+                }))
+                .on_action(
+                    cx.listener(|this, _: &SelectNext, _, cx| this.move_browser_selection(1, cx)),
+                )
+                .on_action(cx.listener(|this, _: &ConfirmSelection, _, cx| {
+                    this.confirm_browser_selection(cx)
+                }))
+                .on_action(cx.listener(|this, _: &FocusNextList, _, cx| {
+                    this.schema_browser.focused_list = this.schema_browser.focused_list.next();
+                    cx.notify();
+                }))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(theme.text_muted)
+                                .child("Schema Browser"),
+                        )
+                        .when(self.connection.is_connected(), |node| {
+                            node.child(result_action_button(&theme, "Refresh all").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.refresh_schema_browser(cx)
+                                }),
+                            ))
+                        }),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .gap_1()
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .flex_shrink_0()
+                                .w(px(self.settings.schema_list_width))
+                                .gap_1()
+                                .child(schemas_label)
+                                .child(filter_box(&theme, self.schema_filter_input.clone()))
+                                .child(schema_list),
+                        )
+                        .child(self.render_splitter(&theme, Splitter::SchemaList, cx))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .flex_shrink_0()
+                                .w(px(self.settings.table_list_width))
+                                .gap_1()
+                                .child(tables_label)
+                                .child(filter_box(&theme, self.table_filter_input.clone()))
+                                .child(table_list)
+                                .when_some(view_list, |node, view_list| {
+                                    node.child(
+                                        div()
+                                            .pt_2()
+                                            .text_xs()
+                                            .text_color(theme.text_muted)
+                                            .child("Views"),
+                                    )
+                                    .child(view_list)
+                                }),
+                        )
+                        .child(self.render_splitter(&theme, Splitter::TableList, cx))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap_1()
+                                .flex_grow()
+                                .min_w(px(0.))
+                                .child(columns_label)
+                                .child(column_list)
+                                .when_some(index_list, |node, index_list| {
+                                    node.child(
+                                        div()
+                                            .pt_2()
+                                            .text_xs()
+                                            .text_color(theme.text_muted)
+                                            .child("Indexes"),
+                                    )
+                                    .child(index_list)
+                                }),
+                        ),
+                )
+                .child(div().text_xs().text_color(theme.text_muted).child(
+                    "Right-click to copy schema/table names. Left-click copies column names. Use ↑/↓ to move, Enter to drill in, and Tab to switch lists.",
+                ))
+                .when_some(
+                    self.render_column_stats_panel(&theme, cx),
+                    |node, stats_panel| node.child(stats_panel),
+                )
+                .when_some(self.render_ddl_panel(&theme, cx), |node, ddl_panel| {
+                    node.child(ddl_panel)
+                })
+                .child(self.render_preview_panel(&theme, cx))
+                .when_some(
+                    self.render_csv_import_panel(&theme, cx),
+                    |node, csv_panel| node.child(csv_panel),
+                );
+
+        if let Some(error) = self.schema_browser.last_error.clone() {
+            panel = panel.child(error_banner(&theme, &error));
+        }
+
+        panel
+    }
+
+    fn render_column_stats_panel(
+        &self,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        if self.schema_browser.column_stats_loading {
+            return Some(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child("Computing column statistics...")
+                    .into_any(),
+            );
+        }
+        let (column, stats) = self.schema_browser.column_stats.clone()?;
+        let nulls = stats.rows.saturating_sub(stats.non_null);
+        let null_share = if stats.rows == 0 {
+            String::new()
+        } else {
+            format!(" ({:.1}%)", nulls as f64 * 100.0 / stats.rows as f64)
+        };
+        let title = if stats.sampled {
+            format!("Stats — {column} (sampled ~{STATS_SAMPLE_ROWS} rows)")
+        } else {
+            format!("Stats — {column}")
+        };
+        let entries = [
+            ("Rows", stats.rows.to_string()),
+            ("Nulls", format!("{nulls}{null_share}")),
+            ("Distinct", stats.distinct.to_string()),
+            ("Min", stats.min.unwrap_or_else(|| "—".into())),
+            ("Max", stats.max.unwrap_or_else(|| "—".into())),
+        ];
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(div().text_sm().text_color(theme.text_muted).child(title))
+                        .child(result_action_button(theme, "Close").on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.schema_browser.column_stats = None;
+                                cx.notify();
+                            }),
+                        )),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .p_2()
+                        .rounded_md()
+                        .bg(theme.panel_muted)
+                        .border_1()
+                        .border_color(theme.border)
+                        .children(entries.into_iter().map(|(label, value)| {
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .w(px(80.))
+                                        .flex_shrink_0()
+                                        .text_sm()
+                                        .text_color(theme.text_muted)
+                                        .child(label),
+                                )
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .min_w(px(0.))
+                                        .text_sm()
+                                        .text_color(theme.text_bright)
+                                        .truncate()
+                                        .child(value),
+                                )
+                        })),
+                )
+                .into_any(),
+        )
+    }
+
+    fn render_ddl_panel(&self, theme: &Theme, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if self.schema_browser.ddl_loading {
+            return Some(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child("Loading DDL...")
+                    .into_any(),
+            );
+        }
+        let ddl = self.schema_browser.ddl.clone()?;
+        let ddl_for_copy = ddl.clone();
+        Some(
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(div().text_sm().text_color(theme.text_muted).child("DDL"))
+                        .child(
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(result_action_button(theme, "Open in editor").on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                        this.open_ddl_in_editor(cx)
+                                    }),
+                                ))
+                                .child(result_action_button(theme, "Copy").on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        this.copy_to_clipboard(ddl_for_copy.clone(), cx)
+                                    }),
+                                ))
+                                .child(result_action_button(theme, "Close").on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                        this.schema_browser.ddl = None;
+                                        cx.notify();
+                                    }),
+                                )),
+                        ),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .p_2()
+                        .rounded_md()
+                        .bg(theme.panel_muted)
+                        .border_1()
+                        .border_color(theme.border)
+                        .max_h(px(320.))
+                        .overflow_y_scroll()
+                        .id("ddl_scroll")
+                        .children(
+                            ddl.lines()
+                                .map(|line| {
+                                    div()
+                                        .text_sm()
+                                        .text_color(theme.text_bright)
+                                        .whitespace_nowrap()
+                                        .child(line.to_string())
+                                })
+                                .collect::<Vec<_>>(),
+                        ),
+                )
+                .into_any(),
+        )
+    }
+
+    fn render_preview_panel(&mut self, theme: &Theme, cx: &mut Context<Self>) -> impl Element {
+        let header = if let (Some(schema), Some(table)) = (
+            self.schema_browser.selected_schema.as_ref(),
+            self.schema_browser.selected_table.as_ref(),
+        ) {
+            format!("Preview: {schema}.{table} (up to {PREVIEW_LIMIT} rows)")
+        } else {
+            "Table preview".into()
+        };
+
+        let content: AnyElement = if self.schema_browser.preview_loading {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Loading preview...")
+                .into_any()
+        } else if let Some(view) = self.schema_browser.preview.as_ref() {
+            div()
+                .max_h(px(260.))
+                .w_full()
+                .min_w(px(0.))
+                .overflow_x_scroll()
+                .restrict_scroll_to_axis()
+                .id("preview_table_scroll")
+                .p_2()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.border)
+                .child(self.render_result_table(
+                    theme,
+                    view,
+                    Some(px(210.)),
+                    Some("preview_table_body_scroll"),
+                    Some((ResultGrid::Preview, cx)),
+                ))
+                .into_any()
+        } else {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Select a table to see its preview")
+                .into_any()
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(div().text_sm().text_color(theme.text_muted).child(header))
+                    .child(
+                        div()
                             .flex()
-                            .items_center()
-                            .justify_center()
-                            .w(px(20.))
-                            .h(px(18.))
-                            .rounded_md()
-                            .bg(rgb(COLOR_PANEL_HIGHLIGHT))
-                            .border_1()
-                            .border_color(rgb(COLOR_BORDER))
-                            .text_xs()
-                            .child("↓");
-                        if can_move_down {
-                            node = node
-                                .cursor_pointer()
-                                .hover(|style| style.bg(rgb(COLOR_PANEL_MUTED)))
-                                .on_mouse_up(
-                                    MouseButton::Left,
-                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                                        cx.stop_propagation();
-                                        this.move_profile_by(profile_id, 1, cx)
-                                    }),
-                                );
-                        } else {
-                            node = node.text_color(rgb(COLOR_TEXT_MUTED));
-                        }
-                        node
-                    })
+                            .gap_2()
+                            .when(
+                                !self.schema_browser.columns.is_empty()
+                                    && self.csv_import.target.is_none()
+                                    && !self.connection.read_only,
+                                |node| {
+                                    node.child(
+                                        result_action_button(theme, "Paste CSV").on_mouse_up(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _: &MouseUpEvent, window, cx| {
+                                                this.open_csv_import(window, cx)
+                                            }),
+                                        ),
+                                    )
+                                },
+                            )
+                            .when(self.schema_browser.preview.is_some(), |node| {
+                                node.child(
+                                    result_action_button(theme, "Open in editor").on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.open_preview_in_editor(cx)
+                                        }),
+                                    ),
+                                )
+                            }),
+                    ),
+            )
+            .child(content)
+    }
+
+    fn render_csv_import_panel(
+        &mut self,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let (schema, table) = self.csv_import.target.clone()?;
+        if self.schema_browser.selected_schema.as_deref() != Some(schema.as_str())
+            || self.schema_browser.selected_table.as_deref() != Some(table.as_str())
+        {
+            return None;
+        }
+        let columns = &self.schema_browser.columns;
+        let state = &self.csv_import;
+        let row_count = state.data_rows().len();
+        let problems = state.problems(columns);
+
+        let mapping = state
+            .headers()
+            .into_iter()
+            .enumerate()
+            .map(|(index, header)| {
+                let target = state
+                    .mapping
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .and_then(|column| columns.get(column));
+                let label = match target {
+                    Some(column) => format!("{} ({})", column.name, column.data_type),
+                    None => "skip".into(),
+                };
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w(px(180.))
+                            .flex_shrink_0()
+                            .text_sm()
+                            .truncate()
+                            .child(header),
+                    )
+                    .child(div().text_xs().text_color(theme.text_muted).child("→"))
+                    .child(selector_pill(theme, label, target.is_some()).on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            let column_count = this.schema_browser.columns.len();
+                            this.csv_import.cycle_mapping(index, column_count);
+                            cx.notify();
+                        }),
+                    ))
+            })
+            .collect::<Vec<_>>();
+
+        let mut panel = div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child(format!("Insert CSV into {schema}.{table}")),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(
+                                selector_pill(theme, "First row is header", state.has_header)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.csv_import.has_header =
+                                                !this.csv_import.has_header;
+                                            this.csv_import.remap(&this.schema_browser.columns);
+                                            cx.notify();
+                                        }),
+                                    ),
+                            )
+                            .child(result_action_button(theme, "Close").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.csv_import = CsvImportState::default();
+                                    this.csv_input.update(cx, |input, _| input.clear());
+                                    cx.notify();
+                                }),
+                            )),
+                    ),
+            )
+            .child(
+                div()
+                    .border_1()
+                    .border_color(theme.border)
+                    .rounded_md()
+                    .bg(theme.panel)
+                    .h(px(CSV_INPUT_HEIGHT))
+                    .overflow_y_scroll()
+                    .id("csv_input_scroll")
+                    .child(self.csv_input.clone()),
+            );
+
+        if let Some(error) = state.parse_error.as_ref() {
+            panel = panel.child(
+                div()
+                    .text_xs()
+                    .text_color(theme.danger)
+                    .child(error.clone()),
+            );
+        } else if !mapping.is_empty() {
+            panel = panel
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child("Click a target to change which column a CSV column fills. Empty unquoted fields insert NULL."),
+                )
+                .child(div().flex().flex_col().gap_1().children(mapping));
+        }
+        if !problems.is_empty() && row_count > 0 {
+            panel = panel.children(
+                problems
+                    .into_iter()
+                    .map(|problem| div().text_xs().text_color(theme.warning).child(problem)),
+            );
+        } else if row_count > 0 && !state.running {
+            let label = if self.query_state.in_transaction {
+                format!("Insert {row_count} rows (inside the open transaction)")
             } else {
+                format!("Insert {row_count} rows")
+            };
+            panel = panel.child(div().flex().child(editor_button(theme, label).on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _window, cx| this.run_csv_import(cx)),
+            )));
+        }
+        if state.running {
+            panel = panel.child(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child(format!("Inserting {row_count} rows...")),
+            );
+        }
+        if let Some(notice) = state.notice.as_ref() {
+            panel = panel.child(
+                div()
+                    .text_sm()
+                    .text_color(theme.success)
+                    .child(notice.clone()),
+            );
+        }
+        if let Some(error) = state.error.as_ref() {
+            panel = panel.child(error_banner(theme, error));
+        }
+        Some(panel.into_any())
+    }
+
+    fn render_sessions_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let state = &self.server_sessions;
+        let columns: [(&str, f32); 8] = [
+            ("PID", 72.),
+            ("User", 110.),
+            ("Database", 120.),
+            ("Application", 130.),
+            ("Client", 120.),
+            ("State", 130.),
+            ("Wait event", 150.),
+            ("Running", 80.),
+        ];
+        let header_cell = |label: &'static str, width: f32| {
+            div()
+                .w(px(width))
+                .flex_shrink_0()
+                .text_xs()
+                .text_color(theme.text_muted)
+                .child(label)
+        };
+        let header = div()
+            .flex()
+            .gap_2()
+            .px_2()
+            .pb_1()
+            .border_b_1()
+            .border_color(theme.border)
+            .children(
+                columns
+                    .iter()
+                    .map(|(label, width)| header_cell(label, *width)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("Query"),
+            )
+            .child(header_cell("", 96.));
+
+        let rows = state.sessions.iter().map(|session| {
+            let pid = session.pid;
+            let values = [
+                session.pid.to_string(),
+                session.user.clone(),
+                session.database.clone(),
+                session.application.clone(),
+                session.client.clone(),
+                session.state.clone(),
+                session.wait_event.clone().unwrap_or_default(),
+                session
+                    .query_duration
+                    .map(format_session_duration)
+                    .unwrap_or_default(),
+            ];
+            let query = session
+                .query
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let action = if session.current {
                 div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("this connection")
+            } else {
+                result_action_button(&theme, "Terminate").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                        this.server_sessions.confirm_terminate = Some(pid);
+                        cx.notify();
+                    }),
+                )
             };
-            let item = div()
+            div()
                 .flex()
-                .flex_col()
+                .items_center()
                 .gap_2()
-                .p_3()
-                .rounded_lg()
-                .bg(if is_selected {
-                    rgb(COLOR_PANEL_HIGHLIGHT)
-                } else {
-                    rgb(COLOR_PANEL_MUTED)
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .when(state.confirm_terminate == Some(pid), |node| {
+                    node.bg(theme.danger_surface)
                 })
-                .border_1()
-                .border_color(if is_selected {
-                    rgb(COLOR_ACCENT)
+                .hover(|style| style.bg(theme.panel_highlight))
+                .children(columns.iter().zip(values).map(|((_, width), value)| {
+                    div()
+                        .w(px(*width))
+                        .flex_shrink_0()
+                        .text_sm()
+                        .truncate()
+                        .child(value)
+                }))
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w(px(0.))
+                        .text_sm()
+                        .truncate()
+                        .child(truncate_graphemes(&query, SESSION_QUERY_CHARS).into_owned()),
+                )
+                .child(div().w(px(96.)).flex_shrink_0().child(action))
+        });
+
+        let list = if state.sessions.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child(if state.loading || state.loaded_at.is_none() {
+                    "Loading sessions..."
                 } else {
-                    rgb(COLOR_BORDER)
+                    "No sessions are visible to this user."
                 })
-                .cursor_pointer()
+                .into_any()
+        } else {
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(header)
                 .child(
                     div()
+                        .id("server-sessions")
                         .flex()
-                        .justify_between()
-                        .items_start()
-                        .gap_2()
-                        .child(div().text_sm().text_color(rgb(0xf7f8fe)).child(name))
-                        .child(reorder_controls),
+                        .flex_col()
+                        .max_h(px(SESSIONS_LIST_MAX_HEIGHT))
+                        .overflow_y_scroll()
+                        .children(rows),
                 )
+                .into_any()
+        };
+
+        let mut panel = div().flex().flex_col().gap_3().child(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .gap_2()
                 .child(
                     div()
-                        .text_xs()
-                        .text_color(rgb(COLOR_TEXT_MUTED))
-                        .child(format!(
-                            "{}@{}:{}",
-                            profile.username, profile.host, profile.port
-                        )),
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child(format!("{} sessions", state.sessions.len())),
                 )
-                .on_mouse_up(
-                    MouseButton::Left,
-                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                        this.select_profile(profile_id, cx)
-                    }),
-                );
-            profile_items.push(item);
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            selector_pill(&theme, "Auto-refresh", !state.paused).on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.toggle_sessions_paused(cx)
+                                }),
+                            ),
+                        )
+                        .child(editor_button(&theme, "Refresh").on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.refresh_server_sessions(cx)
+                            }),
+                        )),
+                ),
+        );
+
+        if let Some(pid) = state.confirm_terminate {
+            panel = panel.child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .p_3()
+                    .rounded_md()
+                    .bg(theme.danger_surface)
+                    .border_1()
+                    .border_color(theme.danger)
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .text_color(theme.danger_soft)
+                            .child(format!(
+                                "Terminate session {pid}? Its connection is closed and any open transaction is rolled back."
+                            )),
+                    )
+                    .child(editor_button(&theme, "Terminate").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            this.terminate_server_session(pid, cx)
+                        }),
+                    ))
+                    .child(editor_button(&theme, "Cancel").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.server_sessions.confirm_terminate = None;
+                            cx.notify();
+                        }),
+                    )),
+            );
         }
 
-        let form = self.render_profile_form(cx);
+        panel.child(list)
+    }
+
+    fn render_notifications_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let state = &self.notifications;
+        let channels = if state.channels.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("Not listening on any channel.")
+        } else {
+            div()
+                .flex()
+                .flex_wrap()
+                .items_center()
+                .gap_2()
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child("Listening on"),
+                )
+                .children(state.channels.iter().map(|channel| {
+                    let listened = channel.clone();
+                    selector_pill(&theme, format!("{channel}  ✕"), true).on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                            this.unlisten_channel(listened.clone(), cx)
+                        }),
+                    )
+                }))
+        };
+        let list =
+            if state.entries.is_empty() {
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child("No notifications received yet.")
+                    .into_any()
+            } else {
+                div()
+                    .id("notifications")
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .max_h(px(NOTIFICATIONS_LIST_MAX_HEIGHT))
+                    .overflow_y_scroll()
+                    .children(state.entries.iter().rev().map(|entry| {
+                        div()
+                            .flex()
+                            .items_start()
+                            .gap_3()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .hover(|style| style.bg(theme.panel_highlight))
+                            .text_sm()
+                            .child(
+                                div()
+                                    .flex_shrink_0()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child(entry.at.format("%H:%M:%S").to_string()),
+                            )
+                            .child(
+                                div()
+                                    .w(px(140.))
+                                    .flex_shrink_0()
+                                    .truncate()
+                                    .text_color(theme.text_bright)
+                                    .child(entry.channel.clone()),
+                            )
+                            .child(div().flex_1().min_w(px(0.)).child(
+                                if entry.payload.is_empty() {
+                                    "(no payload)".to_string()
+                                } else {
+                                    entry.payload.clone()
+                                },
+                            ))
+                    }))
+                    .into_any()
+            };
 
         div()
             .flex()
             .flex_col()
-            .flex_shrink_0()
-            .w(px(320.))
-            .p_5()
-            .gap_4()
-            .bg(rgb(COLOR_PANEL))
-            .border_1()
-            .border_color(rgb(COLOR_BORDER))
-            .rounded_lg()
+            .gap_3()
             .child(
                 div()
                     .flex()
-                    .justify_between()
                     .items_center()
+                    .justify_between()
+                    .gap_2()
                     .child(
                         div()
-                            .text_lg()
-                            .text_color(rgb(0xfbfaff))
-                            .child("Connection Profiles"),
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child(format!("Notifications ({})", state.entries.len())),
                     )
                     .child(
                         div()
-                            .text_sm()
-                            .px_3()
-                            .py_1()
-                            .rounded_full()
-                            .bg(rgb(COLOR_ACCENT))
-                            .hover(|style| style.bg(rgb(COLOR_ACCENT_SOFT)))
-                            .cursor_pointer()
-                            .child("New")
-                            .on_mouse_up(
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(div().w(px(220.)).child(self.listen_channel_input.clone()))
+                            .child(editor_button(&theme, "Listen").on_mouse_up(
                                 MouseButton::Left,
                                 cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                                    this.begin_create_profile(cx)
+                                    this.listen_channel(cx)
                                 }),
-                            ),
+                            ))
+                            .child(editor_button(&theme, "Clear").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.notifications.entries.clear();
+                                    cx.notify();
+                                }),
+                            )),
                     ),
             )
+            .child(channels)
+            .when_some(state.error.as_deref(), |node, error| {
+                node.child(error_banner(&theme, error))
+            })
+            .child(list)
+    }
+
+    fn render_schema_diff_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let state = &self.schema_diff;
+        let is_running = state.is_running();
+        let sides: Vec<gpui::Div> = (0..2)
+            .map(|side| self.render_schema_diff_side(&theme, side, cx))
+            .collect();
+
+        let result = state.result.as_ref().map(|tables| {
+            let count = |kind: DiffKind| tables.iter().filter(|table| table.kind == kind).count();
+            let summary = if tables.iter().all(|table| table.kind == DiffKind::Unchanged) {
+                format!("No differences in {} table(s).", tables.len())
+            } else {
+                format!(
+                    "Tables: {} added, {} removed, {} changed, {} unchanged.",
+                    count(DiffKind::Added),
+                    count(DiffKind::Removed),
+                    count(DiffKind::Changed),
+                    count(DiffKind::Unchanged)
+                )
+            };
+            let [left, right] = &state.sides;
+            let mut rows = vec![schema_diff_row(
+                &theme,
+                None,
+                "Table / column".into(),
+                format!("{} · {}", left.profile_name, left.schema),
+                format!("{} · {}", right.profile_name, right.schema),
+                false,
+            )];
+            for table in tables {
+                if table.kind == DiffKind::Unchanged && !state.show_unchanged {
+                    continue;
+                }
+                let side_summary = |present: bool, count: usize| {
+                    if present {
+                        format!("{count} column(s)")
+                    } else {
+                        "—".to_string()
+                    }
+                };
+                let left_count = table.columns.iter().filter(|c| c.left.is_some()).count();
+                let right_count = table.columns.iter().filter(|c| c.right.is_some()).count();
+                rows.push(schema_diff_row(
+                    &theme,
+                    Some(table.kind),
+                    table.name.clone(),
+                    side_summary(table.kind != DiffKind::Added, left_count),
+                    side_summary(table.kind != DiffKind::Removed, right_count),
+                    false,
+                ));
+                if table.kind != DiffKind::Changed {
+                    continue;
+                }
+                for column in &table.columns {
+                    if column.kind == DiffKind::Unchanged && !state.show_unchanged {
+                        continue;
+                    }
+                    let definition = |column: Option<&ColumnMetadata>| {
+                        column.map_or_else(|| "—".to_string(), column_type_label)
+                    };
+                    rows.push(schema_diff_row(
+                        &theme,
+                        Some(column.kind),
+                        column.name.clone(),
+                        definition(column.left.as_ref()),
+                        definition(column.right.as_ref()),
+                        true,
+                    ));
+                }
+            }
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .child(div().text_sm().text_color(theme.text_muted).child(summary))
+                .child(
+                    div()
+                        .id("schema_diff_rows")
+                        .flex()
+                        .flex_col()
+                        .max_h(px(SCHEMA_DIFF_LIST_MAX_HEIGHT))
+                        .overflow_y_scroll()
+                        .children(rows),
+                )
+        });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
             .child(
                 div()
                     .flex()
-                    .flex_col()
+                    .items_center()
+                    .justify_between()
                     .gap_2()
-                    .max_h(px(260.))
-                    .p_1()
-                    .rounded_lg()
-                    .bg(rgb(COLOR_PANEL_MUTED))
-                    .border_1()
-                    .border_color(rgb(COLOR_BORDER))
-                    .overflow_y_scroll()
-                    .children(profile_items),
+                    .child(div().text_sm().text_color(theme.text_muted).child(
+                        "Compares the tables and columns of two schemas, on two profiles or \
+                         twice on one. Added and removed are relative to the left side.",
+                    ))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                selector_pill(&theme, "Show unchanged", state.show_unchanged)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.schema_diff.show_unchanged =
+                                                !this.schema_diff.show_unchanged;
+                                            cx.notify();
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                editor_button(&theme, if is_running { "Stop" } else { "Compare" })
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                            if is_running {
+                                                this.stop_schema_diff(cx);
+                                            } else {
+                                                this.start_schema_diff(cx);
+                                            }
+                                        }),
+                                    ),
+                            ),
+                    ),
             )
-            .child(form)
-            .child(self.render_profile_actions(cx))
+            .child(div().flex().gap_3().children(sides))
+            .children(result)
     }
 
-    fn render_profile_actions(&mut self, cx: &mut Context<Self>) -> impl Element {
+    fn render_schema_diff_side(
+        &self,
+        theme: &Theme,
+        side: usize,
+        cx: &mut Context<Self>,
+    ) -> gpui::Div {
+        let state = &self.schema_diff.sides[side];
+        let profiles = self.profiles.iter().map(|profile| {
+            let profile_id = profile.id;
+            selector_pill(
+                theme,
+                profile.name.clone(),
+                state.profile_id == Some(profile_id),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                    this.set_schema_diff_profile(side, profile_id, cx)
+                }),
+            )
+        });
+        let status: Option<gpui::Div> = match &state.status {
+            SchemaDiffStatus::Idle => None,
+            SchemaDiffStatus::Connecting => Some(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child(format!("Connecting to {}...", state.profile_name)),
+            ),
+            SchemaDiffStatus::Loading => {
+                let loaded = state.tables.len();
+                let message = if loaded + state.pending_tables == 0 {
+                    format!("Loading tables in {}...", state.schema)
+                } else {
+                    format!(
+                        "Loading columns: {loaded} of {} table(s)...",
+                        loaded + state.pending_tables
+                    )
+                };
+                Some(div().text_sm().text_color(theme.text_muted).child(message))
+            }
+            SchemaDiffStatus::Loaded => {
+                Some(div().text_sm().text_color(theme.success).child(format!(
+                    "Loaded {} table(s) from {}.",
+                    state.tables.len(),
+                    state.schema
+                )))
+            }
+            SchemaDiffStatus::Failed(message) => Some(error_banner(theme, message)),
+        };
+
         div()
             .flex()
+            .flex_col()
+            .flex_1()
+            .min_w(px(0.))
             .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
             .child(
                 div()
-                    .px_3()
-                    .py_2()
-                    .rounded_full()
-                    .bg(rgb(COLOR_PANEL_HIGHLIGHT))
-                    .border_1()
-                    .border_color(rgb(COLOR_ACCENT))
-                    .text_sm()
-                    .child("Edit")
-                    .cursor_pointer()
-                    .on_mouse_up(
-                        MouseButton::Left,
-                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                            this.begin_edit_profile(cx)
-                        }),
-                    ),
-            )
-            .child(
-                div()
-                    .px_3()
-                    .py_2()
-                    .rounded_full()
-                    .bg(rgb(COLOR_DANGER_SURFACE))
-                    .border_1()
-                    .border_color(rgb(COLOR_DANGER))
                     .text_sm()
-                    .text_color(rgb(COLOR_DANGER_SOFT))
-                    .hover(|style| style.bg(rgb(0x4a2034)))
-                    .child("Delete")
-                    .cursor_pointer()
-                    .on_mouse_up(
-                        MouseButton::Left,
-                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                            this.delete_selected_profile(cx)
-                        }),
-                    ),
+                    .text_color(theme.text_bright)
+                    .child(if side == 0 { "Left (base)" } else { "Right" }),
             )
+            .child(div().flex().flex_wrap().gap_2().children(profiles))
+            .child(filter_box(theme, self.schema_diff_inputs[side].clone()))
+            .children(status)
     }
 
-    fn render_profile_form(&mut self, cx: &mut Context<Self>) -> impl Element {
-        let form_visible = !matches!(self.profile_form_mode, ProfileFormMode::Hidden);
-        let notice = self.profile_notice.clone();
-
-        if !form_visible {
-            return div();
-        }
-
-        let mut node = div()
+    fn render_editor_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let mut panel = div()
             .flex()
             .flex_col()
             .gap_2()
             .p_4()
             .rounded_lg()
-            .bg(rgb(COLOR_PANEL_MUTED))
+            .bg(theme.panel)
             .border_1()
-            .border_color(rgb(COLOR_BORDER))
+            .border_color(theme.border)
+            .key_context("SqlEditor")
+            .on_action(cx.listener(|this, _: &RunQuery, _, cx| this.execute_query(cx)))
+            .on_action(cx.listener(|this, _: &RunSelection, _, cx| this.execute_selection(cx)))
+            .on_action(cx.listener(|this, _: &FormatSql, _, cx| this.format_editor_sql(cx)))
             .child(
                 div()
-                    .text_sm()
-                    .text_color(rgb(COLOR_TEXT_MUTED))
-                    .child("Profile Details"),
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child("SQL Editor"),
+                    )
+                    .child(
+                        selector_pill(
+                            &theme,
+                            "Auto-close brackets",
+                            self.settings.auto_close_brackets,
+                        )
+                        .on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.toggle_auto_close_brackets(cx)
+                            }),
+                        ),
+                    ),
+            )
+            .child(
+                div()
+                    .border_1()
+                    .border_color(theme.border)
+                    .rounded_md()
+                    .bg(theme.panel_muted)
+                    .h(px(self.editor_height()))
+                    .overflow_y_scroll()
+                    .id("sql_editor_scroll")
+                    .child(self.sql_input.clone()),
             )
-            .child(self.profile_form.name.clone())
-            .child(self.profile_form.host.clone())
-            .child(self.profile_form.port.clone())
-            .child(self.profile_form.database.clone())
-            .child(self.profile_form.username.clone())
             .child(
                 div()
                     .flex()
+                    .flex_wrap()
                     .gap_2()
                     .child(
                         div()
-                            .px_3()
+                            .px_4()
                             .py_2()
-                            .bg(rgb(COLOR_ACCENT))
-                            .hover(|style| style.bg(rgb(COLOR_ACCENT_SOFT)))
+                            .bg(theme.accent)
+                            .hover(|style| style.bg(theme.accent_soft))
+                            .text_color(theme.on_accent)
                             .rounded_full()
                             .text_sm()
-                            .child("Save")
+                            .child("Run (Cmd/Ctrl + Enter)")
                             .cursor_pointer()
                             .on_mouse_up(
                                 MouseButton::Left,
                                 cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                                    this.save_profile(cx)
+                                    this.execute_query(cx)
                                 }),
                             ),
                     )
                     .child(
                         div()
-                            .px_3()
+                            .px_4()
                             .py_2()
-                            .bg(rgb(COLOR_PANEL_HIGHLIGHT))
+                            .bg(theme.panel_highlight)
+                            .border_1()
+                            .border_color(if self.compare_run.picker_open {
+                                theme.accent
+                            } else {
+                                theme.border
+                            })
+                            .hover(|style| style.bg(theme.panel_muted))
                             .rounded_full()
                             .text_sm()
-                            .child("Cancel")
+                            .child("Run on…")
                             .cursor_pointer()
                             .on_mouse_up(
                                 MouseButton::Left,
                                 cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                                    this.cancel_profile_form(cx)
+                                    this.toggle_compare_picker(cx)
                                 }),
                             ),
-                    ),
-            );
-
-        if let Some(text) = notice {
-            node = node.child(div().text_xs().text_color(rgb(0xfbbf24)).child(text));
-        }
-
-        node
-    }
-
-    fn render_workspace(&mut self, cx: &mut Context<Self>) -> impl Element {
-        div()
-            .flex()
-            .flex_col()
-            .flex_grow()
-            .min_w(px(0.))
-            .h_full()
-            .overflow_y_scroll()
-            .id("workspace_scroll")
-            .p_5()
-            .gap_5()
-            .child(self.render_connection_panel(cx))
-            .child(self.render_main_tabs(cx))
-    }
-
-    fn render_connection_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
-        let dot_count = if self.connection.is_busy() {
-            self.connecting_indicator as usize
-        } else {
-            0
-        };
-        let status_text = self.connection.status_text(dot_count);
-        let error = self.connection.last_error.clone();
-        let is_connected = self.connection.is_connected();
-        let button_label = if is_connected {
-            "Disconnect"
-        } else {
-            "Connect"
-        };
-
-        let mut panel = div()
-            .flex()
-            .flex_row()
-            .items_center()
-            .gap_3()
-            .p_4()
-            .rounded_lg()
-            .bg(rgb(COLOR_PANEL_MUTED))
-            .border_1()
-            .border_color(rgb(COLOR_BORDER))
-            .child(
-                div()
-                    .flex()
-                    .flex_col()
-                    .gap_1()
-                    .flex_grow()
+                    )
                     .child(
                         div()
-                            .text_sm()
-                            .text_color(rgb(COLOR_TEXT_MUTED))
-                            .child("Status"),
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(div().text_xs().text_color(theme.text_muted).child("Limit"))
+                            .child(
+                                div()
+                                    .w(px(88.))
+                                    .border_1()
+                                    .border_color(theme.border)
+                                    .rounded_md()
+                                    .bg(theme.panel_muted)
+                                    .child(self.row_limit_input.clone()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_muted)
+                                    .child("Timeout (s)"),
+                            )
+                            .child(
+                                div()
+                                    .w(px(64.))
+                                    .border_1()
+                                    .border_color(theme.border)
+                                    .rounded_md()
+                                    .bg(theme.panel_muted)
+                                    .child(self.statement_timeout_input.clone()),
+                            ),
+                    )
+                    .child(
+                        selector_pill(&theme, "History", self.history_open)
+                            .px_4()
+                            .py_2()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.toggle_history(cx)
+                                }),
+                            ),
                     )
-                    .child(div().text_xl().child(status_text)),
-            )
-            .child(
+                    .child(editor_button(&theme, "Format").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.format_editor_sql(cx)
+                        }),
+                    ))
+                    .child(editor_button(&theme, "Explain").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.explain_query(false, false, cx)
+                        }),
+                    ))
+                    .child(editor_button(&theme, "Explain Analyze").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.explain_query(true, false, cx)
+                        }),
+                    ))
+                    .when(!self.query_state.in_transaction, |node| {
+                        node.child(editor_button(&theme, "Begin").on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.begin_transaction(cx)
+                            }),
+                        ))
+                    })
+                    .when(self.query_state.in_transaction, |node| {
+                        node.child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .px_3()
+                                .rounded_full()
+                                .border_1()
+                                .border_color(theme.accent)
+                                .text_xs()
+                                .text_color(theme.accent)
+                                .child("In transaction"),
+                        )
+                        .child(editor_button(&theme, "Commit").on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.end_transaction(true, cx)
+                            }),
+                        ))
+                        .child(
+                            editor_button(&theme, "Rollback").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.end_transaction(false, cx)
+                                }),
+                            ),
+                        )
+                    })
+                    .when(
+                        matches!(self.query_state.status, QueryStatus::Running),
+                        |node| {
+                            node.child(div().text_sm().child(
+                                if self.query_state.cancel_requested {
+                                    "Cancelling...".to_string()
+                                } else {
+                                    format!(
+                                        "Running... {:.1}s",
+                                        self.query_state.elapsed.as_secs_f32()
+                                    )
+                                },
+                            ))
+                            .child(
+                                div()
+                                    .px_4()
+                                    .py_2()
+                                    .rounded_full()
+                                    .bg(theme.danger_surface)
+                                    .border_1()
+                                    .border_color(theme.danger)
+                                    .text_sm()
+                                    .text_color(theme.danger_soft)
+                                    .hover(|style| style.bg(theme.danger_hover))
+                                    .child("Cancel")
+                                    .cursor_pointer()
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.cancel_query(cx)
+                                        }),
+                                    ),
+                            )
+                        },
+                    ),
+            );
+
+        let param_count = parameter_count(self.connection.kind, &self.sql_input.read(cx).text());
+        if param_count > 0 {
+            panel = panel.child(self.render_bind_params(param_count, cx));
+        }
+
+        if self.compare_run.picker_open {
+            panel = panel.child(self.render_compare_picker(cx));
+        }
+
+        if self.history_open {
+            panel = panel.child(self.render_history_panel(cx));
+        }
+
+        if self.query_state.confirm_explain_analyze {
+            panel = panel.child(
                 div()
                     .flex()
-                    .flex_col()
-                    .gap_1()
-                    .w(px(220.))
+                    .items_center()
+                    .gap_2()
+                    .p_3()
+                    .rounded_md()
+                    .bg(theme.danger_surface)
+                    .border_1()
+                    .border_color(theme.danger)
                     .child(
                         div()
+                            .flex_1()
                             .text_sm()
-                            .text_color(rgb(COLOR_TEXT_MUTED))
-                            .child("Password"),
+                            .text_color(theme.danger_soft)
+                            .child(
+                                "EXPLAIN ANALYZE executes the statement, so it may change data.",
+                            ),
                     )
-                    .child(self.password_input.clone()),
-            )
-            .child(
+                    .child(editor_button(&theme, "Run anyway").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.explain_query(true, true, cx)
+                        }),
+                    ))
+                    .child(editor_button(&theme, "Cancel").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.query_state.confirm_explain_analyze = false;
+                            cx.notify();
+                        }),
+                    )),
+            );
+        }
+
+        if let Some((text, range)) = self.query_state.confirm_destructive.as_ref() {
+            let kind = self.connection.kind;
+            let statements = split_statements(kind, &text[range.clone()])
+                .into_iter()
+                .filter(|statement| is_destructive_statement(kind, statement))
+                .map(statement_tag)
+                .collect::<Vec<_>>();
+            panel = panel.child(
                 div()
-                    .align_self_end()
                     .flex()
-                    .flex_shrink_0()
                     .items_center()
-                    .justify_center()
-                    .h(px(36.))
-                    .px_4()
-                    .rounded_lg()
-                    .text_sm()
-                    .text_color(if is_connected {
-                        rgb(COLOR_DANGER_SOFT)
-                    } else {
-                        rgb(0xf8fafc)
-                    })
-                    .bg(if is_connected {
-                        rgb(COLOR_DANGER_SURFACE)
-                    } else {
-                        rgb(COLOR_ACCENT)
-                    })
+                    .gap_2()
+                    .p_3()
+                    .rounded_md()
+                    .bg(theme.danger_surface)
                     .border_1()
-                    .border_color(if is_connected {
-                        rgb(COLOR_DANGER)
-                    } else {
-                        rgb(COLOR_ACCENT_SOFT)
-                    })
-                    .hover(|style| {
-                        if is_connected {
-                            style.bg(rgb(0x4a2034))
-                        } else {
-                            style.bg(rgb(COLOR_ACCENT_SOFT))
-                        }
-                    })
-                    .cursor_pointer()
+                    .border_color(theme.danger)
                     .child(
                         div()
-                            .flex()
-                            .items_center()
-                            .gap_2()
-                            .child(connection_action_icon(&self.connection.status))
-                            .child(button_label),
+                            .flex_1()
+                            .text_sm()
+                            .text_color(theme.danger_soft)
+                            .child(format!(
+                                "This run contains destructive statements ({}). DROP, TRUNCATE, and DELETE/UPDATE without WHERE affect whole tables.",
+                                statements.join(", ")
+                            )),
                     )
-                    .on_mouse_up(
+                    .child(editor_button(&theme, "Run anyway").on_mouse_up(
                         MouseButton::Left,
                         cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                            if this.connection.is_connected() {
-                                this.disconnect(cx);
-                            } else {
-                                this.connect_selected(cx);
+                            if let Some((text, range)) = this.query_state.confirm_destructive.take()
+                            {
+                                this.run_editor_range(text, range, true, cx);
                             }
                         }),
-                    ),
+                    ))
+                    .child(editor_button(&theme, "Cancel").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.query_state.confirm_destructive = None;
+                            cx.notify();
+                        }),
+                    )),
             );
-
-        if let Some(text) = error {
-            panel = panel.child(error_banner(&text).align_self_end());
         }
 
-        panel
-    }
-
-    fn render_main_tabs(&mut self, cx: &mut Context<Self>) -> impl Element {
-        let tabs = [
-            (MainTab::SchemaBrowser, "Schema Browser"),
-            (MainTab::SqlEditor, "SQL Editor"),
-        ];
-        let mut tab_buttons = Vec::new();
-        for (tab, label) in tabs {
-            let is_active = self.active_tab == tab;
-            let tab_value = tab;
-            tab_buttons.push(
+        if !self.query_state.notices.is_empty() {
+            panel = panel.child(
                 div()
-                    .px_3()
-                    .py_2()
-                    .rounded_full()
-                    .text_sm()
-                    .text_color(if is_active {
-                        rgb(0xfdf4ff)
-                    } else {
-                        rgb(COLOR_TEXT_MUTED)
-                    })
-                    .bg(if is_active {
-                        rgb(COLOR_ACCENT)
-                    } else {
-                        rgb(COLOR_PANEL_MUTED)
-                    })
-                    .border_1()
-                    .border_color(if is_active {
-                        rgb(COLOR_ACCENT_SOFT)
-                    } else {
-                        rgb(COLOR_BORDER)
-                    })
-                    .hover(|style| {
-                        if is_active {
-                            style.bg(rgb(0x9f67ff))
-                        } else {
-                            style.bg(rgb(COLOR_PANEL_HIGHLIGHT))
-                        }
-                    })
-                    .cursor_pointer()
-                    .child(label)
-                    .on_mouse_up(
-                        MouseButton::Left,
-                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                            this.active_tab = tab_value;
-                            cx.notify();
-                        }),
+                    .flex()
+                    .flex_col()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .children(
+                        self.query_state
+                            .notices
+                            .iter()
+                            .flat_map(|notice| notice.lines())
+                            .map(|line| div().child(line.to_string()))
+                            .collect::<Vec<_>>(),
                     ),
             );
         }
 
-        let content: AnyElement = match self.active_tab {
-            MainTab::SchemaBrowser => self.render_schema_browser(cx).into_any(),
-            MainTab::SqlEditor => div()
-                .flex()
-                .flex_col()
-                .gap_4()
-                .child(self.render_editor_panel(cx))
-                .child(self.render_results_panel())
-                .into_any(),
-        };
+        if let Some(error) = self.query_state.last_error.clone() {
+            panel = panel.child(error_banner(&theme, &error.message));
+            let sqlstate = error.code.map(|code| match sqlstate::describe(&code) {
+                Some(info) => format!("SQLSTATE {code} ({}): {}", info.name, info.explanation),
+                None => format!("SQLSTATE {code}"),
+            });
+            let details = sqlstate
+                .into_iter()
+                .chain(error.detail.map(|detail| format!("DETAIL: {detail}")))
+                .chain(error.hint.map(|hint| format!("HINT: {hint}")))
+                .collect::<Vec<_>>();
+            if !details.is_empty() {
+                panel = panel.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .children(details.into_iter().map(|line| div().child(line))),
+                );
+            }
+        }
 
-        div()
-            .flex()
-            .flex_col()
-            .gap_3()
-            .p_4()
-            .rounded_lg()
-            .bg(rgb(COLOR_PANEL))
-            .border_1()
-            .border_color(rgb(COLOR_BORDER))
-            .child(div().flex().gap_2().children(tab_buttons))
-            .child(content)
+        panel
     }
 
-    fn render_schema_browser(&mut self, cx: &mut Context<Self>) -> impl Element {
-        let schema_list: AnyElement = if self.schema_browser.schemas_loading {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Loading schemas...")
-                .into_any()
-        } else if self.schema_browser.schemas.is_empty() {
-            let message = if self.connection.is_connected() {
-                "No schemas available."
-            } else {
-                "Connect to load schemas."
-            };
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child(message)
-                .into_any()
-        } else {
-            let items = self.schema_browser.schemas.iter().map(|schema| {
-                let schema_name = schema.clone();
-                let schema_name_for_copy = schema_name.clone();
-                let is_selected = self
-                    .schema_browser
-                    .selected_schema
-                    .as_ref()
-                    .map(|current| current == schema)
-                    .unwrap_or(false);
+    fn render_snippets_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let profile_id = self.snippet_profile();
+        let items: Vec<_> = self
+            .snippets
+            .iter()
+            .filter(|snippet| Some(snippet.profile_id) == profile_id)
+            .map(|snippet| {
+                let id = snippet.id;
                 div()
                     .flex()
-                    .justify_between()
-                    .items_center()
+                    .flex_col()
+                    .gap_1()
                     .p_2()
                     .rounded_md()
-                    .bg(if is_selected {
-                        rgb(COLOR_PANEL_HIGHLIGHT)
-                    } else {
-                        rgb(COLOR_PANEL_MUTED)
-                    })
+                    .bg(theme.panel_muted)
                     .border_1()
-                    .border_color(if is_selected {
-                        rgb(COLOR_ACCENT)
-                    } else {
-                        rgb(COLOR_BORDER)
-                    })
-                    .hover(|style| style.bg(rgb(COLOR_PANEL_HIGHLIGHT)))
-                    .cursor_pointer()
+                    .border_color(theme.border)
                     .child(
                         div()
                             .text_sm()
-                            .text_color(rgb(0xfdf4ff))
-                            .child(schema.clone()),
-                    )
-                    .on_mouse_up(
-                        MouseButton::Left,
-                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                            this.select_schema(schema_name.clone(), cx);
-                        }),
+                            .truncate()
+                            .hover(|style| style.text_color(theme.accent))
+                            .cursor_pointer()
+                            .child(snippet.name.clone())
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    this.load_snippet(id, cx)
+                                }),
+                            ),
                     )
-                    .on_mouse_up(
-                        MouseButton::Right,
-                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                            this.copy_to_clipboard(schema_name_for_copy.clone(), cx);
-                        }),
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(result_action_button(&theme, "Update").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    this.update_snippet(id, cx)
+                                }),
+                            ))
+                            .child(result_action_button(&theme, "Delete").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    this.delete_snippet(id, cx)
+                                }),
+                            )),
                     )
-            });
-            let scroll = div()
-                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
-                .min_w(px(0.))
-                .overflow_y_scroll()
-                .restrict_scroll_to_axis()
-                .id("schema_list_scroll")
-                .p_1()
-                .rounded_md()
-                .bg(rgb(COLOR_PANEL_MUTED))
-                .border_1()
-                .border_color(rgb(COLOR_BORDER))
-                .child(div().flex().flex_col().gap_1().children(items));
-            div().absorb_vertical_scroll().child(scroll).into_any()
+            })
+            .collect();
+
+        let mut panel = div()
+            .flex()
+            .flex_col()
+            .flex_shrink_0()
+            .w(px(SNIPPETS_PANEL_WIDTH))
+            .gap_2()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.panel)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child("Snippets"),
+            );
+
+        if self.snippet_naming {
+            panel = panel
+                .child(
+                    div()
+                        .border_1()
+                        .border_color(theme.border)
+                        .rounded_md()
+                        .bg(theme.panel_muted)
+                        .child(self.snippet_name_input.clone()),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .gap_2()
+                        .child(result_action_button(&theme, "Save").on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.save_snippet(cx)
+                            }),
+                        ))
+                        .child(result_action_button(&theme, "Cancel").on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                this.snippet_naming = false;
+                                this.snippet_notice = None;
+                                cx.notify();
+                            }),
+                        )),
+                );
+        } else {
+            panel = panel.child(
+                result_action_button(&theme, "Save current as snippet").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| this.begin_save_snippet(cx)),
+                ),
+            );
+        }
+
+        if let Some(notice) = self.snippet_notice.clone() {
+            panel = panel.child(div().text_xs().text_color(theme.text_muted).child(notice));
+        }
+
+        panel = if items.is_empty() {
+            panel.child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("No snippets for this profile."),
+            )
+        } else {
+            panel.child(
+                div()
+                    .id("snippet-list")
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .max_h(px(LIST_SCROLL_MAX_HEIGHT * 2.))
+                    .overflow_y_scroll()
+                    .children(items),
+            )
         };
 
-        let table_list: AnyElement = if self.schema_browser.tables_loading {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Loading tables...")
-                .into_any()
-        } else if self.schema_browser.selected_schema.is_none() {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Select a schema")
-                .into_any()
-        } else if self.schema_browser.tables.is_empty() {
+        panel.child(
             div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("No tables found")
-                .into_any()
-        } else {
-            let items = self.schema_browser.tables.iter().map(|table| {
-                let table_name = table.clone();
-                let table_name_for_copy = table_name.clone();
-                let is_selected = self
-                    .schema_browser
-                    .selected_table
-                    .as_ref()
-                    .map(|current| current == table)
-                    .unwrap_or(false);
+                .flex()
+                .gap_2()
+                .child(result_action_button(&theme, "Import").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| this.import_snippets(cx)),
+                ))
+                .child(result_action_button(&theme, "Export").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| this.export_snippets(cx)),
+                )),
+        )
+    }
+
+    fn render_bind_params(&mut self, count: usize, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        self.ensure_bind_params(count, cx);
+        let kind = self.connection.kind;
+        let rows = self.bind_params[..count]
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
                 div()
                     .flex()
-                    .justify_between()
                     .items_center()
-                    .p_2()
-                    .rounded_md()
-                    .bg(if is_selected {
-                        rgb(COLOR_PANEL_HIGHLIGHT)
-                    } else {
-                        rgb(COLOR_PANEL_MUTED)
-                    })
-                    .border_1()
-                    .border_color(if is_selected {
-                        rgb(COLOR_ACCENT)
-                    } else {
-                        rgb(COLOR_BORDER)
-                    })
-                    .hover(|style| style.bg(rgb(COLOR_PANEL_HIGHLIGHT)))
-                    .cursor_pointer()
+                    .gap_2()
                     .child(
                         div()
+                            .w(px(40.))
                             .text_sm()
-                            .text_color(rgb(0xfdf4ff))
-                            .child(table.clone()),
-                    )
-                    .on_mouse_up(
-                        MouseButton::Left,
-                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                            this.select_table(table_name.clone(), cx);
-                        }),
+                            .text_color(theme.text_muted)
+                            .child(param_label(kind, index)),
                     )
-                    .on_mouse_up(
-                        MouseButton::Right,
-                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                            this.copy_to_clipboard(table_name_for_copy.clone(), cx);
-                        }),
+                    .child(
+                        div()
+                            .w(px(220.))
+                            .border_1()
+                            .border_color(theme.border)
+                            .rounded_md()
+                            .bg(theme.panel)
+                            .when(param.kind == ParamKind::Null, |node| node.opacity(0.5))
+                            .child(param.input.clone()),
                     )
-            });
-            let scroll = div()
-                .max_h(px(LIST_SCROLL_MAX_HEIGHT))
-                .min_w(px(0.))
-                .overflow_y_scroll()
-                .restrict_scroll_to_axis()
-                .id("table_list_scroll")
-                .p_1()
-                .rounded_md()
-                .bg(rgb(COLOR_PANEL_MUTED))
-                .border_1()
-                .border_color(rgb(COLOR_BORDER))
-                .child(div().flex().flex_col().gap_1().children(items));
-            div().absorb_vertical_scroll().child(scroll).into_any()
-        };
+                    .children(ParamKind::ALL.into_iter().map(|param_kind| {
+                        selector_pill(&theme, param_kind.label(), param.kind == param_kind)
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    if let Some(param) = this.bind_params.get_mut(index) {
+                                        param.kind = param_kind;
+                                    }
+                                    cx.notify();
+                                }),
+                            )
+                    }))
+            })
+            .collect::<Vec<_>>();
 
-        let column_list: AnyElement = if self.schema_browser.columns_loading {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Loading columns...")
-                .into_any()
-        } else if self.schema_browser.selected_table.is_none() {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Select a table")
-                .into_any()
-        } else if self.schema_browser.columns.is_empty() {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("No columns found")
-                .into_any()
-        } else {
-            let items = self.schema_browser.columns.iter().map(|column| {
-                let column_name = column.name.clone();
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("Bind parameters"),
+            )
+            .children(rows)
+    }
+
+    fn render_history_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let profile_id = self.connection.profile_id.or(self.selected_profile);
+        let items: Vec<_> = self
+            .history
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, entry)| Some(entry.profile_id) == profile_id)
+            .map(|(index, entry)| {
+                let executed_at = entry.executed_at.with_timezone(&Local);
+                let first_line = entry.sql.lines().next().unwrap_or_default().to_string();
                 div()
                     .flex()
-                    .justify_between()
                     .items_center()
+                    .gap_3()
                     .p_2()
                     .rounded_md()
-                    .bg(rgb(COLOR_PANEL_MUTED))
-                    .border_1()
-                    .border_color(rgb(COLOR_BORDER))
-                    .hover(|style| style.bg(rgb(COLOR_PANEL_HIGHLIGHT)))
+                    .hover(|style| style.bg(theme.panel_highlight))
                     .cursor_pointer()
                     .child(
                         div()
-                            .text_sm()
-                            .text_color(rgb(0xfdf4ff))
-                            .child(column.name.clone()),
-                    )
-                    .child(
-                        div()
+                            .flex_shrink_0()
                             .text_xs()
-                            .text_color(rgb(COLOR_TEXT_MUTED))
-                            .child(column.data_type.clone()),
+                            .text_color(theme.text_muted)
+                            .child(executed_at.format("%Y-%m-%d %H:%M:%S").to_string()),
                     )
+                    .child(div().flex_1().text_sm().truncate().child(first_line))
                     .on_mouse_up(
                         MouseButton::Left,
                         cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
-                            this.copy_to_clipboard(column_name.clone(), cx);
+                            this.load_history_entry(index, cx)
                         }),
                     )
-            });
-            let scroll = div()
+            })
+            .collect();
+
+        let list = if items.is_empty() {
+            div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("No queries have been run on this profile yet.")
+                .into_any()
+        } else {
+            div()
+                .id("query-history")
+                .flex()
+                .flex_col()
+                .gap_1()
                 .max_h(px(LIST_SCROLL_MAX_HEIGHT))
-                .min_w(px(0.))
                 .overflow_y_scroll()
-                .restrict_scroll_to_axis()
-                .id("column_list_scroll")
-                .p_1()
-                .rounded_md()
-                .bg(rgb(COLOR_PANEL_MUTED))
-                .border_1()
-                .border_color(rgb(COLOR_BORDER))
-                .child(div().flex().flex_col().gap_1().children(items));
-            div().absorb_vertical_scroll().child(scroll).into_any()
+                .children(items)
+                .into_any()
         };
 
-        let mut panel =
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("Click a query to load it into the editor."),
+            )
+            .child(list)
+    }
+
+    fn render_compare_picker(&mut self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let items = self.profiles.iter().map(|profile| {
+            let profile_id = profile.id;
+            let is_checked = self.compare_run.selected_profiles.contains(&profile_id);
             div()
                 .flex()
-                .flex_col()
+                .items_center()
                 .gap_2()
-                .p_4()
-                .rounded_lg()
-                .bg(rgb(COLOR_PANEL))
+                .p_2()
+                .rounded_md()
+                .bg(theme.panel_muted)
                 .border_1()
-                .border_color(rgb(COLOR_BORDER))
+                .border_color(if is_checked {
+                    theme.accent
+                } else {
+                    theme.border
+                })
+                .hover(|style| style.bg(theme.panel_highlight))
+                .cursor_pointer()
                 .child(
                     div()
-                        .text_sm()
-                        .text_color(rgb(COLOR_TEXT_MUTED))
-                        .child("Schema Browser"),
+                        .flex_shrink_0()
+                        .w(px(12.))
+                        .h(px(12.))
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(theme.accent)
+                        .when(is_checked, |node| node.bg(theme.accent)),
                 )
-                .child(
-                    div()
-                        .flex()
-                        .gap_3()
-                        .child(
-                            div()
-                                .flex()
-                                .flex_col()
-                                .gap_1()
-                                .child(
-                                    div()
-                                        .text_xs()
-                                        .text_color(rgb(COLOR_TEXT_MUTED))
-                                        .child("Schemas"),
-                                )
-                                .child(schema_list),
-                        )
-                        .child(
-                            div()
-                                .flex()
-                                .flex_col()
-                                .gap_1()
-                                .child(
-                                    div()
-                                        .text_xs()
-                                        .text_color(rgb(COLOR_TEXT_MUTED))
-                                        .child("Tables"),
-                                )
-                                .child(table_list),
-                        )
-                        .child(
-                            div()
-                                .flex()
-                                .flex_col()
-                                .gap_1()
-                                .flex_grow()
-                                .child(
-                                    div()
-                                        .text_xs()
-                                        .text_color(rgb(COLOR_TEXT_MUTED))
-                                        .child("Columns"),
-                                )
-                                .child(column_list),
-                        ),
+                .child(div().text_sm().child(profile.name.clone()))
+                .child(div().text_xs().text_color(theme.text_muted).child(format!(
+                    "{}@{}:{}",
+                    profile.username, profile.host, profile.port
+                )))
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                        this.toggle_compare_profile(profile_id, cx)
+                    }),
                 )
-                .child(div().text_xs().text_color(rgb(COLOR_TEXT_MUTED)).child(
-                    "Right-click to copy schema/table names. Left-click copies column names.",
-                ))
-                .child(self.render_preview_panel());
-
-        if let Some(error) = self.schema_browser.last_error.clone() {
-            panel = panel.child(error_banner(&error));
-        }
-
-        panel
-    }
-
-    fn render_preview_panel(&mut self) -> impl Element {
-        let header = if let (Some(schema), Some(table)) = (
-            self.schema_browser.selected_schema.as_ref(),
-            self.schema_browser.selected_table.as_ref(),
-        ) {
-            format!("Preview: {schema}.{table} (up to {PREVIEW_LIMIT} rows)")
-        } else {
-            "Table preview".into()
-        };
-
-        let content: AnyElement = if self.schema_browser.preview_loading {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Loading preview...")
-                .into_any()
-        } else if let Some(view) = self.schema_browser.preview.as_ref() {
+        });
+        let selected_count = self.compare_run.selected_profiles.len();
+        let confirm = self.compare_run.confirm_destructive.clone().map(|message| {
             div()
-                .max_h(px(260.))
-                .w_full()
-                .min_w(px(0.))
-                .overflow_x_scroll()
-                .restrict_scroll_to_axis()
-                .id("preview_table_scroll")
-                .p_2()
+                .flex()
+                .items_center()
+                .gap_2()
+                .p_3()
                 .rounded_md()
-                .bg(rgb(COLOR_PANEL_MUTED))
+                .bg(theme.danger_surface)
                 .border_1()
-                .border_color(rgb(COLOR_BORDER))
-                .child(self.render_result_table(
-                    view,
-                    Some(px(210.)),
-                    Some("preview_table_body_scroll"),
+                .border_color(theme.danger)
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .text_color(theme.danger_soft)
+                        .child(message),
+                )
+                .child(editor_button(&theme, "Run anyway").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.start_compare_run(true, cx)
+                    }),
                 ))
-                .into_any()
-        } else {
-            div()
-                .text_sm()
-                .text_color(rgb(COLOR_TEXT_MUTED))
-                .child("Select a table to see its preview")
-                .into_any()
-        };
+                .child(editor_button(&theme, "Cancel").on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                        this.compare_run.confirm_destructive = None;
+                        cx.notify();
+                    }),
+                ))
+        });
 
         div()
             .flex()
@@ -1524,95 +7838,273 @@ impl DbMiruApp {
             .gap_2()
             .p_3()
             .rounded_lg()
-            .bg(rgb(COLOR_PANEL_MUTED))
+            .bg(theme.panel_muted)
             .border_1()
-            .border_color(rgb(COLOR_BORDER))
+            .border_color(theme.border)
             .child(
                 div()
-                    .text_sm()
-                    .text_color(rgb(COLOR_TEXT_MUTED))
-                    .child(header),
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("Runs the current SQL on each selected profile in turn."),
+            )
+            .child(div().flex().flex_col().gap_1().children(items))
+            .children(confirm)
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .px_4()
+                            .py_2()
+                            .bg(theme.accent)
+                            .hover(|style| style.bg(theme.accent_soft))
+                            .text_color(theme.on_accent)
+                            .rounded_full()
+                            .text_sm()
+                            .child(format!("Run on {selected_count} profile(s)"))
+                            .cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.start_compare_run(false, cx)
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_4()
+                            .py_2()
+                            .bg(theme.panel_highlight)
+                            .rounded_full()
+                            .text_sm()
+                            .child("Cancel")
+                            .cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.toggle_compare_picker(cx)
+                                }),
+                            ),
+                    ),
             )
-            .child(content)
     }
 
-    fn render_editor_panel(&mut self, cx: &mut Context<Self>) -> impl Element {
-        let mut panel = div()
+    fn render_compare_panel(&self, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let is_running = self.compare_run.is_running();
+        let panels = self
+            .compare_run
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(idx, target)| {
+                let content: AnyElement = match &target.status {
+                    CompareTargetStatus::Pending => div()
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child("Waiting...")
+                        .into_any(),
+                    CompareTargetStatus::Connecting => div()
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child("Connecting...")
+                        .into_any(),
+                    CompareTargetStatus::Running => div()
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child("Query is running...")
+                        .into_any(),
+                    CompareTargetStatus::Finished(view) => div()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(theme.text_muted)
+                                .child(result_summary(view, self.compare_run.row_limit)),
+                        )
+                        .child(
+                            div()
+                                .w_full()
+                                .min_w(px(0.))
+                                .overflow_x_scroll()
+                                .restrict_scroll_to_axis()
+                                .id("compare_table_scroll")
+                                .child(self.render_result_table(
+                                    &theme,
+                                    view,
+                                    Some(px(260.)),
+                                    Some("compare_table_body_scroll"),
+                                    None,
+                                )),
+                        )
+                        .into_any(),
+                    CompareTargetStatus::Completed(message) => div()
+                        .text_sm()
+                        .text_color(theme.success)
+                        .child(message.clone())
+                        .into_any(),
+                    CompareTargetStatus::Failed(message) => {
+                        error_banner(&theme, message).into_any()
+                    }
+                };
+                div()
+                    .id(("compare_result", idx))
+                    .flex()
+                    .flex_col()
+                    .flex_shrink_0()
+                    .gap_2()
+                    .w(px(COMPARE_PANEL_WIDTH))
+                    .p_3()
+                    .rounded_lg()
+                    .bg(theme.panel_muted)
+                    .border_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_bright)
+                            .child(target.profile_name.clone()),
+                    )
+                    .child(content)
+            });
+
+        div()
             .flex()
             .flex_col()
             .gap_2()
             .p_4()
             .rounded_lg()
-            .bg(rgb(COLOR_PANEL))
+            .bg(theme.panel)
             .border_1()
-            .border_color(rgb(COLOR_BORDER))
-            .key_context("SqlEditor")
-            .on_action(cx.listener(|this, _: &RunQuery, _, cx| this.execute_query(cx)))
-            .child(
-                div()
-                    .text_sm()
-                    .text_color(rgb(COLOR_TEXT_MUTED))
-                    .child("SQL Editor"),
-            )
-            .child(
-                div()
-                    .border_1()
-                    .border_color(rgb(COLOR_BORDER))
-                    .rounded_md()
-                    .bg(rgb(COLOR_PANEL_MUTED))
-                    .child(self.sql_input.clone()),
-            )
+            .border_color(theme.border)
             .child(
                 div()
                     .flex()
-                    .gap_2()
+                    .justify_between()
+                    .items_center()
                     .child(
                         div()
-                            .px_4()
-                            .py_2()
-                            .bg(rgb(COLOR_ACCENT))
-                            .hover(|style| style.bg(rgb(COLOR_ACCENT_SOFT)))
-                            .rounded_full()
                             .text_sm()
-                            .child("Run (Cmd/Ctrl + Enter)")
+                            .text_color(theme.text_muted)
+                            .child("Comparison"),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_full()
+                            .bg(theme.panel_highlight)
+                            .text_xs()
+                            .child(if is_running { "Stop" } else { "Close" })
                             .cursor_pointer()
                             .on_mouse_up(
                                 MouseButton::Left,
                                 cx.listener(|this, _: &MouseUpEvent, _window, cx| {
-                                    this.execute_query(cx)
+                                    this.close_compare_run(cx)
                                 }),
                             ),
-                    )
-                    .when(
-                        matches!(self.query_state.status, QueryStatus::Running),
-                        |node| node.child(div().text_sm().child("Running...")),
                     ),
-            );
-
-        if let Some(text) = self.query_state.last_error.clone() {
-            panel = panel.child(error_banner(&text));
-        }
-
-        panel
+            )
+            .child(
+                div()
+                    .w_full()
+                    .min_w(px(0.))
+                    .overflow_x_scroll()
+                    .restrict_scroll_to_axis()
+                    .id("compare_scroll")
+                    .child(div().flex().gap_3().children(panels)),
+            )
     }
 
-    fn render_results_panel(&self) -> impl Element {
+    fn render_results_panel(&mut self, window: &Window, cx: &mut Context<Self>) -> impl Element {
+        let theme = *cx.theme();
+        let body_height = self.results_body_height(window);
+        self.query_state.result_search = self.result_search_input.read(cx).text();
+        self.sync_column_filters(cx);
         let content = match &self.query_state.last_result {
-            Some(result) => {
-                let meta = if result.truncated {
-                    format!(
-                        "{} rows ({} ms, showing top {} / max {ROW_LIMIT})",
-                        result.row_count,
-                        result.duration.as_millis(),
-                        result.rows.len()
+            Some(result) if self.query_state.plan_view => {
+                let plan = result
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.first())
+                    .map(Cell::as_str)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let plan_for_copy = plan.clone();
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div().text_sm().text_color(theme.text_muted).child(format!(
+                                    "Query plan ({} ms)",
+                                    result.duration.as_millis()
+                                )),
+                            )
+                            .child(result_action_button(&theme, "Copy plan").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    this.copy_to_clipboard(plan_for_copy.clone(), cx)
+                                }),
+                            )),
                     )
-                } else {
-                    format!(
-                        "{} rows ({} ms)",
-                        result.row_count,
-                        result.duration.as_millis()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .p_2()
+                            .rounded_md()
+                            .bg(theme.panel_muted)
+                            .border_1()
+                            .border_color(theme.border)
+                            .max_h(body_height)
+                            .overflow_y_scroll()
+                            .id("query_plan_scroll")
+                            .children(
+                                plan.lines()
+                                    .map(|line| {
+                                        div().text_sm().whitespace_nowrap().child(line.to_string())
+                                    })
+                                    .collect::<Vec<_>>(),
+                            ),
                     )
-                };
+            }
+            Some(result) => {
+                let page = self.query_state.page;
+                let pageable = self.page_sql(page).is_some();
+                let paged = pageable && (page > 0 || result.truncated);
+                let meta = self.result_meta(result);
+                let has_prev = page > 0;
+                let has_next = result.truncated;
+                let detail = self
+                    .query_state
+                    .selected_row
+                    .filter(|row| *row < result.rows.len())
+                    .map(|row| self.render_row_detail(result, row, cx));
+                let search = self.query_state.result_search.trim();
+                let search_mode = self.query_state.search_mode;
+                let column_filtered = result.has_column_filters();
+                let match_summary = (!search.is_empty() || column_filtered).then(|| {
+                    let matching_rows = (0..result.rows.len())
+                        .filter(|idx| {
+                            result.matches_column_filters(*idx)
+                                && (search.is_empty()
+                                    || result.rows[*idx]
+                                        .iter()
+                                        .any(|cell| cell_matches(cell, search)))
+                        })
+                        .count();
+                    format!("{matching_rows} of {} rows match", result.rows.len())
+                });
+                let column_filters_open = self.query_state.column_filters_open;
 
                 div()
                     .flex()
@@ -1620,9 +8112,128 @@ impl DbMiruApp {
                     .gap_1()
                     .child(
                         div()
-                            .text_sm()
-                            .text_color(rgb(COLOR_TEXT_MUTED))
-                            .child(meta),
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(theme.text_muted)
+                                    .child(meta),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .gap_2()
+                                    .when(paged, |node| {
+                                        node.child(
+                                            result_action_button(&theme, "Prev")
+                                                .when(!has_prev, |node| node.opacity(0.4))
+                                                .when(has_prev, |node| {
+                                                    node.on_mouse_up(
+                                                        MouseButton::Left,
+                                                        cx.listener(
+                                                            move |this, _: &MouseUpEvent, _window, cx| {
+                                                                this.go_to_page(page - 1, cx)
+                                                            },
+                                                        ),
+                                                    )
+                                                }),
+                                        )
+                                        .child(
+                                            result_action_button(&theme, "Next")
+                                                .when(!has_next, |node| node.opacity(0.4))
+                                                .when(has_next, |node| {
+                                                    node.on_mouse_up(
+                                                        MouseButton::Left,
+                                                        cx.listener(
+                                                            move |this, _: &MouseUpEvent, _window, cx| {
+                                                                this.go_to_page(page + 1, cx)
+                                                            },
+                                                        ),
+                                                    )
+                                                }),
+                                        )
+                                    })
+                                    .child(result_action_button(&theme, "Copy all (TSV)").on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.copy_result_as_tsv(false, cx)
+                                        }),
+                                    ))
+                                    .when(detail.is_some(), |node| {
+                                        node.child(
+                                            result_action_button(&theme, "Copy selected row")
+                                                .on_mouse_up(
+                                                    MouseButton::Left,
+                                                    cx.listener(
+                                                        |this, _: &MouseUpEvent, _window, cx| {
+                                                            this.copy_result_as_tsv(true, cx)
+                                                        },
+                                                    ),
+                                                ),
+                                        )
+                                    })
+                                    .child(result_action_button(&theme, "Copy as JSON").on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.copy_result_as_json(true, cx)
+                                        }),
+                                    ))
+                                    .child(
+                                        result_action_button(&theme, "Copy as JSON (strings)").on_mouse_up(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                                this.copy_result_as_json(false, cx)
+                                            }),
+                                        ),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(filter_box(&theme, self.result_search_input.clone()).w(px(240.)))
+                            .child(
+                                selector_pill(&theme, "Filter rows", search_mode == SearchMode::Filter)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.query_state.search_mode = SearchMode::Filter;
+                                            cx.notify();
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                selector_pill(&theme, "Highlight", search_mode == SearchMode::Highlight)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.query_state.search_mode = SearchMode::Highlight;
+                                            cx.notify();
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                selector_pill(&theme, "Column filters", column_filters_open)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.toggle_column_filters(cx)
+                                        }),
+                                    ),
+                            )
+                            .when_some(match_summary, |node, summary| {
+                                node.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.text_muted)
+                                        .child(summary),
+                                )
+                            }),
                     )
                     .child(
                         div()
@@ -1632,18 +8243,34 @@ impl DbMiruApp {
                             .restrict_scroll_to_axis()
                             .id("result_table_scroll")
                             .child(self.render_result_table(
+                                &theme,
                                 result,
-                                Some(px(320.)),
+                                Some(body_height),
                                 Some("result_table_body_scroll"),
+                                Some((ResultGrid::Query, cx)),
                             )),
                     )
+                    .children(detail)
+            }
+            None if self.query_state.last_completion.is_some() => div()
+                .px_3()
+                .py_2()
+                .rounded_md()
+                .bg(theme.panel_muted)
+                .border_1()
+                .border_color(theme.success)
+                .text_sm()
+                .text_color(theme.success)
+                .child(self.query_state.last_completion.clone().unwrap_or_default()),
+            None => {
+                div()
+                    .text_sm()
+                    .text_color(theme.text_muted)
+                    .child(match self.query_state.status {
+                        QueryStatus::Running => "Query is running...",
+                        QueryStatus::Idle => "Results will appear here.",
+                    })
             }
-            None => div().text_sm().text_color(rgb(COLOR_TEXT_MUTED)).child(
-                match self.query_state.status {
-                    QueryStatus::Running => "Query is running...",
-                    QueryStatus::Idle => "Results will appear here.",
-                },
-            ),
         };
 
         div()
@@ -1652,83 +8279,521 @@ impl DbMiruApp {
             .gap_2()
             .p_4()
             .rounded_lg()
-            .bg(rgb(COLOR_PANEL))
+            .bg(theme.panel)
             .border_1()
-            .border_color(rgb(COLOR_BORDER))
+            .border_color(theme.border)
             .child(
                 div()
-                    .text_sm()
-                    .text_color(rgb(COLOR_TEXT_MUTED))
-                    .child("Results / Errors"),
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child("Results / Errors"),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                selector_pill(&theme, "Type colors", self.settings.type_colors)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.toggle_type_colors(cx)
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                selector_pill(
+                                    &theme,
+                                    "1,000 separators",
+                                    self.settings.thousands_separators,
+                                )
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                        this.toggle_thousands_separators(cx)
+                                    }),
+                                ),
+                            )
+                            .child(
+                                selector_pill(&theme, "Wrap text", self.settings.wrap_cells)
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                            this.toggle_wrap_cells(cx)
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                result_action_button(
+                                    &theme,
+                                    if self.results_maximized {
+                                        "Restore editor"
+                                    } else {
+                                        "Maximize results"
+                                    },
+                                )
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                        this.toggle_results_maximized(cx)
+                                    }),
+                                ),
+                            )
+                            .child(result_action_button(&theme, "Pop out").on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                                    this.open_results_window(cx)
+                                }),
+                            )),
+                    ),
             )
             .child(content)
+            .children(
+                (self.query_state.timings.len() > 1)
+                    .then(|| statement_timings(&theme, &self.query_state.timings)),
+            )
+    }
+
+    fn result_meta(&self, result: &QueryResultView) -> String {
+        let page = self.query_state.page;
+        let paged = self.page_sql(page).is_some() && (page > 0 || result.truncated);
+        let meta = if paged {
+            page_summary(result, page, self.query_state.row_limit)
+        } else {
+            result_summary(result, self.query_state.row_limit)
+        };
+        match timing_breakdown(&self.query_state.timings) {
+            Some(breakdown) => format!("{meta} · {breakdown}"),
+            None => meta,
+        }
+    }
+
+    fn open_results_window(&mut self, cx: &mut Context<Self>) {
+        if let Some(handle) = self.results_window
+            && handle
+                .update(cx, |_, window, _| window.activate_window())
+                .is_ok()
+        {
+            return;
+        }
+        let app = cx.entity();
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                gpui::size(px(900.), px(560.)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("DbMiru — Results".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        match cx.open_window(options, |_, cx| cx.new(|cx| ResultsWindow::new(app, cx))) {
+            Ok(handle) => self.results_window = Some(handle),
+            Err(err) => tracing::warn!("Failed to open results window: {err:?}"),
+        }
+    }
+
+    fn render_row_detail(
+        &self,
+        view: &QueryResultView,
+        row: usize,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let theme = *cx.theme();
+        let fields =
+            view.columns
+                .iter()
+                .zip(&view.rows[row])
+                .enumerate()
+                .map(|(idx, (column, value))| {
+                    let is_json = view
+                        .column_metadata
+                        .get(idx)
+                        .is_some_and(|metadata| is_json_type(&metadata.data_type))
+                        && value.as_text().is_some();
+                    let expanded = is_json && self.query_state.pretty_json.contains(&idx);
+                    let pretty = value.as_text().filter(|_| expanded).and_then(pretty_json);
+                    let value_for_copy =
+                        pretty.clone().unwrap_or_else(|| value.as_str().to_string());
+                    let content = match pretty {
+                        Some(pretty) => div()
+                            .flex()
+                            .flex_col()
+                            .text_color(theme.text_bright)
+                            .children(
+                                pretty
+                                    .lines()
+                                    .map(|line| div().whitespace_nowrap().child(line.to_string()))
+                                    .collect::<Vec<_>>(),
+                            ),
+                        None => cell_content(&theme, value, 0),
+                    };
+                    div()
+                        .flex()
+                        .items_start()
+                        .gap_2()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(theme.border)
+                        .child(
+                            div()
+                                .flex_shrink_0()
+                                .w(px(RESULT_COL_MIN_WIDTH))
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child(column.clone()),
+                        )
+                        .child(content.flex_1().min_w(px(0.)).text_sm())
+                        .when(is_json, |node| {
+                            node.child(
+                                div()
+                                    .id(("row_detail_json", idx))
+                                    .flex_shrink_0()
+                                    .px_2()
+                                    .rounded_full()
+                                    .text_xs()
+                                    .bg(theme.panel_highlight)
+                                    .hover(|style| style.bg(theme.accent_soft))
+                                    .cursor_pointer()
+                                    .child(if expanded { "Compact" } else { "Pretty" })
+                                    .on_mouse_up(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                            this.toggle_pretty_json(idx, cx);
+                                        }),
+                                    ),
+                            )
+                        })
+                        .child(
+                            div()
+                                .id(("row_detail_copy", idx))
+                                .flex_shrink_0()
+                                .px_2()
+                                .rounded_full()
+                                .text_xs()
+                                .bg(theme.panel_highlight)
+                                .hover(|style| style.bg(theme.accent_soft))
+                                .cursor_pointer()
+                                .child("Copy")
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        this.copy_to_clipboard(value_for_copy.clone(), cx);
+                                    }),
+                                ),
+                        )
+                });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_3()
+            .rounded_md()
+            .bg(theme.panel_muted)
+            .border_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child(format!("Row #{}", row + 1)),
+                    )
+                    .child(result_action_button(&theme, "Close").on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseUpEvent, _window, cx| {
+                            this.query_state.selected_row = None;
+                            cx.notify();
+                        }),
+                    )),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .max_h(px(260.))
+                    .overflow_y_scroll()
+                    .id("row_detail_scroll")
+                    .children(fields),
+            )
+            .into_any()
     }
 
     fn render_result_table(
         &self,
+        theme: &Theme,
         view: &QueryResultView,
         max_body_height: Option<Pixels>,
         body_scroll_id: Option<&'static str>,
+        // Grids given a context sort on header clicks; only the query grid also
+        // supports row selection, search, and column resizing.
+        mut grid_cx: Option<(ResultGrid, &mut Context<Self>)>,
     ) -> AnyElement {
-        let col_width = px(RESULT_COL_MIN_WIDTH);
-        let total_width =
-            px(RESULT_NUMBER_WIDTH + view.columns.len() as f32 * RESULT_COL_MIN_WIDTH);
+        let density = self.settings.row_density;
+        let total_width = px(view.total_width(density));
         let header = div()
             .flex()
             .flex_shrink_0()
             .min_w(total_width)
             .border_b_1()
-            .border_color(rgb(COLOR_BORDER))
-            .bg(rgb(COLOR_PANEL_HIGHLIGHT))
+            .border_color(theme.border)
+            .bg(theme.panel_highlight)
             .child(
-                div()
+                grid_padding(div(), density)
                     .flex_shrink_0()
-                    .w(px(RESULT_NUMBER_WIDTH))
+                    .w(px(row_number_width(density)))
                     .text_xs()
-                    .text_color(rgb(0xfdf4ff))
-                    .p_2()
+                    .text_color(theme.text_bright)
                     .child("#"),
             )
-            .children(view.columns.iter().map(|col| {
-                div()
+            .children(view.columns.iter().enumerate().map(|(col_idx, col)| {
+                let label = match view.sort {
+                    Some(sort) if sort.column == col_idx => match sort.direction {
+                        SortDirection::Ascending => format!("{col} ▲"),
+                        SortDirection::Descending => format!("{col} ▼"),
+                    },
+                    _ => col.clone(),
+                };
+                let cell = grid_padding(div(), density)
+                    .relative()
+                    .flex()
+                    .flex_col()
                     .flex_shrink_0()
-                    .w(col_width)
-                    .text_sm()
-                    .text_color(rgb(0xfdf4ff))
-                    .p_2()
-                    .child(col.clone())
+                    .w(px(view.column_width(col_idx)))
+                    .when(view.is_numeric_column(col_idx), |node| node.items_end())
+                    .child(
+                        grid_text(div(), density)
+                            .text_color(theme.text_bright)
+                            .child(label),
+                    )
+                    .when_some(view.column_metadata.get(col_idx), |node, metadata| {
+                        node.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child(column_type_label(metadata)),
+                        )
+                    });
+                match grid_cx.as_mut() {
+                    Some((grid, cx)) => {
+                        let grid = *grid;
+                        cell.cursor_pointer()
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                    if this.query_state.column_resize.is_none() {
+                                        this.toggle_result_sort(grid, col_idx, cx)
+                                    }
+                                }),
+                            )
+                            .when(grid == ResultGrid::Query, |node| {
+                                node.child(
+                                    div()
+                                        .absolute()
+                                        .top_0()
+                                        .bottom_0()
+                                        .right_0()
+                                        .w(px(RESULT_RESIZE_HANDLE_WIDTH))
+                                        .cursor_col_resize()
+                                        .hover(|style| style.bg(theme.border))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(
+                                                move |this, event: &MouseDownEvent, _window, cx| {
+                                                    cx.stop_propagation();
+                                                    this.start_column_resize(col_idx, event, cx);
+                                                },
+                                            ),
+                                        ),
+                                )
+                            })
+                    }
+                    None => cell,
+                }
             }));
 
-        let rows = view.rows.iter().enumerate().map(|(idx, row)| {
+        let mut sort_cx = match grid_cx {
+            Some((ResultGrid::Query, cx)) => Some(cx),
+            _ => None,
+        };
+        let column_filters = sort_cx.is_some();
+        let filter_row = (column_filters && self.query_state.column_filters_open).then(|| {
             div()
                 .flex()
                 .flex_shrink_0()
                 .min_w(total_width)
                 .border_b_1()
-                .border_color(rgb(COLOR_BORDER))
-                .bg(rgb(COLOR_PANEL_MUTED))
-                .hover(|style| style.bg(rgb(COLOR_PANEL_HIGHLIGHT)))
-                .child(
-                    div()
-                        .flex_shrink_0()
-                        .w(px(RESULT_NUMBER_WIDTH))
-                        .text_xs()
-                        .text_color(rgb(COLOR_TEXT_MUTED))
-                        .p_2()
-                        .child(format!("#{}", idx + 1)),
-                )
-                .children(row.iter().map(|cell| {
+                .border_color(theme.border)
+                .bg(theme.panel_muted)
+                .child(div().flex_shrink_0().w(px(row_number_width(density))))
+                .children((0..view.columns.len()).map(|col_idx| {
                     div()
                         .flex_shrink_0()
-                        .w(col_width)
-                        .p_2()
-                        .text_sm()
-                        .text_color(rgb(0xf7f8ff))
-                        .child(cell.clone())
+                        .w(px(view.column_width(col_idx)))
+                        .p_1()
+                        .children(
+                            self.column_filter_inputs
+                                .get(col_idx)
+                                .map(|input| filter_box(theme, input.clone())),
+                        )
                 }))
         });
+        let selected_row = if sort_cx.is_some() {
+            self.query_state.selected_row
+        } else {
+            None
+        };
+        let search = if sort_cx.is_some() {
+            self.query_state.result_search.trim()
+        } else {
+            ""
+        };
+        let highlight = !search.is_empty() && self.query_state.search_mode == SearchMode::Highlight;
+        let rows = view
+            .display_order()
+            .into_iter()
+            .filter(|idx| {
+                (!column_filters || view.matches_column_filters(*idx))
+                    && (search.is_empty()
+                        || highlight
+                        || view.rows[*idx]
+                            .iter()
+                            .any(|cell| cell_matches(cell, search)))
+            })
+            .map(|idx| {
+                let row = &view.rows[idx];
+                let node = div()
+                    .flex()
+                    .flex_shrink_0()
+                    .min_w(total_width)
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .bg(if selected_row == Some(idx) {
+                        theme.panel_highlight
+                    } else {
+                        theme.panel_muted
+                    })
+                    .hover(|style| style.bg(theme.panel_highlight))
+                    .child(
+                        grid_padding(div(), density)
+                            .flex_shrink_0()
+                            .w(px(row_number_width(density)))
+                            .text_xs()
+                            .text_color(theme.text_muted)
+                            .child(format!("#{}", idx + 1)),
+                    )
+                    .children(row.iter().enumerate().map(|(col_idx, cell)| {
+                        let flattened;
+                        let cell = match cell {
+                            Cell::Text(value)
+                                if !self.settings.wrap_cells && value.contains(['\n', '\r']) =>
+                            {
+                                flattened = Cell::Text(single_line(value));
+                                &flattened
+                            }
+                            _ => cell,
+                        };
+                        let numeric = view.is_numeric_column(col_idx);
+                        let content = if numeric {
+                            numeric_cell_content(
+                                theme,
+                                cell,
+                                self.settings.cell_max_chars,
+                                self.settings.thousands_separators,
+                            )
+                        } else {
+                            cell_content(theme, cell, self.settings.cell_max_chars)
+                        };
+                        // NULL and empty cells keep their own dimmed style.
+                        let tint = match cell {
+                            Cell::Text(value) if self.settings.type_colors && !value.is_empty() => {
+                                if numeric {
+                                    Some(theme.number)
+                                } else if view.is_boolean_column(col_idx) {
+                                    Some(theme.boolean)
+                                } else {
+                                    None
+                                }
+                            }
+                            _ => None,
+                        };
+                        let content = content.when_some(tint, |node, color| node.text_color(color));
+                        let content = if self.settings.wrap_cells {
+                            let line_height = wrapped_line_height(density);
+                            content
+                                .line_height(rems(line_height))
+                                .max_h(rems(line_height * WRAPPED_CELL_MAX_LINES))
+                                .overflow_hidden()
+                        } else {
+                            content.truncate()
+                        };
+                        grid_text(grid_padding(content, density), density)
+                            .flex_shrink_0()
+                            .w(px(view.column_width(col_idx)))
+                            .when(highlight && cell_matches(cell, search), |node| {
+                                node.bg(theme.match_bg)
+                            })
+                            // Bubbles up to the row, which still selects it.
+                            .when_some(sort_cx.as_deref_mut(), |node, cx| {
+                                node.on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                        if this.query_state.column_resize.is_none() {
+                                            this.copy_cell_value(idx, col_idx, cx)
+                                        }
+                                    }),
+                                )
+                            })
+                    }));
+                match sort_cx.as_deref_mut() {
+                    Some(cx) => node
+                        .cursor_pointer()
+                        .on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                if this.query_state.column_resize.is_none() {
+                                    this.select_result_row(idx, cx)
+                                }
+                            }),
+                        )
+                        .on_mouse_up(
+                            MouseButton::Right,
+                            cx.listener(move |this, _: &MouseUpEvent, _window, cx| {
+                                this.copy_row_as_insert(idx, cx)
+                            }),
+                        ),
+                    None => node,
+                }
+            });
 
-        let body = div().flex().flex_col().min_w(total_width).children(rows);
+        let body = div()
+            .flex()
+            .flex_col()
+            .min_w(total_width)
+            .children(rows)
+            .when(view.rows.is_empty(), |node| {
+                node.child(
+                    div()
+                        .p_2()
+                        .text_sm()
+                        .italic()
+                        .text_color(theme.text_muted)
+                        .child("No rows returned"),
+                )
+            });
 
         let body: AnyElement = if let Some(max_height) = max_body_height {
             let body_scroll_id = body_scroll_id.unwrap_or("result_table_body_scroll");
@@ -1744,33 +8809,986 @@ impl DbMiruApp {
                 .id(body_scroll_id)
                 .child(body);
 
-            div().absorb_vertical_scroll().child(scroll).into_any()
+            div().absorb_vertical_scroll().child(scroll).into_any()
+        } else {
+            body.into_any()
+        };
+
+        let table = div()
+            .flex()
+            .flex_col()
+            .flex_shrink_0()
+            .min_w(total_width)
+            .child(header)
+            .children(filter_row)
+            .child(body);
+        match sort_cx {
+            Some(cx) => table
+                .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _window, cx| {
+                    this.update_column_resize(event, cx)
+                }))
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, _cx| {
+                        this.query_state.column_resize = None;
+                    }),
+                )
+                .on_mouse_up_out(
+                    MouseButton::Left,
+                    cx.listener(|this, _: &MouseUpEvent, _window, _cx| {
+                        this.query_state.column_resize = None;
+                    }),
+                )
+                .into_any(),
+            None => table.into_any(),
+        }
+    }
+}
+
+fn completion_message(affected: u64, tag: &str) -> String {
+    let counts_rows = matches!(
+        tag,
+        "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "COPY" | "SELECT" | "REPLACE"
+    );
+    match (counts_rows || affected > 0, affected) {
+        (true, 1) => format!("{tag}: 1 row affected"),
+        (true, affected) => format!("{tag}: {affected} rows affected"),
+        (false, _) if tag.is_empty() => "Statement completed.".into(),
+        (false, _) => format!("{tag} completed."),
+    }
+}
+
+fn column_type_label(column: &ColumnMetadata) -> String {
+    match column.nullable {
+        Some(true) => format!("{} · NULL", column.data_type),
+        Some(false) => format!("{} · NOT NULL", column.data_type),
+        None => column.data_type.clone(),
+    }
+}
+
+// The schema a diff side loads when its field is left empty.
+fn default_diff_schema(profile: &ConnectionProfile) -> String {
+    profile
+        .last_schema
+        .clone()
+        .unwrap_or_else(|| match profile.kind {
+            ProfileKind::Postgres => "public".into(),
+            ProfileKind::MySql => profile.database.clone(),
+        })
+}
+
+fn base_type(data_type: &str) -> String {
+    let data_type = data_type.to_ascii_lowercase();
+    let base = data_type.split('(').next().unwrap_or_default().trim();
+    base.trim_end_matches(" unsigned").to_string()
+}
+
+fn is_numeric_type(base: &str) -> bool {
+    matches!(
+        base,
+        "smallint"
+            | "integer"
+            | "bigint"
+            | "int"
+            | "int2"
+            | "int4"
+            | "int8"
+            | "tinyint"
+            | "mediumint"
+            | "smallserial"
+            | "serial"
+            | "bigserial"
+            | "numeric"
+            | "decimal"
+            | "real"
+            | "double precision"
+            | "double"
+            | "float"
+            | "float4"
+            | "float8"
+    )
+}
+
+// Checks the types whose text form is easy to verify up front; anything else is
+// left for the server to reject.
+fn csv_value_problem(data_type: &str, value: &str) -> Option<String> {
+    let base = base_type(data_type);
+    let trimmed = value.trim();
+    let valid = match base.as_str() {
+        "smallint" | "integer" | "bigint" | "int" | "int2" | "int4" | "int8" | "tinyint"
+        | "mediumint" | "smallserial" | "serial" | "bigserial" => trimmed.parse::<i64>().is_ok(),
+        base if is_numeric_type(base) => trimmed.parse::<f64>().is_ok(),
+        "boolean" | "bool" => matches!(
+            trimmed.to_ascii_lowercase().as_str(),
+            "true" | "false" | "t" | "f" | "yes" | "no" | "y" | "n" | "1" | "0"
+        ),
+        "date" => NaiveDate::parse_from_str(trimmed, "%Y-%m-%d").is_ok(),
+        "json" | "jsonb" => serde_json::from_str::<serde_json::Value>(value).is_ok(),
+        "uuid" => {
+            trimmed.len() == 36
+                && trimmed.char_indices().all(|(idx, ch)| match idx {
+                    8 | 13 | 18 | 23 => ch == '-',
+                    _ => ch.is_ascii_hexdigit(),
+                })
+        }
+        _ => true,
+    };
+    (!valid).then(|| {
+        format!(
+            "'{}' is not a valid {data_type}",
+            truncate_graphemes(value, 40)
+        )
+    })
+}
+
+fn is_temporal_type(base: &str) -> bool {
+    matches!(
+        base,
+        "date"
+            | "time"
+            | "time without time zone"
+            | "timestamp"
+            | "timestamp without time zone"
+            | "timestamptz"
+            | "timestamp with time zone"
+            | "datetime"
+    )
+}
+
+// Parses the date/time renderings the adapters produce; times of day sort on
+// a fixed date and zoned timestamps by their UTC instant.
+fn parse_temporal(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.naive_utc());
+    }
+    if let Ok(timestamp) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f") {
+        return Some(timestamp);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+    NaiveTime::parse_from_str(value, "%H:%M:%S%.f")
+        .ok()
+        .map(|time| NaiveDate::default().and_time(time))
+}
+
+fn sql_literal(kind: ProfileKind, cell: &Cell, data_type: Option<&str>) -> String {
+    let Some(value) = cell.as_text() else {
+        return "NULL".into();
+    };
+    let base = data_type.map(base_type).unwrap_or_default();
+    let bare = (is_numeric_type(&base) && value.parse::<f64>().is_ok_and(f64::is_finite))
+        || (matches!(base.as_str(), "boolean" | "bool") && matches!(value, "true" | "false"));
+    if bare {
+        return value.to_string();
+    }
+    let escaped = match kind {
+        ProfileKind::Postgres => value.replace('\'', "''"),
+        ProfileKind::MySql => value.replace('\\', "\\\\").replace('\'', "''"),
+    };
+    format!("'{escaped}'")
+}
+
+fn row_insert_statement(
+    kind: ProfileKind,
+    table: &str,
+    view: &QueryResultView,
+    row: usize,
+) -> String {
+    let names = view
+        .columns
+        .iter()
+        .map(|column| quote_identifier(kind, column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = view.rows[row]
+        .iter()
+        .enumerate()
+        .map(|(idx, cell)| {
+            let data_type = view
+                .column_metadata
+                .get(idx)
+                .map(|metadata| metadata.data_type.as_str());
+            sql_literal(kind, cell, data_type)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("INSERT INTO {table} ({names}) VALUES ({values});")
+}
+
+fn placeholder_value(data_type: &str) -> &'static str {
+    let base = base_type(data_type);
+    match base.as_str() {
+        _ if is_numeric_type(&base) => "0",
+        "boolean" | "bool" => "false",
+        _ if base.contains("char") || base.contains("text") => "''",
+        _ => "NULL",
+    }
+}
+
+fn insert_template(
+    kind: ProfileKind,
+    schema: &str,
+    table: &str,
+    columns: &[ColumnMetadata],
+) -> String {
+    let names = columns
+        .iter()
+        .map(|column| quote_identifier(kind, &column.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = columns
+        .iter()
+        .map(|column| placeholder_value(&column.data_type))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "INSERT INTO {} ({names}) VALUES ({values});",
+        qualified_table_name(kind, schema, table)
+    )
+}
+
+fn update_template(
+    kind: ProfileKind,
+    schema: &str,
+    table: &str,
+    columns: &[ColumnMetadata],
+    keys: &[String],
+) -> String {
+    let assignment = |column: &ColumnMetadata| {
+        format!(
+            "{} = {}",
+            quote_identifier(kind, &column.name),
+            placeholder_value(&column.data_type)
+        )
+    };
+    let mut set_columns = columns
+        .iter()
+        .filter(|column| !keys.contains(&column.name))
+        .peekable();
+    let set = if set_columns.peek().is_some() {
+        set_columns.map(assignment).collect::<Vec<_>>()
+    } else {
+        columns.iter().map(assignment).collect()
+    };
+    let condition = if keys.is_empty() {
+        "<condition>".to_string()
+    } else {
+        keys.iter()
+            .map(|key| {
+                let value = columns
+                    .iter()
+                    .find(|column| &column.name == key)
+                    .map(|column| placeholder_value(&column.data_type))
+                    .unwrap_or("NULL");
+                format!("{} = {value}", quote_identifier(kind, key))
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    };
+    format!(
+        "UPDATE {} SET {} WHERE {condition};",
+        qualified_table_name(kind, schema, table),
+        set.join(", ")
+    )
+}
+
+fn param_label(kind: ProfileKind, index: usize) -> String {
+    match kind {
+        ProfileKind::Postgres => format!("${}", index + 1),
+        ProfileKind::MySql => format!("?{}", index + 1),
+    }
+}
+
+fn cell_matches(cell: &Cell, search: &str) -> bool {
+    cell.as_text()
+        .is_some_and(|text| text.to_lowercase().contains(&search.to_lowercase()))
+}
+
+// Numeric columns accept a comparison such as `>100` or `<= 2.5`; anything else
+// is a case-insensitive substring match, which NULL never satisfies.
+fn column_filter_matches(cell: &Cell, filter: &str, numeric: bool) -> bool {
+    let filter = filter.trim();
+    if filter.is_empty() {
+        return true;
+    }
+    if numeric && let Some(test) = numeric_comparison(filter) {
+        return cell
+            .as_text()
+            .and_then(|text| text.trim().parse::<f64>().ok())
+            .is_some_and(test);
+    }
+    cell_matches(cell, filter)
+}
+
+fn numeric_comparison(filter: &str) -> Option<impl Fn(f64) -> bool> {
+    const OPERATORS: [&str; 7] = [">=", "<=", "<>", "!=", ">", "<", "="];
+    let (operator, rest) = OPERATORS
+        .iter()
+        .find_map(|operator| filter.strip_prefix(operator).map(|rest| (*operator, rest)))?;
+    let bound = rest.trim().parse::<f64>().ok()?;
+    Some(move |value: f64| match operator {
+        ">=" => value >= bound,
+        "<=" => value <= bound,
+        "<>" | "!=" => value != bound,
+        ">" => value > bound,
+        "<" => value < bound,
+        _ => value == bound,
+    })
+}
+
+fn matches_filter(value: &str, filter: &str) -> bool {
+    let filter = filter.trim();
+    filter.is_empty() || value.to_lowercase().contains(&filter.to_lowercase())
+}
+
+fn profile_from_form_values(
+    values: &ProfileFormValues,
+) -> std::result::Result<ConnectionProfile, String> {
+    let required = |value: &str, field: &str| {
+        let value = value.trim();
+        if value.is_empty() {
+            Err(format!("{field} is required."))
+        } else {
+            Ok(value.to_string())
+        }
+    };
+    let name = required(&values.name, "Name")?;
+    let host = required(&values.host, "Host")?;
+    validate_host(&host, "Host")?;
+    let port = parse_port(&values.port).ok_or("Port must be a number from 1 to 65535.")?;
+    let database = required(&values.database, "Database")?;
+    let username = required(&values.username, "Username")?;
+    let connect_timeout_secs = values
+        .connect_timeout
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|secs| *secs > 0)
+        .ok_or("Connect timeout must be a whole number of seconds above 0.")?;
+    let default_row_limit =
+        parse_row_limit(&values.row_limit).ok_or("Row limit must be a number above 0.")?;
+    let keepalive_secs = values
+        .keepalive
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| "Keep-alive interval must be a whole number of seconds.")?;
+    let statement_timeout_secs = parse_statement_timeout(&values.statement_timeout)
+        .ok_or("Statement timeout must be a whole number of seconds.")?;
+    let connect_retries = values
+        .connect_retries
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| "Connect retries must be a whole number.")?;
+    let retry_backoff_secs = values
+        .retry_backoff
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| "Retry backoff must be a whole number of seconds.")?;
+    let ssh_tunnel = match values.ssh_host.trim() {
+        "" => None,
+        ssh_host => {
+            validate_host(ssh_host, "SSH host")?;
+            let port =
+                parse_port(&values.ssh_port).ok_or("SSH port must be a number from 1 to 65535.")?;
+            let ssh_user = values.ssh_user.trim().to_string();
+            if ssh_user.starts_with('-') {
+                return Err("SSH user must not start with \"-\".".into());
+            }
+            Some(SshTunnel {
+                host: ssh_host.to_string(),
+                port,
+                username: ssh_user,
+                key_path: Some(values.ssh_key.trim().to_string()).filter(|path| !path.is_empty()),
+            })
+        }
+    };
+    let mut profile = ConnectionProfile::new(
+        name,
+        host,
+        port,
+        database,
+        username,
+        values.remember_password,
+    );
+    profile.kind = values.kind;
+    profile.ssl_mode = values.ssl_mode;
+    profile.color = values.color;
+    profile.connect_timeout_secs = connect_timeout_secs;
+    profile.default_row_limit = default_row_limit;
+    profile.keepalive_secs = keepalive_secs;
+    profile.statement_timeout_secs = statement_timeout_secs;
+    profile.connect_retries = connect_retries;
+    profile.retry_backoff_secs = retry_backoff_secs;
+    profile.group = Some(values.group.trim().to_string()).filter(|group| !group.is_empty());
+    profile.auto_reconnect = values.auto_reconnect;
+    profile.read_only = values.read_only;
+    profile.confirm_destructive = values.confirm_destructive;
+    profile.ssh_tunnel = ssh_tunnel;
+    Ok(profile)
+}
+
+fn parse_port(value: &str) -> Option<u16> {
+    value.trim().parse().ok().filter(|port| *port != 0)
+}
+
+// Catches the usual paste mistakes: a URL, a `host:port` pair, or stray whitespace.
+// Unix socket directories (`/var/run/postgresql`) and IPv6 addresses pass.
+fn validate_host(host: &str, field: &str) -> std::result::Result<(), String> {
+    if host.chars().any(char::is_whitespace) {
+        return Err(format!("{field} must not contain spaces."));
+    }
+    if host.contains("://") {
+        return Err(format!(
+            "{field} must be a host name or address, not a URL; paste URLs into the connection string field and press Apply."
+        ));
+    }
+    if host.starts_with('/') {
+        return Ok(());
+    }
+    if host.matches(':').count() == 1 {
+        return Err(format!(
+            "{field} must not include a port; put it in the port field."
+        ));
+    }
+    if host.contains(['/', '@', '?', '#'])
+        || host.starts_with(['.', '-'])
+        || host.ends_with('-')
+        || host.contains("..")
+    {
+        return Err(format!(
+            "{field} \"{host}\" is not a valid host name or address."
+        ));
+    }
+    Ok(())
+}
+
+// Formatting only changes whitespace and letter case, so the cursor keeps its
+// place by staying behind the same number of non-whitespace characters.
+fn offset_in_reformatted(original: &str, offset: usize, formatted: &str) -> usize {
+    let visible = original[..offset.min(original.len())]
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .count();
+    if visible == 0 {
+        return 0;
+    }
+    formatted
+        .char_indices()
+        .filter(|(_, ch)| !ch.is_whitespace())
+        .nth(visible - 1)
+        .map(|(idx, ch)| idx + ch.len_utf8())
+        .unwrap_or(formatted.len())
+}
+
+fn parse_row_limit(value: &str) -> Option<usize> {
+    value.trim().parse().ok().filter(|limit| *limit > 0)
+}
+
+fn parse_statement_timeout(value: &str) -> Option<u64> {
+    match value.trim() {
+        "" => Some(0),
+        value => value.parse().ok(),
+    }
+}
+
+fn result_summary(result: &QueryResultView, limit: usize) -> String {
+    if result.truncated {
+        format!(
+            "{}+ rows ({} ms, stopped at max {limit})",
+            result.rows.len(),
+            result.duration.as_millis()
+        )
+    } else {
+        format!(
+            "{} rows ({} ms)",
+            result.row_count,
+            result.duration.as_millis()
+        )
+    }
+}
+
+fn timing_breakdown(timings: &[StatementTiming]) -> Option<String> {
+    match timings {
+        [
+            StatementTiming {
+                prepare: Some(prepare),
+                execute,
+                ..
+            },
+        ] => Some(format!(
+            "prepare {}, execute {}",
+            format_timing(*prepare),
+            format_timing(*execute)
+        )),
+        _ => None,
+    }
+}
+
+fn format_session_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+fn format_timing(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn statement_timings(theme: &Theme, timings: &[StatementTiming]) -> gpui::Div {
+    div()
+        .flex()
+        .flex_col()
+        .text_xs()
+        .text_color(theme.text_muted)
+        .child(format!("{} statements", timings.len()))
+        .children(timings.iter().enumerate().map(|(index, timing)| {
+            let first_line = timing
+                .statement
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default();
+            let total = timing.prepare.unwrap_or_default() + timing.execute;
+            div()
+                .flex()
+                .gap_2()
+                .child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .child(format!(
+                            "{}. {}",
+                            index + 1,
+                            truncate_graphemes(first_line, TIMING_STATEMENT_CHARS)
+                        )),
+                )
+                .child(format_timing(total))
+        }))
+}
+
+fn page_summary(result: &QueryResultView, page: usize, limit: usize) -> String {
+    let first = page * limit + 1;
+    let last = page * limit + result.rows.len();
+    let more = if result.truncated {
+        ", more available"
+    } else {
+        ""
+    };
+    if result.rows.is_empty() {
+        format!(
+            "Page {} · no rows ({} ms)",
+            page + 1,
+            result.duration.as_millis()
+        )
+    } else {
+        format!(
+            "Page {} · rows {first}–{last} ({} ms{more})",
+            page + 1,
+            result.duration.as_millis()
+        )
+    }
+}
+
+fn step_selection(names: &[String], current: Option<&str>, delta: isize) -> Option<String> {
+    if names.is_empty() {
+        return None;
+    }
+    let position = current.and_then(|current| names.iter().position(|name| name == current));
+    let next = match position {
+        Some(idx) => idx.saturating_add_signed(delta).min(names.len() - 1),
+        None if delta < 0 => names.len() - 1,
+        None => 0,
+    };
+    (position != Some(next)).then(|| names[next].clone())
+}
+
+fn row_number_width(density: RowDensity) -> f32 {
+    match density {
+        RowDensity::Comfortable => RESULT_NUMBER_WIDTH,
+        RowDensity::Compact => RESULT_NUMBER_WIDTH * COMPACT_GRID_SCALE,
+    }
+}
+
+fn grid_padding(node: gpui::Div, density: RowDensity) -> gpui::Div {
+    match density {
+        RowDensity::Comfortable => node.p_2(),
+        RowDensity::Compact => node.px_1p5().py_0p5(),
+    }
+}
+
+fn wrapped_line_height(density: RowDensity) -> f32 {
+    match density {
+        RowDensity::Comfortable => 1.25,
+        RowDensity::Compact => 1.,
+    }
+}
+
+// Shows line breaks as `↵` so a multi-line value stays on one grid line.
+fn single_line(value: &str) -> String {
+    value.replace("\r\n", "↵").replace(['\n', '\r'], "↵")
+}
+
+fn grid_text(node: gpui::Div, density: RowDensity) -> gpui::Div {
+    match density {
+        RowDensity::Comfortable => node.text_sm(),
+        RowDensity::Compact => node.text_xs(),
+    }
+}
+
+fn editor_button(theme: &Theme, label: impl Into<SharedString>) -> gpui::Div {
+    div()
+        .px_4()
+        .py_2()
+        .bg(theme.panel_highlight)
+        .border_1()
+        .border_color(theme.border)
+        .hover(|style| style.bg(theme.panel_muted))
+        .rounded_full()
+        .text_sm()
+        .cursor_pointer()
+        .child(label.into())
+}
+
+fn refresh_icon(theme: &Theme) -> gpui::Div {
+    div()
+        .text_xs()
+        .text_color(theme.text_muted)
+        .hover(|style| style.text_color(theme.accent))
+        .cursor_pointer()
+        .child("↻")
+}
+
+fn badge(theme: &Theme, label: &'static str) -> gpui::Div {
+    div()
+        .flex_shrink_0()
+        .px_2()
+        .rounded_full()
+        .bg(theme.panel_highlight)
+        .text_xs()
+        .text_color(theme.text_muted)
+        .child(label)
+}
+
+fn filter_box(theme: &Theme, input: gpui::Entity<TextInput>) -> gpui::Div {
+    div()
+        .border_1()
+        .border_color(theme.border)
+        .rounded_md()
+        .bg(theme.panel_muted)
+        .child(input)
+}
+
+fn form_checkbox(theme: &Theme, label: &'static str, checked: bool) -> gpui::Div {
+    div()
+        .flex()
+        .items_center()
+        .gap_2()
+        .cursor_pointer()
+        .child(
+            div()
+                .flex_shrink_0()
+                .w(px(12.))
+                .h(px(12.))
+                .rounded_sm()
+                .border_1()
+                .border_color(theme.accent)
+                .when(checked, |node| node.bg(theme.accent)),
+        )
+        .child(div().text_sm().child(label))
+}
+
+fn selector_pill(theme: &Theme, label: impl Into<SharedString>, is_active: bool) -> gpui::Div {
+    div()
+        .px_2()
+        .py_1()
+        .rounded_full()
+        .text_xs()
+        .when(is_active, |node| node.text_color(theme.on_accent))
+        .bg(if is_active {
+            theme.accent
+        } else {
+            theme.panel_highlight
+        })
+        .border_1()
+        .border_color(if is_active {
+            theme.accent_soft
+        } else {
+            theme.border
+        })
+        .cursor_pointer()
+        .child(label.into())
+}
+
+fn profile_color_dot(theme: &Theme, color: ProfileColor) -> gpui::Div {
+    div()
+        .flex_shrink_0()
+        .size(px(8.))
+        .rounded_full()
+        .bg(theme.profile_color(color))
+}
+
+fn format_row_estimate(rows: u64) -> String {
+    match rows {
+        0..1_000 => rows.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", rows as f64 / 1_000.0),
+        1_000_000..1_000_000_000 => format!("{:.1}M", rows as f64 / 1_000_000.0),
+        _ => format!("{:.1}B", rows as f64 / 1_000_000_000.0),
+    }
+}
+
+fn result_action_button(theme: &Theme, label: &'static str) -> gpui::Div {
+    div()
+        .px_2()
+        .py_1()
+        .rounded_full()
+        .text_xs()
+        .bg(theme.panel_highlight)
+        .hover(|style| style.bg(theme.accent_soft))
+        .cursor_pointer()
+        .child(label)
+}
+
+fn result_to_json(result: &QueryResultView, native_types: bool) -> String {
+    let keys = json_object_keys(&result.columns);
+    let mut out = String::from("[");
+    for (row_idx, row) in result.rows.iter().enumerate() {
+        out.push_str(if row_idx == 0 { "\n  {" } else { ",\n  {" });
+        for (col_idx, (key, cell)) in keys.iter().zip(row).enumerate() {
+            if col_idx > 0 {
+                out.push(',');
+            }
+            out.push_str(&serde_json::Value::from(key.as_str()).to_string());
+            out.push(':');
+            let value = match cell.as_text() {
+                None => serde_json::Value::Null,
+                Some(text) if native_types => native_json_value(text),
+                Some(text) => serde_json::Value::from(text),
+            };
+            out.push_str(&value.to_string());
+        }
+        out.push('}');
+    }
+    if !result.rows.is_empty() {
+        out.push('\n');
+    }
+    out.push(']');
+    out
+}
+
+fn json_object_keys(columns: &[String]) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::with_capacity(columns.len());
+    for (idx, column) in columns.iter().enumerate() {
+        // Unnamed columns (MySQL `SELECT ''`) are named after their position.
+        let base = if column.is_empty() {
+            format!("column_{}", idx + 1)
         } else {
-            body.into_any()
+            column.clone()
         };
+        let mut key = base.clone();
+        let mut suffix = 2;
+        while keys.contains(&key) || (key != *column && columns.contains(&key)) {
+            key = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        keys.push(key);
+    }
+    keys
+}
 
-        div()
-            .flex()
-            .flex_col()
-            .flex_shrink_0()
-            .min_w(total_width)
-            .child(header)
-            .child(body)
-            .into_any()
+fn native_json_value(cell: &str) -> serde_json::Value {
+    match cell {
+        "true" => return serde_json::Value::Bool(true),
+        "false" => return serde_json::Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(value) = cell.parse::<i64>()
+        && value.to_string() == cell
+    {
+        return value.into();
+    }
+    if let Ok(value) = cell.parse::<f64>()
+        && value.is_finite()
+        && value.to_string() == cell
+    {
+        return value.into();
+    }
+    cell.into()
+}
+
+fn display_width(value: &str) -> usize {
+    value
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+fn is_json_type(data_type: &str) -> bool {
+    matches!(base_type(data_type).as_str(), "json" | "jsonb")
+}
+
+fn pretty_json(text: &str) -> Option<String> {
+    if text.len() > JSON_PRETTY_MAX_BYTES {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+fn cell_content(theme: &Theme, cell: &Cell, max_chars: usize) -> gpui::Div {
+    match cell {
+        Cell::Null => div().italic().text_color(theme.null).child("NULL"),
+        Cell::Text(value) if value.is_empty() => {
+            div().italic().text_color(theme.null).child("(empty)")
+        }
+        Cell::Text(value) => div()
+            .text_color(theme.text_bright)
+            .child(truncate_graphemes(value, max_chars).into_owned()),
+    }
+}
+
+// Numbers line up on their last digit; grouping only changes what is drawn, so
+// copies and exports keep the raw value.
+fn numeric_cell_content(
+    theme: &Theme,
+    cell: &Cell,
+    max_chars: usize,
+    thousands_separators: bool,
+) -> gpui::Div {
+    match cell {
+        Cell::Text(value) if thousands_separators && !value.is_empty() => div()
+            .text_right()
+            .text_color(theme.text_bright)
+            .child(truncate_graphemes(&group_thousands(value), max_chars).into_owned()),
+        _ => cell_content(theme, cell, max_chars).text_right(),
+    }
+}
+
+// Inserts `,` every three digits of the integer part of a plain decimal number;
+// anything else (exponents, NaN, Infinity, money with a currency sign) is returned as is.
+fn group_thousands(value: &str) -> String {
+    let (sign, unsigned) = match value.strip_prefix(['-', '+']) {
+        Some(rest) => (&value[..1], rest),
+        None => ("", value),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if integer.len() <= 3 || !is_digits(integer) || !fraction.is_none_or(is_digits) {
+        return value.to_string();
+    }
+    let mut grouped = String::with_capacity(value.len() + integer.len() / 3);
+    grouped.push_str(sign);
+    for (idx, digit) in integer.chars().enumerate() {
+        if idx > 0 && (integer.len() - idx) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+fn truncate_graphemes(value: &str, max_chars: usize) -> Cow<'_, str> {
+    if max_chars == 0 || value.len() <= max_chars {
+        return Cow::Borrowed(value);
+    }
+    match value.grapheme_indices(true).nth(max_chars) {
+        Some((end, _)) => Cow::Owned(format!("{}…", &value[..end])),
+        None => Cow::Borrowed(value),
     }
 }
 
-fn connection_action_icon(status: &ConnectionStatus) -> gpui::Div {
+fn connection_action_icon(theme: &Theme, status: &ConnectionStatus) -> gpui::Div {
     let (color, size) = match status {
-        ConnectionStatus::Connected(_) => (rgb(COLOR_SUCCESS), px(10.)),
-        ConnectionStatus::Connecting(_) => (rgb(0xfbbf24), px(10.)),
-        ConnectionStatus::Disconnected => (rgb(COLOR_DANGER), px(8.)),
+        ConnectionStatus::Connected(_) => (theme.success, px(10.)),
+        ConnectionStatus::Connecting(_) => (theme.warning, px(10.)),
+        ConnectionStatus::Disconnected => (theme.danger, px(8.)),
     };
 
     div().w(size).h(size).rounded_full().bg(color)
 }
 
-fn error_banner(message: &str) -> gpui::Div {
+// One line of the schema diff: name, left definition, right definition. `None`
+// marks the header.
+fn schema_diff_row(
+    theme: &Theme,
+    kind: Option<DiffKind>,
+    name: String,
+    left: String,
+    right: String,
+    nested: bool,
+) -> gpui::Div {
+    let (marker, color) = match kind {
+        Some(DiffKind::Added) => ("+", theme.success),
+        Some(DiffKind::Removed) => ("−", theme.danger),
+        Some(DiffKind::Changed) => ("~", theme.warning),
+        Some(DiffKind::Unchanged) | None => ("", theme.text_muted),
+    };
+    let cell = |text: String| div().flex_1().min_w(px(0.)).truncate().child(text);
+    div()
+        .flex()
+        .items_center()
+        .gap_3()
+        .px_2()
+        .py_1()
+        .text_sm()
+        .border_b_1()
+        .border_color(theme.border)
+        .when(kind.is_none(), |node| {
+            node.text_xs().text_color(theme.text_muted)
+        })
+        .when(kind.is_some(), |node| {
+            node.hover(|style| style.bg(theme.panel_highlight))
+        })
+        .child(
+            div()
+                .flex()
+                .flex_1()
+                .min_w(px(0.))
+                .gap_2()
+                .when(nested, |node| node.pl_4())
+                .child(
+                    div()
+                        .w(px(10.))
+                        .flex_shrink_0()
+                        .text_color(color)
+                        .child(marker),
+                )
+                .child(
+                    div()
+                        .min_w(px(0.))
+                        .truncate()
+                        .when(!nested && kind.is_some(), |node| {
+                            node.text_color(theme.text_bright)
+                        })
+                        .child(name),
+                ),
+        )
+        .child(
+            cell(left).when(kind == Some(DiffKind::Changed) && nested, |node| {
+                node.text_color(color)
+            }),
+        )
+        .child(
+            cell(right).when(kind == Some(DiffKind::Changed) && nested, |node| {
+                node.text_color(color)
+            }),
+        )
+}
+
+fn error_banner(theme: &Theme, message: &str) -> gpui::Div {
     let message_text = SharedString::from(message.to_owned());
     div()
         .flex()
@@ -1778,9 +9796,9 @@ fn error_banner(message: &str) -> gpui::Div {
         .gap_2()
         .p_3()
         .rounded_md()
-        .bg(rgb(COLOR_PANEL_HIGHLIGHT))
+        .bg(theme.panel_highlight)
         .border_1()
-        .border_color(rgb(COLOR_DANGER))
+        .border_color(theme.danger)
         .child(
             div()
                 .flex()
@@ -1789,97 +9807,727 @@ fn error_banner(message: &str) -> gpui::Div {
                 .w(px(20.))
                 .h(px(20.))
                 .rounded_full()
-                .bg(rgb(COLOR_DANGER_SURFACE))
+                .bg(theme.danger_surface)
                 .border_1()
-                .border_color(rgb(COLOR_DANGER))
+                .border_color(theme.danger)
                 .text_xs()
-                .text_color(rgb(COLOR_DANGER_SOFT))
+                .text_color(theme.danger_soft)
                 .child("!"),
         )
         .child(
             div()
                 .text_sm()
-                .text_color(rgb(COLOR_DANGER_SOFT))
+                .text_color(theme.danger_soft)
                 .child(message_text),
         )
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum ActivityLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl ActivityLevel {
+    fn label(self) -> &'static str {
+        match self {
+            ActivityLevel::Info => "INFO",
+            ActivityLevel::Warning => "WARN",
+            ActivityLevel::Error => "ERROR",
+        }
+    }
+}
+
+// Transient notice drawn over the bottom-right corner of the main window.
+struct Toast {
+    level: ActivityLevel,
+    message: String,
+    shown_at: Instant,
+}
+
+struct ActivityEntry {
+    at: DateTime<Local>,
+    level: ActivityLevel,
+    message: String,
+}
+
+#[derive(Default)]
+struct ConnectionState {
+    status: ConnectionStatus,
+    kind: ProfileKind,
+    read_only: bool,
+    confirm_destructive: bool,
+    profile_id: Option<ProfileId>,
+    last_password: Option<String>,
+    dropped: bool,
+    session: Option<DbSessionHandle>,
+    server_info: Option<ServerInfo>,
+    // The statement_timeout the server session currently enforces.
+    statement_timeout: Option<Duration>,
+    // Attempt and total of the connect retry in progress.
+    connect_retry: Option<(u32, u32)>,
+    last_error: Option<String>,
+}
+
+impl ConnectionState {
+    fn is_connected(&self) -> bool {
+        matches!(self.status, ConnectionStatus::Connected(_))
+    }
+
+    fn is_busy(&self) -> bool {
+        matches!(self.status, ConnectionStatus::Connecting(_))
+    }
+
+    fn status_text(&self, dots: usize) -> String {
+        match &self.status {
+            ConnectionStatus::Disconnected => "Disconnected".into(),
+            ConnectionStatus::Connecting(name) => {
+                const DOTS: [&str; 4] = ["", ".", "..", "..."];
+                let suffix = DOTS[dots.min(3)];
+                match self.connect_retry {
+                    Some((attempt, retries)) => {
+                        format!("Connecting to {name}{suffix} retry {attempt}/{retries}")
+                    }
+                    None => format!("Connecting to {name}{suffix}"),
+                }
+            }
+            ConnectionStatus::Connected(name) => format!("Connected to {name}"),
+        }
+    }
+}
+
+#[derive(Default, PartialEq)]
+enum ConnectionStatus {
+    #[default]
+    Disconnected,
+    Connecting(String),
+    Connected(String),
+}
+
+#[derive(Default)]
+struct QueryState {
+    status: QueryStatus,
+    cancel_requested: bool,
+    started_at: Option<Instant>,
+    elapsed: Duration,
+    timer_active: bool,
+    row_limit: usize,
+    paged_sql: Option<String>,
+    page: usize,
+    plan_view: bool,
+    confirm_explain_analyze: bool,
+    // Editor text and range of a run held back until destructive statements are confirmed.
+    confirm_destructive: Option<(String, Range<usize>)>,
+    selected_row: Option<usize>,
+    pretty_json: HashSet<usize>,
+    last_error: Option<QueryError>,
+    notices: Vec<String>,
+    last_completion: Option<String>,
+    last_result: Option<QueryResultView>,
+    timings: Vec<StatementTiming>,
+    history_sql: Option<String>,
+    // Editor text at the time of the run and the character offset the run started at.
+    error_origin: Option<(String, usize)>,
+    in_transaction: bool,
+    result_search: String,
+    search_mode: SearchMode,
+    column_filters_open: bool,
+    column_resize: Option<ColumnResize>,
+}
+
+#[derive(Clone, Copy)]
+struct ColumnResize {
+    column: usize,
+    start_x: Pixels,
+    start_width: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Splitter {
+    Sidebar,
+    SchemaList,
+    TableList,
+    // Between the SQL editor and the results; drags vertically.
+    Editor,
+}
+
+impl Splitter {
+    fn size_range(self) -> (f32, f32) {
+        match self {
+            Splitter::Sidebar => (SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH),
+            Splitter::SchemaList | Splitter::TableList => {
+                (BROWSER_LIST_MIN_WIDTH, BROWSER_LIST_MAX_WIDTH)
+            }
+            Splitter::Editor => (EDITOR_MIN_HEIGHT, EDITOR_MAX_HEIGHT),
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Splitter::Editor)
+    }
+
+    fn axis_position(self, position: gpui::Point<Pixels>) -> Pixels {
+        if self.is_vertical() {
+            position.y
+        } else {
+            position.x
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct PanelResize {
+    splitter: Splitter,
+    start_position: Pixels,
+    start_size: f32,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum SearchMode {
+    #[default]
+    Filter,
+    Highlight,
+}
+
+#[derive(Default, PartialEq)]
+enum QueryStatus {
+    #[default]
+    Idle,
+    Running,
+}
+
+struct QueryResultView {
+    columns: Vec<String>,
+    column_metadata: Vec<ColumnMetadata>,
+    rows: Vec<Vec<Cell>>,
+    row_count: usize,
+    duration: Duration,
+    truncated: bool,
+    sort: Option<ResultSort>,
+    sorted_rows: Vec<usize>,
+    column_widths: Vec<f32>,
+    // Filter text per column from the grid's filter row; rows must match all.
+    column_filters: Vec<String>,
+}
+
+impl QueryResultView {
+    fn column_width(&self, column: usize) -> f32 {
+        self.column_widths
+            .get(column)
+            .copied()
+            .unwrap_or(RESULT_COL_MIN_WIDTH)
+    }
+
+    fn total_width(&self, density: RowDensity) -> f32 {
+        row_number_width(density)
+            + (0..self.columns.len())
+                .map(|column| self.column_width(column))
+                .sum::<f32>()
+    }
+
+    fn is_numeric_column(&self, column: usize) -> bool {
+        self.column_metadata
+            .get(column)
+            .is_some_and(|metadata| is_numeric_type(&base_type(&metadata.data_type)))
+    }
+
+    fn is_boolean_column(&self, column: usize) -> bool {
+        self.column_metadata.get(column).is_some_and(|metadata| {
+            matches!(base_type(&metadata.data_type).as_str(), "boolean" | "bool")
+        })
+    }
+
+    fn set_column_width(&mut self, column: usize, width: f32) {
+        if let Some(current) = self.column_widths.get_mut(column) {
+            *current = width.clamp(RESULT_COL_RESIZE_MIN_WIDTH, RESULT_COL_MAX_WIDTH);
+        }
+    }
+
+    fn fit_column(&mut self, column: usize) {
+        let width = self.content_width(column, self.rows.len());
+        self.set_column_width(column, width);
+    }
+
+    fn content_width(&self, column: usize, sample_rows: usize) -> f32 {
+        let header = self
+            .columns
+            .get(column)
+            .map_or(0, |name| name.chars().count());
+        let type_label = self
+            .column_metadata
+            .get(column)
+            .map(|metadata| column_type_label(metadata).chars().count())
+            .unwrap_or(0);
+        let widest = self
+            .rows
+            .iter()
+            .take(sample_rows)
+            .filter_map(|row| row.get(column))
+            .map(|cell| display_width(cell.as_str()))
+            .chain([header + 2, type_label])
+            .max()
+            .unwrap_or(0);
+        widest as f32 * RESULT_CHAR_WIDTH + RESULT_CELL_PADDING
+    }
+
+    fn has_column_filters(&self) -> bool {
+        self.column_filters
+            .iter()
+            .any(|filter| !filter.trim().is_empty())
+    }
+
+    fn matches_column_filters(&self, row: usize) -> bool {
+        self.column_filters
+            .iter()
+            .enumerate()
+            .all(|(column, filter)| {
+                self.rows[row].get(column).is_none_or(|cell| {
+                    column_filter_matches(cell, filter, self.is_numeric_column(column))
+                })
+            })
+    }
+
+    fn display_order(&self) -> Vec<usize> {
+        if self.sort.is_some() {
+            self.sorted_rows.clone()
+        } else {
+            (0..self.rows.len()).collect()
+        }
+    }
+
+    fn toggle_sort(&mut self, column: usize) {
+        self.sort = match self.sort {
+            Some(sort) if sort.column == column => match sort.direction {
+                SortDirection::Ascending => Some(ResultSort {
+                    column,
+                    direction: SortDirection::Descending,
+                }),
+                SortDirection::Descending => None,
+            },
+            _ => Some(ResultSort {
+                column,
+                direction: SortDirection::Ascending,
+            }),
+        };
+
+        let Some(sort) = self.sort else {
+            self.sorted_rows.clear();
+            return;
+        };
+        let cell = |row: usize| self.rows[row].get(sort.column).and_then(Cell::as_text);
+        let order_kind = match self.column_metadata.get(sort.column) {
+            Some(metadata) => CellOrder::for_type(&metadata.data_type),
+            None if (0..self.rows.len())
+                .filter_map(cell)
+                .all(|value| value.parse::<f64>().is_ok()) =>
+            {
+                CellOrder::Numeric
+            }
+            None => CellOrder::Text,
+        };
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|a, b| match (cell(*a), cell(*b)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => {
+                let ordering = order_kind.compare(a, b);
+                match sort.direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            }
+        });
+        self.sorted_rows = order;
+    }
+}
+
+impl From<QueryResult> for QueryResultView {
+    fn from(value: QueryResult) -> Self {
+        let mut view = Self {
+            columns: value.columns,
+            column_metadata: value.column_metadata,
+            rows: value.rows,
+            row_count: value.row_count,
+            duration: value.duration,
+            truncated: value.truncated,
+            sort: None,
+            sorted_rows: Vec::new(),
+            column_widths: Vec::new(),
+            column_filters: Vec::new(),
+        };
+        view.column_widths = (0..view.columns.len())
+            .map(|column| {
+                view.content_width(column, RESULT_AUTOSIZE_SAMPLE_ROWS)
+                    .clamp(RESULT_COL_AUTO_MIN_WIDTH, RESULT_COL_AUTO_MAX_WIDTH)
+            })
+            .collect();
+        view
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ResultSort {
+    column: usize,
+    direction: SortDirection,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ResultGrid {
+    Query,
+    Preview,
+}
+
+#[derive(Clone, Copy)]
+enum CellOrder {
+    Numeric,
+    Temporal,
+    Text,
+}
+
+impl CellOrder {
+    fn for_type(data_type: &str) -> Self {
+        let base = base_type(data_type);
+        if is_numeric_type(&base) {
+            CellOrder::Numeric
+        } else if is_temporal_type(&base) {
+            CellOrder::Temporal
+        } else {
+            CellOrder::Text
+        }
+    }
+
+    // Values that do not parse as the column type fall back to text order.
+    fn compare(self, a: &str, b: &str) -> Ordering {
+        let typed = match self {
+            CellOrder::Numeric => a
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .zip(b.trim().parse::<f64>().ok())
+                .map(|(a, b)| a.total_cmp(&b)),
+            CellOrder::Temporal => parse_temporal(a)
+                .zip(parse_temporal(b))
+                .map(|(a, b)| a.cmp(&b)),
+            CellOrder::Text => None,
+        };
+        typed.unwrap_or_else(|| a.cmp(b))
+    }
+}
+
+#[derive(Default)]
+struct CompareRunState {
+    picker_open: bool,
+    // Set while waiting for "Run anyway" on destructive SQL.
+    confirm_destructive: Option<String>,
+    selected_profiles: Vec<ProfileId>,
+    sql: String,
+    row_limit: usize,
+    params: Vec<ParamValue>,
+    targets: Vec<CompareTarget>,
+    session: Option<DbSessionHandle>,
+    events: Option<Receiver<DbEvent>>,
+    ticking: bool,
+}
+
+impl CompareRunState {
+    fn is_running(&self) -> bool {
+        self.events.is_some()
+    }
+
+    fn next_pending(&self) -> Option<usize> {
+        self.targets
+            .iter()
+            .position(|target| matches!(target.status, CompareTargetStatus::Pending))
+    }
+
+    fn active_target(&self) -> Option<usize> {
+        self.targets.iter().position(|target| {
+            matches!(
+                target.status,
+                CompareTargetStatus::Connecting | CompareTargetStatus::Running
+            )
+        })
+    }
+}
+
+struct CompareTarget {
+    profile_id: ProfileId,
+    profile_name: String,
+    status: CompareTargetStatus,
+}
+
+enum CompareTargetStatus {
+    Pending,
+    Connecting,
+    Running,
+    Finished(QueryResultView),
+    Completed(String),
+    Failed(String),
+}
+
+// Two schemas loaded over sessions of their own; the left side is the base, so
+// "added" means only on the right.
+#[derive(Default)]
+struct SchemaDiffState {
+    sides: [SchemaDiffSide; 2],
+    result: Option<Vec<TableDiff>>,
+    show_unchanged: bool,
+    ticking: bool,
+}
+
+impl SchemaDiffState {
+    fn is_running(&self) -> bool {
+        self.sides.iter().any(|side| side.events.is_some())
+    }
+}
+
 #[derive(Default)]
-struct ConnectionState {
-    status: ConnectionStatus,
+struct SchemaDiffSide {
+    profile_id: Option<ProfileId>,
+    profile_name: String,
+    schema: String,
+    status: SchemaDiffStatus,
+    tables: SchemaSnapshot,
+    // Tables whose `ColumnsLoaded` has not arrived yet.
+    pending_tables: usize,
     session: Option<DbSessionHandle>,
-    last_error: Option<String>,
+    events: Option<Receiver<DbEvent>>,
 }
 
-impl ConnectionState {
-    fn is_connected(&self) -> bool {
-        matches!(self.status, ConnectionStatus::Connected(_))
-    }
+#[derive(Default)]
+enum SchemaDiffStatus {
+    #[default]
+    Idle,
+    Connecting,
+    Loading,
+    Loaded,
+    Failed(String),
+}
 
-    fn is_busy(&self) -> bool {
-        matches!(self.status, ConnectionStatus::Connecting(_))
-    }
+struct BindParam {
+    input: gpui::Entity<TextInput>,
+    kind: ParamKind,
+}
 
-    fn status_text(&self, dots: usize) -> String {
-        match &self.status {
-            ConnectionStatus::Disconnected => "Disconnected".into(),
-            ConnectionStatus::Connecting(name) => {
-                const DOTS: [&str; 4] = ["", ".", "..", "..."];
-                let suffix = DOTS[dots.min(3)];
-                format!("Connecting to {name}{suffix}")
-            }
-            ConnectionStatus::Connected(name) => format!("Connected to {name}"),
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum ParamKind {
+    #[default]
+    Text,
+    Int,
+    Bool,
+    Null,
+}
+
+impl ParamKind {
+    const ALL: [ParamKind; 4] = [
+        ParamKind::Text,
+        ParamKind::Int,
+        ParamKind::Bool,
+        ParamKind::Null,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ParamKind::Text => "text",
+            ParamKind::Int => "int",
+            ParamKind::Bool => "bool",
+            ParamKind::Null => "null",
         }
     }
 }
 
-#[derive(Default, PartialEq)]
-enum ConnectionStatus {
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum MainTab {
     #[default]
-    Disconnected,
-    Connecting(String),
-    Connected(String),
+    SchemaBrowser,
+    SqlEditor,
+    Sessions,
+    SchemaDiff,
 }
 
+// Pasted CSV waiting to be inserted into a schema-browser table.
 #[derive(Default)]
-struct QueryState {
-    status: QueryStatus,
-    last_error: Option<String>,
-    last_result: Option<QueryResultView>,
+struct CsvImportState {
+    // The panel only shows while this table is selected.
+    target: Option<(String, String)>,
+    has_header: bool,
+    // Text the records were parsed from, to skip reparsing unchanged input.
+    source: String,
+    records: Vec<Vec<Option<String>>>,
+    parse_error: Option<String>,
+    // Table column index (into the browser's columns) for each CSV column.
+    mapping: Vec<Option<usize>>,
+    running: bool,
+    notice: Option<String>,
+    error: Option<String>,
 }
 
-#[derive(Default, PartialEq)]
-enum QueryStatus {
-    #[default]
-    Idle,
-    Running,
-}
+impl CsvImportState {
+    fn is_for(&self, schema: &str, table: &str) -> bool {
+        self.target
+            .as_ref()
+            .is_some_and(|(target_schema, target_table)| {
+                target_schema == schema && target_table == table
+            })
+    }
 
-struct QueryResultView {
-    columns: Vec<String>,
-    rows: Vec<Vec<String>>,
-    row_count: usize,
-    duration: Duration,
-    truncated: bool,
-}
+    fn headers(&self) -> Vec<String> {
+        let width = self.records.iter().map(Vec::len).max().unwrap_or(0);
+        (0..width)
+            .map(|index| {
+                self.has_header
+                    .then(|| self.records.first())
+                    .flatten()
+                    .and_then(|header| header.get(index).cloned().flatten())
+                    .filter(|name| !name.trim().is_empty())
+                    .unwrap_or_else(|| format!("column {}", index + 1))
+            })
+            .collect()
+    }
 
-impl From<QueryResult> for QueryResultView {
-    fn from(value: QueryResult) -> Self {
-        Self {
-            columns: value.columns,
-            rows: value.rows,
-            row_count: value.row_count,
-            duration: value.duration,
-            truncated: value.truncated,
+    fn data_rows(&self) -> &[Vec<Option<String>>] {
+        let skip = usize::from(self.has_header).min(self.records.len());
+        &self.records[skip..]
+    }
+
+    // Headers match table columns by name (case-insensitively); without a
+    // header row the columns are filled in table order.
+    fn remap(&mut self, columns: &[ColumnMetadata]) {
+        self.mapping = self
+            .headers()
+            .iter()
+            .enumerate()
+            .map(|(index, header)| {
+                if self.has_header {
+                    columns
+                        .iter()
+                        .position(|column| column.name.eq_ignore_ascii_case(header.trim()))
+                } else {
+                    (index < columns.len()).then_some(index)
+                }
+            })
+            .collect();
+    }
+
+    fn cycle_mapping(&mut self, index: usize, column_count: usize) {
+        if let Some(target) = self.mapping.get_mut(index) {
+            *target = match *target {
+                None if column_count > 0 => Some(0),
+                Some(column) if column + 1 < column_count => Some(column + 1),
+                _ => None,
+            };
+        }
+    }
+
+    fn mapped_columns(&self) -> Vec<(usize, usize)> {
+        self.mapping
+            .iter()
+            .enumerate()
+            .filter_map(|(field, column)| column.map(|column| (field, column)))
+            .collect()
+    }
+
+    // Reasons the insert cannot run yet, capped at CSV_MAX_PROBLEMS.
+    fn problems(&self, columns: &[ColumnMetadata]) -> Vec<String> {
+        let mapped = self.mapped_columns();
+        if mapped.is_empty() {
+            return vec!["Map at least one CSV column to a table column.".into()];
+        }
+        let mut problems = Vec::new();
+        for (position, (_, column)) in mapped.iter().enumerate() {
+            if mapped[..position].iter().any(|(_, other)| other == column) {
+                problems.push(format!(
+                    "Column {} is mapped more than once.",
+                    columns[*column].name
+                ));
+            }
+        }
+        'rows: for (row, record) in self.data_rows().iter().enumerate() {
+            for (field, column) in &mapped {
+                let column = &columns[*column];
+                let problem = match record.get(*field).and_then(Option::as_deref) {
+                    None if column.nullable == Some(false) => {
+                        Some("empty value for a NOT NULL column".to_string())
+                    }
+                    None => None,
+                    Some(value) => csv_value_problem(&column.data_type, value),
+                };
+                if let Some(problem) = problem {
+                    problems.push(format!(
+                        "Row {}, column {}: {problem}",
+                        row + 1,
+                        column.name
+                    ));
+                    if problems.len() >= CSV_MAX_PROBLEMS {
+                        break 'rows;
+                    }
+                }
+            }
         }
+        problems
     }
 }
 
+#[derive(Default)]
+struct CommandPaletteState {
+    open: bool,
+    query: String,
+    matches: Vec<PaletteCommand>,
+    selected: usize,
+    scroll: ScrollHandle,
+    // Refocused when the palette is dismissed without running a command.
+    return_focus: Option<FocusHandle>,
+}
+
+#[derive(Default)]
+struct NotificationsState {
+    // Channels the server confirmed a LISTEN for, in the order they were added.
+    channels: Vec<String>,
+    entries: VecDeque<NotificationEntry>,
+    error: Option<String>,
+}
+
+struct NotificationEntry {
+    at: DateTime<Local>,
+    channel: String,
+    payload: String,
+}
+
+#[derive(Default)]
+struct ServerSessionsState {
+    sessions: Vec<SessionActivity>,
+    loading: bool,
+    loaded_at: Option<Instant>,
+    // Auto-refresh is on unless paused from the Sessions tab.
+    paused: bool,
+    // Backend PID awaiting confirmation before it is terminated.
+    confirm_terminate: Option<i64>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
-enum MainTab {
+enum BrowserList {
     #[default]
-    SchemaBrowser,
-    SqlEditor,
+    Schemas,
+    Tables,
+    Columns,
+}
+
+impl BrowserList {
+    fn next(self) -> Self {
+        match self {
+            BrowserList::Schemas => BrowserList::Tables,
+            BrowserList::Tables => BrowserList::Columns,
+            BrowserList::Columns => BrowserList::Schemas,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -1889,12 +10537,26 @@ struct SchemaBrowserState {
     selected_schema: Option<String>,
     tables: Vec<String>,
     tables_loading: bool,
+    table_sizes: HashMap<String, u64>,
+    views: Vec<ViewMetadata>,
+    views_loading: bool,
     selected_table: Option<String>,
     columns: Vec<ColumnMetadata>,
     columns_loading: bool,
+    indexes: Vec<IndexMetadata>,
+    foreign_keys: Vec<ForeignKeyMetadata>,
+    table_menu: Option<String>,
+    focused_list: BrowserList,
+    column_cursor: Option<usize>,
     preview: Option<QueryResultView>,
     preview_loading: bool,
+    ddl: Option<String>,
+    ddl_loading: bool,
+    column_stats: Option<(String, ColumnStats)>,
+    column_stats_loading: bool,
     last_error: Option<String>,
+    schema_filter: String,
+    table_filter: String,
 }
 
 impl SchemaBrowserState {
@@ -1902,6 +10564,76 @@ impl SchemaBrowserState {
         *self = Self::default();
     }
 
+    fn visible_schemas(&self) -> Vec<&String> {
+        self.schemas
+            .iter()
+            .filter(|schema| matches_filter(schema, &self.schema_filter))
+            .collect()
+    }
+
+    fn visible_tables(&self) -> Vec<&String> {
+        self.tables
+            .iter()
+            .filter(|table| matches_filter(table, &self.table_filter))
+            .collect()
+    }
+
+    fn visible_views(&self) -> Vec<&ViewMetadata> {
+        self.views
+            .iter()
+            .filter(|view| matches_filter(&view.name, &self.table_filter))
+            .collect()
+    }
+
+    fn set_schema_filter(&mut self, filter: String) {
+        self.schema_filter = filter;
+        if self
+            .selected_schema
+            .as_ref()
+            .is_some_and(|schema| !matches_filter(schema, &self.schema_filter))
+        {
+            self.selected_schema = None;
+            self.tables.clear();
+            self.tables_loading = false;
+            self.views.clear();
+            self.views_loading = false;
+            self.clear_table_selection();
+        }
+    }
+
+    fn set_table_filter(&mut self, filter: String) {
+        self.table_filter = filter;
+        if self
+            .selected_table
+            .as_ref()
+            .is_some_and(|table| !matches_filter(table, &self.table_filter))
+        {
+            self.clear_table_selection();
+        }
+    }
+
+    fn is_primary_key(&self, column: &str) -> bool {
+        self.indexes
+            .iter()
+            .any(|index| index.primary && index.columns.iter().any(|name| name == column))
+    }
+
+    fn clear_table_selection(&mut self) {
+        self.selected_table = None;
+        self.table_menu = None;
+        self.column_cursor = None;
+        self.columns.clear();
+        self.indexes.clear();
+        self.foreign_keys.clear();
+        self.columns_loading = false;
+        self.preview = None;
+        self.preview_loading = false;
+        self.ddl = None;
+        self.ddl_loading = false;
+        self.column_stats = None;
+        self.column_stats_loading = false;
+    }
+
     fn start_schema_load(&mut self) {
         self.schemas_loading = true;
         self.tables_loading = false;
@@ -1909,78 +10641,367 @@ impl SchemaBrowserState {
         self.preview_loading = false;
         self.schemas.clear();
         self.tables.clear();
+        self.table_sizes.clear();
+        self.views.clear();
+        self.views_loading = false;
         self.columns.clear();
+        self.indexes.clear();
+        self.foreign_keys.clear();
         self.preview = None;
         self.selected_schema = None;
         self.selected_table = None;
         self.last_error = None;
+        self.schema_filter.clear();
+        self.table_filter.clear();
+    }
+
+    fn has_table_or_view(&self, name: &str) -> bool {
+        self.tables.iter().any(|table| table == name)
+            || self.views.iter().any(|view| view.name == name)
+    }
+
+    fn clear_schema_selection(&mut self) {
+        self.selected_schema = None;
+        self.tables.clear();
+        self.table_sizes.clear();
+        self.views.clear();
+        self.tables_loading = false;
+        self.views_loading = false;
+        self.clear_table_selection();
     }
 
     fn stop_loading(&mut self) {
         self.schemas_loading = false;
         self.tables_loading = false;
+        self.views_loading = false;
         self.columns_loading = false;
         self.preview_loading = false;
+        self.ddl_loading = false;
+        self.column_stats_loading = false;
     }
 }
 
 struct ProfileForm {
     name: gpui::Entity<TextInput>,
+    group: gpui::Entity<TextInput>,
     host: gpui::Entity<TextInput>,
     port: gpui::Entity<TextInput>,
+    connect_timeout: gpui::Entity<TextInput>,
+    row_limit: gpui::Entity<TextInput>,
+    keepalive: gpui::Entity<TextInput>,
+    statement_timeout: gpui::Entity<TextInput>,
+    connect_retries: gpui::Entity<TextInput>,
+    retry_backoff: gpui::Entity<TextInput>,
     database: gpui::Entity<TextInput>,
     username: gpui::Entity<TextInput>,
+    password: gpui::Entity<TextInput>,
+    ssh_host: gpui::Entity<TextInput>,
+    ssh_port: gpui::Entity<TextInput>,
+    ssh_user: gpui::Entity<TextInput>,
+    ssh_key: gpui::Entity<TextInput>,
+    connection_string: gpui::Entity<TextInput>,
+    remember_password: bool,
+    auto_reconnect: bool,
+    read_only: bool,
+    confirm_destructive: bool,
+    kind: ProfileKind,
+    ssl_mode: SslMode,
+    color: Option<ProfileColor>,
 }
 
 impl ProfileForm {
     fn new(cx: &mut Context<DbMiruApp>) -> Self {
         Self {
             name: cx.new(|cx| TextInput::new(cx, "", "Name")),
+            group: cx.new(|cx| TextInput::new(cx, "", "Group (optional)")),
             host: cx.new(|cx| TextInput::new(cx, "", "Host")),
             port: cx.new(|cx| TextInput::new(cx, "5432", "Port")),
+            connect_timeout: cx.new(|cx| {
+                TextInput::new(
+                    cx,
+                    &DEFAULT_CONNECT_TIMEOUT_SECS.to_string(),
+                    "Connect timeout (seconds)",
+                )
+            }),
+            row_limit: cx
+                .new(|cx| TextInput::new(cx, &DEFAULT_ROW_LIMIT.to_string(), "Default row limit")),
+            keepalive: cx
+                .new(|cx| TextInput::new(cx, "0", "Keep-alive interval (seconds, 0 = off)")),
+            statement_timeout: cx
+                .new(|cx| TextInput::new(cx, "0", "Statement timeout (seconds, 0 = off)")),
+            connect_retries: cx.new(|cx| TextInput::new(cx, "0", "Connect retries (0 = off)")),
+            retry_backoff: cx.new(|cx| {
+                TextInput::new(
+                    cx,
+                    &DEFAULT_RETRY_BACKOFF_SECS.to_string(),
+                    "Retry backoff (seconds, doubles per retry)",
+                )
+            }),
             database: cx.new(|cx| TextInput::new(cx, "", "Database")),
             username: cx.new(|cx| TextInput::new(cx, "", "Username")),
+            password: cx.new(|cx| {
+                TextInput::new(cx, "", "Password (saved to keychain)").with_obscured(true)
+            }),
+            ssh_host: cx.new(|cx| TextInput::new(cx, "", "SSH tunnel host (optional)")),
+            ssh_port: cx.new(|cx| TextInput::new(cx, &DEFAULT_SSH_PORT.to_string(), "SSH port")),
+            ssh_user: cx.new(|cx| TextInput::new(cx, "", "SSH user")),
+            ssh_key: cx.new(|cx| TextInput::new(cx, "", "SSH key path (optional)")),
+            connection_string: cx.new(|cx| {
+                TextInput::new(
+                    cx,
+                    "",
+                    "Paste connection string (postgres:// or mysql://...)",
+                )
+            }),
+            remember_password: false,
+            auto_reconnect: false,
+            read_only: false,
+            confirm_destructive: false,
+            kind: ProfileKind::default(),
+            ssl_mode: SslMode::default(),
+            color: None,
         }
     }
 
     fn values(&self, cx: &mut Context<DbMiruApp>) -> ProfileFormValues {
         ProfileFormValues {
             name: self.name.read(cx).text(),
+            group: self.group.read(cx).text(),
             host: self.host.read(cx).text(),
             port: self.port.read(cx).text(),
+            connect_timeout: self.connect_timeout.read(cx).text(),
+            row_limit: self.row_limit.read(cx).text(),
+            keepalive: self.keepalive.read(cx).text(),
+            statement_timeout: self.statement_timeout.read(cx).text(),
+            connect_retries: self.connect_retries.read(cx).text(),
+            retry_backoff: self.retry_backoff.read(cx).text(),
             database: self.database.read(cx).text(),
             username: self.username.read(cx).text(),
+            password: self.password.read(cx).text(),
+            ssh_host: self.ssh_host.read(cx).text(),
+            ssh_port: self.ssh_port.read(cx).text(),
+            ssh_user: self.ssh_user.read(cx).text(),
+            ssh_key: self.ssh_key.read(cx).text(),
+            remember_password: self.remember_password,
+            auto_reconnect: self.auto_reconnect,
+            read_only: self.read_only,
+            confirm_destructive: self.confirm_destructive,
+            kind: self.kind,
+            ssl_mode: self.ssl_mode,
+            color: self.color,
         }
     }
 
-    fn set_values(&self, values: &ProfileFormValues, cx: &mut Context<DbMiruApp>) {
+    fn set_values(&mut self, values: &ProfileFormValues, cx: &mut Context<DbMiruApp>) {
         self.name
             .update(cx, |input, _| input.set_text(&values.name));
+        self.group
+            .update(cx, |input, _| input.set_text(&values.group));
         self.host
             .update(cx, |input, _| input.set_text(&values.host));
         self.port
             .update(cx, |input, _| input.set_text(&values.port));
+        self.connect_timeout
+            .update(cx, |input, _| input.set_text(&values.connect_timeout));
+        self.row_limit
+            .update(cx, |input, _| input.set_text(&values.row_limit));
+        self.keepalive
+            .update(cx, |input, _| input.set_text(&values.keepalive));
+        self.statement_timeout
+            .update(cx, |input, _| input.set_text(&values.statement_timeout));
+        self.connect_retries
+            .update(cx, |input, _| input.set_text(&values.connect_retries));
+        self.retry_backoff
+            .update(cx, |input, _| input.set_text(&values.retry_backoff));
         self.database
             .update(cx, |input, _| input.set_text(&values.database));
         self.username
             .update(cx, |input, _| input.set_text(&values.username));
+        self.password
+            .update(cx, |input, _| input.set_text(&values.password));
+        self.ssh_host
+            .update(cx, |input, _| input.set_text(&values.ssh_host));
+        self.ssh_port
+            .update(cx, |input, _| input.set_text(&values.ssh_port));
+        self.ssh_user
+            .update(cx, |input, _| input.set_text(&values.ssh_user));
+        self.ssh_key
+            .update(cx, |input, _| input.set_text(&values.ssh_key));
+        self.remember_password = values.remember_password;
+        self.auto_reconnect = values.auto_reconnect;
+        self.read_only = values.read_only;
+        self.confirm_destructive = values.confirm_destructive;
+        self.kind = values.kind;
+        self.ssl_mode = values.ssl_mode;
+        self.color = values.color;
     }
 
-    fn clear(&self, cx: &mut Context<DbMiruApp>) {
+    fn clear(&mut self, cx: &mut Context<DbMiruApp>) {
         self.name.update(cx, |input, _| input.clear());
+        self.group.update(cx, |input, _| input.clear());
         self.host.update(cx, |input, _| input.clear());
         self.port.update(cx, |input, _| input.set_text("5432"));
+        self.connect_timeout.update(cx, |input, _| {
+            input.set_text(&DEFAULT_CONNECT_TIMEOUT_SECS.to_string())
+        });
+        self.row_limit.update(cx, |input, _| {
+            input.set_text(&DEFAULT_ROW_LIMIT.to_string())
+        });
+        self.keepalive.update(cx, |input, _| input.set_text("0"));
+        self.statement_timeout
+            .update(cx, |input, _| input.set_text("0"));
+        self.connect_retries
+            .update(cx, |input, _| input.set_text("0"));
+        self.retry_backoff.update(cx, |input, _| {
+            input.set_text(&DEFAULT_RETRY_BACKOFF_SECS.to_string())
+        });
         self.database.update(cx, |input, _| input.clear());
         self.username.update(cx, |input, _| input.clear());
+        self.password.update(cx, |input, _| input.clear());
+        self.ssh_host.update(cx, |input, _| input.clear());
+        self.ssh_port
+            .update(cx, |input, _| input.set_text(&DEFAULT_SSH_PORT.to_string()));
+        self.ssh_user.update(cx, |input, _| input.clear());
+        self.ssh_key.update(cx, |input, _| input.clear());
+        self.connection_string.update(cx, |input, _| input.clear());
+        self.remember_password = false;
+        self.auto_reconnect = false;
+        self.read_only = false;
+        self.confirm_destructive = false;
+        self.kind = ProfileKind::default();
+        self.ssl_mode = SslMode::default();
+        self.color = None;
     }
 }
 
 struct ProfileFormValues {
     name: String,
+    group: String,
     host: String,
     port: String,
     database: String,
     username: String,
+    password: String,
+    remember_password: bool,
+    auto_reconnect: bool,
+    read_only: bool,
+    confirm_destructive: bool,
+    kind: ProfileKind,
+    ssl_mode: SslMode,
+    color: Option<ProfileColor>,
+    connect_timeout: String,
+    row_limit: String,
+    keepalive: String,
+    statement_timeout: String,
+    connect_retries: String,
+    retry_backoff: String,
+    ssh_host: String,
+    ssh_port: String,
+    ssh_user: String,
+    ssh_key: String,
+}
+
+// Mirrors the main window's latest result; it re-renders whenever the app
+// entity notifies, so a finished query shows up here too.
+struct ResultsWindow {
+    app: WeakEntity<DbMiruApp>,
+    _app_observer: Subscription,
+}
+
+impl ResultsWindow {
+    fn new(app: gpui::Entity<DbMiruApp>, cx: &mut Context<Self>) -> Self {
+        Self {
+            _app_observer: cx.observe(&app, |_, _, cx| cx.notify()),
+            app: app.downgrade(),
+        }
+    }
+}
+
+impl Render for ResultsWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = *cx.theme();
+        let body_height = window.viewport_size().height - px(RESULTS_WINDOW_CHROME_HEIGHT);
+        let content = match self.app.upgrade() {
+            None => div()
+                .text_sm()
+                .text_color(theme.text_muted)
+                .child("The main window was closed.")
+                .into_any(),
+            Some(app) => {
+                let app = app.read(cx);
+                match (&app.query_state.last_result, &app.query_state.status) {
+                    (Some(result), _) if !app.query_state.plan_view => div()
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(theme.text_muted)
+                                .child(app.result_meta(result)),
+                        )
+                        .child(
+                            div()
+                                .w_full()
+                                .min_w(px(0.))
+                                .overflow_x_scroll()
+                                .restrict_scroll_to_axis()
+                                .id("results_window_scroll")
+                                .child(app.render_result_table(
+                                    &theme,
+                                    result,
+                                    Some(body_height),
+                                    Some("results_window_body_scroll"),
+                                    None,
+                                )),
+                        )
+                        .into_any(),
+                    (_, QueryStatus::Running) => div()
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child("Query is running...")
+                        .into_any(),
+                    _ => div()
+                        .text_sm()
+                        .text_color(theme.text_muted)
+                        .child("Run a query to see its rows here.")
+                        .into_any(),
+                }
+            }
+        };
+        window.set_window_title("DbMiru — Results");
+        div()
+            .flex()
+            .flex_col()
+            .font_family(APP_FONT_FAMILY)
+            .size_full()
+            .bg(theme.canvas)
+            .text_color(theme.text)
+            .p_4()
+            .child(content)
+    }
+}
+
+#[derive(Clone)]
+struct DraggedProfile {
+    id: ProfileId,
+    name: String,
+}
+
+impl Render for DraggedProfile {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = *cx.theme();
+        div()
+            .px_3()
+            .py_2()
+            .rounded_lg()
+            .bg(theme.panel_highlight)
+            .border_1()
+            .border_color(theme.accent)
+            .text_sm()
+            .text_color(theme.text_bright)
+            .child(self.name.clone())
+    }
 }
 
 #[derive(Clone, Copy, Default)]
@@ -1990,3 +11011,277 @@ enum ProfileFormMode {
     Creating,
     Editing(ProfileId),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form_values() -> ProfileFormValues {
+        ProfileFormValues {
+            name: "local".into(),
+            group: String::new(),
+            host: "localhost".into(),
+            port: "5432".into(),
+            database: "app".into(),
+            username: "app".into(),
+            password: String::new(),
+            remember_password: false,
+            auto_reconnect: false,
+            read_only: false,
+            confirm_destructive: false,
+            kind: ProfileKind::Postgres,
+            ssl_mode: SslMode::default(),
+            color: None,
+            connect_timeout: "10".into(),
+            row_limit: "500".into(),
+            keepalive: "0".into(),
+            statement_timeout: "0".into(),
+            connect_retries: "0".into(),
+            retry_backoff: "2".into(),
+            ssh_host: String::new(),
+            ssh_port: "22".into(),
+            ssh_user: String::new(),
+            ssh_key: String::new(),
+        }
+    }
+
+    fn form_error(edit: impl FnOnce(&mut ProfileFormValues)) -> String {
+        let mut values = form_values();
+        edit(&mut values);
+        match profile_from_form_values(&values) {
+            Ok(_) => panic!("form values were accepted"),
+            Err(message) => message,
+        }
+    }
+
+    fn result_view(columns: &[&str], rows: Vec<Vec<Cell>>) -> QueryResultView {
+        QueryResultView::from(QueryResult {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            column_metadata: Vec::new(),
+            row_count: rows.len(),
+            rows,
+            duration: Duration::ZERO,
+            truncated: false,
+            timings: Vec::new(),
+        })
+    }
+
+    fn text(value: &str) -> Cell {
+        Cell::Text(value.into())
+    }
+
+    #[test]
+    fn json_escapes_strings_and_keeps_null() {
+        let view = result_view(
+            &["name", "note"],
+            vec![vec![text("say \"hi\"\n\\ \u{1}"), Cell::Null]],
+        );
+        let json = result_to_json(&view, true);
+        assert_eq!(
+            json,
+            "[\n  {\"name\":\"say \\\"hi\\\"\\n\\\\ \\u0001\",\"note\":null}\n]"
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["name"], "say \"hi\"\n\\ \u{1}");
+        assert!(parsed[0]["note"].is_null());
+        assert_eq!(result_to_json(&result_view(&["a"], Vec::new()), true), "[]");
+    }
+
+    #[test]
+    fn json_native_and_string_modes() {
+        let view = result_view(
+            &["int", "float", "flag", "padded", "big", "text"],
+            vec![vec![
+                text("42"),
+                text("1.5"),
+                text("true"),
+                text("007"),
+                text("12345678901234567890"),
+                text("NULL"),
+            ]],
+        );
+        let native: serde_json::Value = serde_json::from_str(&result_to_json(&view, true)).unwrap();
+        assert_eq!(
+            native[0],
+            serde_json::json!({
+                "int": 42,
+                "float": 1.5,
+                "flag": true,
+                "padded": "007",
+                "big": "12345678901234567890",
+                "text": "NULL",
+            })
+        );
+        let strings: serde_json::Value =
+            serde_json::from_str(&result_to_json(&view, false)).unwrap();
+        assert_eq!(strings[0]["int"], "42");
+        assert_eq!(strings[0]["flag"], "true");
+    }
+
+    #[test]
+    fn json_keys_are_unique() {
+        let columns = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            json_object_keys(&columns(&["id", "id", "id"])),
+            ["id", "id_2", "id_3"]
+        );
+        assert_eq!(
+            json_object_keys(&columns(&["id", "id", "id_2"])),
+            ["id", "id_3", "id_2"]
+        );
+        assert_eq!(
+            json_object_keys(&columns(&["", "", "column_1"])),
+            ["column_1_2", "column_2", "column_1"]
+        );
+        let view = result_view(&["a", "a"], vec![vec![text("1"), text("2")]]);
+        let parsed: serde_json::Value = serde_json::from_str(&result_to_json(&view, true)).unwrap();
+        assert_eq!(parsed[0], serde_json::json!({"a": 1, "a_2": 2}));
+    }
+
+    #[test]
+    fn profile_form_accepts_valid_values() {
+        let profile = profile_from_form_values(&form_values()).unwrap();
+        assert_eq!(profile.host, "localhost");
+        assert_eq!(profile.port, 5432);
+        assert!(profile.ssh_tunnel.is_none());
+    }
+
+    #[test]
+    fn profile_form_rejects_port_zero() {
+        assert_eq!(
+            form_error(|values| values.port = "0".into()),
+            "Port must be a number from 1 to 65535."
+        );
+        assert_eq!(
+            form_error(|values| values.port = "65536".into()),
+            "Port must be a number from 1 to 65535."
+        );
+        assert_eq!(
+            form_error(|values| {
+                values.ssh_host = "bastion".into();
+                values.ssh_port = "0".into();
+            }),
+            "SSH port must be a number from 1 to 65535."
+        );
+    }
+
+    #[test]
+    fn profile_form_requires_non_blank_host() {
+        assert_eq!(
+            form_error(|values| values.host = "   ".into()),
+            "Host is required."
+        );
+        assert_eq!(
+            form_error(|values| values.name = "\t".into()),
+            "Name is required."
+        );
+    }
+
+    #[test]
+    fn profile_form_rejects_host_with_spaces() {
+        assert_eq!(
+            form_error(|values| values.host = "db server".into()),
+            "Host must not contain spaces."
+        );
+        assert_eq!(
+            form_error(|values| values.ssh_host = "bastion host".into()),
+            "SSH host must not contain spaces."
+        );
+    }
+
+    #[test]
+    fn profile_form_rejects_url_and_port_in_host() {
+        assert!(
+            form_error(|values| values.host = "postgres://db/app".into())
+                .starts_with("Host must be a host name or address, not a URL")
+        );
+        assert!(
+            form_error(|values| values.ssh_host = "ssh://bastion".into())
+                .starts_with("SSH host must be a host name or address, not a URL")
+        );
+        assert_eq!(
+            form_error(|values| values.host = "db:5432".into()),
+            "Host must not include a port; put it in the port field."
+        );
+    }
+
+    #[test]
+    fn profile_form_rejects_option_like_values() {
+        assert_eq!(
+            form_error(|values| values.host = "-oProxyCommand=x".into()),
+            "Host \"-oProxyCommand=x\" is not a valid host name or address."
+        );
+        assert_eq!(
+            form_error(|values| values.ssh_host = "-oProxyCommand=x".into()),
+            "SSH host \"-oProxyCommand=x\" is not a valid host name or address."
+        );
+        assert_eq!(
+            form_error(|values| {
+                values.ssh_host = "bastion".into();
+                values.ssh_user = "-oProxyCommand=x".into();
+            }),
+            "SSH user must not start with \"-\"."
+        );
+    }
+
+    #[test]
+    fn validate_host_accepts_sockets_and_addresses() {
+        for host in [
+            "/var/run/postgresql",
+            "::1",
+            "fe80::1",
+            "db.example.com",
+            "10.0.0.5",
+        ] {
+            assert_eq!(validate_host(host, "Host"), Ok(()), "{host}");
+        }
+        for host in ["user@db", "db..example", ".db", "db-", "db?x"] {
+            assert!(validate_host(host, "Host").is_err(), "{host}");
+        }
+    }
+
+    #[test]
+    fn profile_form_reports_field_specific_messages() {
+        assert_eq!(
+            form_error(|values| values.connect_timeout = "0".into()),
+            "Connect timeout must be a whole number of seconds above 0."
+        );
+        assert_eq!(
+            form_error(|values| values.row_limit = "0".into()),
+            "Row limit must be a number above 0."
+        );
+        assert_eq!(
+            form_error(|values| values.keepalive = "soon".into()),
+            "Keep-alive interval must be a whole number of seconds."
+        );
+        assert_eq!(
+            form_error(|values| values.connect_retries = "-1".into()),
+            "Connect retries must be a whole number."
+        );
+        assert_eq!(
+            form_error(|values| values.database = String::new()),
+            "Database is required."
+        );
+    }
+
+    #[test]
+    fn restored_window_needs_its_title_bar_on_a_display() {
+        let display = Bounds::new(
+            gpui::point(px(0.), px(0.)),
+            gpui::size(px(1920.), px(1080.)),
+        );
+        let window =
+            |x: f32, y: f32| Bounds::new(gpui::point(px(x), px(y)), gpui::size(px(800.), px(600.)));
+        assert!(title_bar_visible(window(100., 100.), &[display]));
+        // Only a sliver of the window is on screen.
+        assert!(!title_bar_visible(window(1919., 100.), &[display]));
+        // The body is visible but the title bar is above the display.
+        assert!(!title_bar_visible(window(100., -20.), &[display]));
+        assert!(!title_bar_visible(window(100., 100.), &[]));
+    }
+}
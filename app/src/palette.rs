@@ -0,0 +1,164 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteCommand {
+    RunQuery,
+    RunSelection,
+    CancelQuery,
+    FormatSql,
+    Connect,
+    Reconnect,
+    Disconnect,
+    ShowSchemaBrowser,
+    ShowSqlEditor,
+    ShowSessions,
+    ShowSchemaDiff,
+    RefreshSchemas,
+    ExportCsv,
+    CopyResultsTsv,
+    CopyResultsJson,
+    OpenResultsWindow,
+    ToggleResultsMaximized,
+    ToggleHistory,
+    ToggleActivityLog,
+    NewProfile,
+    EditProfile,
+    DuplicateProfile,
+    ToggleTheme,
+    ToggleRowDensity,
+    ToggleThousandsSeparators,
+    ToggleTypeColors,
+    ToggleWrapCells,
+}
+
+impl PaletteCommand {
+    pub const ALL: [PaletteCommand; 27] = [
+        PaletteCommand::RunQuery,
+        PaletteCommand::RunSelection,
+        PaletteCommand::CancelQuery,
+        PaletteCommand::FormatSql,
+        PaletteCommand::Connect,
+        PaletteCommand::Reconnect,
+        PaletteCommand::Disconnect,
+        PaletteCommand::ShowSchemaBrowser,
+        PaletteCommand::ShowSqlEditor,
+        PaletteCommand::ShowSessions,
+        PaletteCommand::ShowSchemaDiff,
+        PaletteCommand::RefreshSchemas,
+        PaletteCommand::ExportCsv,
+        PaletteCommand::CopyResultsTsv,
+        PaletteCommand::CopyResultsJson,
+        PaletteCommand::OpenResultsWindow,
+        PaletteCommand::ToggleResultsMaximized,
+        PaletteCommand::ToggleHistory,
+        PaletteCommand::ToggleActivityLog,
+        PaletteCommand::NewProfile,
+        PaletteCommand::EditProfile,
+        PaletteCommand::DuplicateProfile,
+        PaletteCommand::ToggleTheme,
+        PaletteCommand::ToggleRowDensity,
+        PaletteCommand::ToggleThousandsSeparators,
+        PaletteCommand::ToggleTypeColors,
+        PaletteCommand::ToggleWrapCells,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteCommand::RunQuery => "Run Query",
+            PaletteCommand::RunSelection => "Run Selection",
+            PaletteCommand::CancelQuery => "Cancel Query",
+            PaletteCommand::FormatSql => "Format SQL",
+            PaletteCommand::Connect => "Connect",
+            PaletteCommand::Reconnect => "Reconnect",
+            PaletteCommand::Disconnect => "Disconnect",
+            PaletteCommand::ShowSchemaBrowser => "Switch Tab: Schema Browser",
+            PaletteCommand::ShowSqlEditor => "Switch Tab: SQL Editor",
+            PaletteCommand::ShowSessions => "Switch Tab: Sessions",
+            PaletteCommand::ShowSchemaDiff => "Switch Tab: Schema Diff",
+            PaletteCommand::RefreshSchemas => "Refresh Schemas",
+            PaletteCommand::ExportCsv => "Export Results as CSV",
+            PaletteCommand::CopyResultsTsv => "Copy Results as TSV",
+            PaletteCommand::CopyResultsJson => "Copy Results as JSON",
+            PaletteCommand::OpenResultsWindow => "Open Results in Window",
+            PaletteCommand::ToggleResultsMaximized => "Toggle Maximized Results",
+            PaletteCommand::ToggleHistory => "Toggle Query History",
+            PaletteCommand::ToggleActivityLog => "Toggle Activity Log",
+            PaletteCommand::NewProfile => "New Profile",
+            PaletteCommand::EditProfile => "Edit Profile",
+            PaletteCommand::DuplicateProfile => "Duplicate Profile",
+            PaletteCommand::ToggleTheme => "Toggle Light/Dark Theme",
+            PaletteCommand::ToggleRowDensity => "Toggle Compact Rows",
+            PaletteCommand::ToggleThousandsSeparators => "Toggle 1,000 Separators",
+            PaletteCommand::ToggleTypeColors => "Toggle Type Colors",
+            PaletteCommand::ToggleWrapCells => "Toggle Wrapped Cell Text",
+        }
+    }
+
+    pub fn shortcut(self) -> Option<&'static str> {
+        let mac = cfg!(target_os = "macos");
+        Some(match self {
+            PaletteCommand::RunQuery if mac => "Cmd+Enter",
+            PaletteCommand::RunQuery => "Ctrl+Enter",
+            PaletteCommand::RunSelection if mac => "Cmd+Shift+Enter",
+            PaletteCommand::RunSelection => "Ctrl+Shift+Enter",
+            PaletteCommand::FormatSql if mac => "Cmd+Shift+F",
+            PaletteCommand::FormatSql => "Ctrl+Shift+F",
+            PaletteCommand::ShowSchemaBrowser if mac => "Cmd+1",
+            PaletteCommand::ShowSchemaBrowser => "Ctrl+1",
+            PaletteCommand::ShowSqlEditor if mac => "Cmd+2",
+            PaletteCommand::ShowSqlEditor => "Ctrl+2",
+            PaletteCommand::ShowSessions if mac => "Cmd+3",
+            PaletteCommand::ShowSessions => "Ctrl+3",
+            PaletteCommand::ShowSchemaDiff if mac => "Cmd+4",
+            PaletteCommand::ShowSchemaDiff => "Ctrl+4",
+            _ => return None,
+        })
+    }
+}
+
+// Commands whose label contains the query's characters in order, best first;
+// ties keep the `ALL` order.
+pub fn matching_commands(query: &str) -> Vec<PaletteCommand> {
+    let mut scored: Vec<(i32, PaletteCommand)> = PaletteCommand::ALL
+        .into_iter()
+        .filter_map(|command| fuzzy_score(query, command.label()).map(|score| (score, command)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, command)| command).collect()
+}
+
+// Rewards consecutive matches and matches at word starts and penalizes
+// skipped characters before the last match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let needle: Vec<char> = query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if needle.is_empty() {
+        return Some(0);
+    }
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    for ch in candidate.chars() {
+        let word_start = previous.is_none_or(|prev| !prev.is_alphanumeric());
+        if next < needle.len() && ch.to_lowercase().eq(needle[next].to_lowercase()) {
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if word_start {
+                score += 6;
+            }
+            next += 1;
+            previous_matched = true;
+        } else {
+            if next < needle.len() {
+                score -= 1;
+            }
+            previous_matched = false;
+        }
+        previous = Some(ch);
+    }
+    (next == needle.len()).then_some(score)
+}
@@ -0,0 +1,133 @@
+use dbmiru_core::profiles::ProfileColor;
+use dbmiru_storage::ThemeMode;
+use gpui::{App, Global, Rgba, rgb, rgba};
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub canvas: Rgba,
+    pub panel: Rgba,
+    pub panel_muted: Rgba,
+    pub panel_highlight: Rgba,
+    pub border: Rgba,
+    pub text: Rgba,
+    pub text_bright: Rgba,
+    pub text_muted: Rgba,
+    pub on_accent: Rgba,
+    pub accent: Rgba,
+    pub accent_soft: Rgba,
+    pub accent_hover: Rgba,
+    pub success: Rgba,
+    pub warning: Rgba,
+    pub null: Rgba,
+    pub number: Rgba,
+    pub boolean: Rgba,
+    pub match_bg: Rgba,
+    pub danger: Rgba,
+    pub danger_soft: Rgba,
+    pub danger_surface: Rgba,
+    pub danger_hover: Rgba,
+    pub input_frame: Rgba,
+    pub input_bg: Rgba,
+    pub input_border: Rgba,
+    pub input_text: Rgba,
+    pub input_placeholder: Rgba,
+    pub selection: Rgba,
+}
+
+impl Theme {
+    pub fn for_mode(mode: ThemeMode) -> Self {
+        match mode {
+            ThemeMode::Dark => Self::dark(),
+            ThemeMode::Light => Self::light(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            canvas: rgb(0x040715),
+            panel: rgb(0x0a0f1d),
+            panel_muted: rgb(0x11182a),
+            panel_highlight: rgb(0x1c2342),
+            border: rgb(0x1f2a44),
+            text: rgb(0xf4f5fb),
+            text_bright: rgb(0xfdf4ff),
+            text_muted: rgb(0x94a3c4),
+            on_accent: rgb(0xf8fafc),
+            accent: rgb(0x8b5cf6),
+            accent_soft: rgb(0x7c3aed),
+            accent_hover: rgb(0x9f67ff),
+            success: rgb(0x10b981),
+            warning: rgb(0xfbbf24),
+            null: rgb(0x6b7280),
+            number: rgb(0x93c5fd),
+            boolean: rgb(0xc4b5fd),
+            match_bg: rgb(0x3b2a6b),
+            danger: rgb(0xf43f5e),
+            danger_soft: rgb(0xfda4af),
+            danger_surface: rgb(0x3a1826),
+            danger_hover: rgb(0x4a2034),
+            input_frame: rgb(0x111827),
+            input_bg: rgb(0x0b1120),
+            input_border: rgb(0x1f2937),
+            input_text: rgb(0xf8fafc),
+            input_placeholder: rgba(0xffffff59),
+            selection: rgba(0x3311ff30),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            canvas: rgb(0xeef0f6),
+            panel: rgb(0xffffff),
+            panel_muted: rgb(0xf6f7fb),
+            panel_highlight: rgb(0xe6e8f4),
+            border: rgb(0xd6d9e6),
+            text: rgb(0x1b1f2e),
+            text_bright: rgb(0x111827),
+            text_muted: rgb(0x5b6479),
+            on_accent: rgb(0xffffff),
+            accent: rgb(0x7c3aed),
+            accent_soft: rgb(0xa78bfa),
+            accent_hover: rgb(0x6d28d9),
+            success: rgb(0x047857),
+            warning: rgb(0xb45309),
+            null: rgb(0x9ca3af),
+            number: rgb(0x1d4ed8),
+            boolean: rgb(0x6d28d9),
+            match_bg: rgb(0xede4ff),
+            danger: rgb(0xe11d48),
+            danger_soft: rgb(0xbe123c),
+            danger_surface: rgb(0xffe4e6),
+            danger_hover: rgb(0xfecdd3),
+            input_frame: rgb(0xffffff),
+            input_bg: rgb(0xffffff),
+            input_border: rgb(0xd1d5db),
+            input_text: rgb(0x111827),
+            input_placeholder: rgba(0x11182766),
+            selection: rgba(0x7c3aed33),
+        }
+    }
+
+    pub fn profile_color(&self, color: ProfileColor) -> Rgba {
+        match color {
+            ProfileColor::Red => rgb(0xef4444),
+            ProfileColor::Orange => rgb(0xf97316),
+            ProfileColor::Yellow => rgb(0xeab308),
+            ProfileColor::Green => rgb(0x22c55e),
+            ProfileColor::Blue => rgb(0x3b82f6),
+            ProfileColor::Purple => rgb(0xa855f7),
+        }
+    }
+}
+
+impl Global for Theme {}
+
+pub trait ActiveTheme {
+    fn theme(&self) -> &Theme;
+}
+
+impl ActiveTheme for App {
+    fn theme(&self) -> &Theme {
+        self.global::<Theme>()
+    }
+}
@@ -5,10 +5,12 @@ use gpui::{
     Entity, EntityInputHandler, FocusHandle, Focusable, GlobalElementId, IntoElement, KeyBinding,
     LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, Pixels, Point,
     Render, ShapedLine, SharedString, Style, TextRun, UTF16Selection, UnderlineStyle, Window,
-    actions, div, fill, hsla, prelude::*, px, rgb, rgba,
+    actions, div, fill, prelude::*, px,
 };
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::theme::ActiveTheme;
+
 const OBSCURED_CHAR: &str = "•";
 const KEY_CONTEXT: &str = "TextInput";
 
@@ -19,6 +21,9 @@ actions!(
         Delete,
         Left,
         Right,
+        Up,
+        Down,
+        Newline,
         SelectLeft,
         SelectRight,
         SelectAll,
@@ -38,10 +43,15 @@ pub struct TextInput {
     selected_range: Range<usize>,
     selection_reversed: bool,
     marked_range: Option<Range<usize>>,
-    last_layout: Option<ShapedLine>,
+    error_range: Option<Range<usize>>,
+    last_layout: Vec<ShapedLine>,
+    last_line_starts: Vec<usize>,
+    last_line_height: Pixels,
     last_bounds: Option<Bounds<Pixels>>,
     is_selecting: bool,
     obscure: bool,
+    multiline: bool,
+    auto_pair: bool,
 }
 
 impl TextInput {
@@ -54,10 +64,15 @@ impl TextInput {
             selected_range: initial.len()..initial.len(),
             selection_reversed: false,
             marked_range: None,
-            last_layout: None,
+            error_range: None,
+            last_layout: Vec::new(),
+            last_line_starts: Vec::new(),
+            last_line_height: px(0.),
             last_bounds: None,
             is_selecting: false,
             obscure: false,
+            multiline: false,
+            auto_pair: false,
         }
     }
 
@@ -66,19 +81,67 @@ impl TextInput {
         self
     }
 
+    pub fn with_multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    pub fn with_auto_pair(mut self, auto_pair: bool) -> Self {
+        self.auto_pair = auto_pair;
+        self
+    }
+
+    pub fn set_auto_pair(&mut self, auto_pair: bool) {
+        self.auto_pair = auto_pair;
+    }
+
     pub fn set_text(&mut self, value: &str) {
         self.content = value.to_owned();
         let end = self.content.len();
         self.selected_range = end..end;
+        self.error_range = None;
+    }
+
+    pub fn set_cursor(&mut self, offset: usize) {
+        let offset = self.grapheme_start(offset);
+        self.selected_range = offset..offset;
     }
 
     pub fn text(&self) -> String {
         self.content.clone()
     }
 
+    pub fn selected_range(&self) -> Range<usize> {
+        self.selected_range.clone()
+    }
+
     pub fn clear(&mut self) {
         self.content.clear();
         self.selected_range = 0..0;
+        self.error_range = None;
+    }
+
+    pub fn mark_error_at(&mut self, char_offset: usize) {
+        let start = self
+            .content
+            .char_indices()
+            .nth(char_offset)
+            .map_or(self.content.len(), |(idx, _)| idx);
+        let rest = &self.content[start..];
+        let word_len = rest
+            .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .unwrap_or(rest.len());
+        let len = match word_len {
+            0 => rest.chars().next().map_or(0, char::len_utf8),
+            len => len,
+        };
+        self.selected_range = start..start;
+        self.selection_reversed = false;
+        self.error_range = (len > 0).then_some(start..start + len);
+    }
+
+    pub fn clear_error(&mut self) {
+        self.error_range = None;
     }
 
     fn schedule_redraw(window: &mut Window, cx: &mut Context<Self>) {
@@ -89,8 +152,7 @@ impl TextInput {
     }
 
     fn obscured_offset(&self, offset: usize) -> usize {
-        let char_count = self.content[..offset].chars().count();
-        char_count * OBSCURED_CHAR.len()
+        obscured_offset_in(&self.content, offset)
     }
 
     fn obscured_range(&self, range: &Range<usize>) -> Range<usize> {
@@ -110,19 +172,71 @@ impl TextInput {
             .or(self.marked_range.clone())
             .unwrap_or(self.selected_range.clone());
 
-        self.content =
-            self.content[0..range.start].to_owned() + new_text + &self.content[range.end..];
-        self.selected_range = range.start + new_text.len()..range.start + new_text.len();
+        let inserted;
+        (self.content, inserted) = splice_in(&self.content, range, new_text);
+        self.selected_range = inserted.end..inserted.end;
         self.marked_range.take();
+        self.error_range = None;
+        Self::schedule_redraw(window, cx);
+        cx.notify();
+    }
+    // Handles a typed bracket or quote: wraps a selection, types over a matching
+    // closer right after the cursor, or inserts the closing half of the pair.
+    fn insert_auto_pair(
+        &mut self,
+        typed: char,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let closer = match typed {
+            '(' => Some(')'),
+            '[' => Some(']'),
+            '\'' | '"' => Some(typed),
+            _ => None,
+        };
+        let is_closer = matches!(typed, ')' | ']' | '\'' | '"');
+        if closer.is_none() && !is_closer {
+            return false;
+        }
+        let range = self.selected_range.clone();
+        let before = self.content[..range.start].chars().next_back();
+        let after = self.content[range.end..].chars().next();
+
+        if let Some(closer) = closer
+            && !range.is_empty()
+        {
+            let wrapped = format!("{typed}{}{closer}", &self.content[range.clone()]);
+            self.content.replace_range(range.clone(), &wrapped);
+            self.selected_range = range.start + 1..range.end + 1;
+        } else if range.is_empty() && is_closer && after == Some(typed) {
+            self.selected_range = range.end + 1..range.end + 1;
+        } else if let Some(closer) = closer
+            && range.is_empty()
+            && after.is_none_or(|ch| ch.is_whitespace() || matches!(ch, ')' | ']' | ',' | ';'))
+            && !(typed == closer && before.is_some_and(|ch| ch.is_alphanumeric() || ch == '_'))
+        {
+            self.content
+                .insert_str(range.start, &format!("{typed}{closer}"));
+            self.selected_range = range.start + 1..range.start + 1;
+        } else {
+            return false;
+        }
+        self.selection_reversed = false;
+        self.error_range = None;
         Self::schedule_redraw(window, cx);
         cx.notify();
+        true
     }
+
     fn register_keybindings(cx: &mut Context<Self>) {
         cx.bind_keys([
             KeyBinding::new("backspace", Backspace, Some(KEY_CONTEXT)),
             KeyBinding::new("delete", Delete, Some(KEY_CONTEXT)),
             KeyBinding::new("left", Left, Some(KEY_CONTEXT)),
             KeyBinding::new("right", Right, Some(KEY_CONTEXT)),
+            KeyBinding::new("up", Up, Some(KEY_CONTEXT)),
+            KeyBinding::new("down", Down, Some(KEY_CONTEXT)),
+            KeyBinding::new("enter", Newline, Some(KEY_CONTEXT)),
             KeyBinding::new("shift-left", SelectLeft, Some(KEY_CONTEXT)),
             KeyBinding::new("shift-right", SelectRight, Some(KEY_CONTEXT)),
             KeyBinding::new("cmd-a", SelectAll, Some(KEY_CONTEXT)),
@@ -156,6 +270,44 @@ impl TextInput {
         }
     }
 
+    fn up(&mut self, _: &Up, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.multiline {
+            cx.propagate();
+            return;
+        }
+        let cursor = self.cursor_offset();
+        let line = self.line_for_offset(cursor);
+        if line == 0 {
+            self.move_to(0, cx);
+        } else {
+            let offset = self.offset_for_line_x(line - 1, self.x_for_offset(cursor));
+            self.move_to(offset, cx);
+        }
+    }
+
+    fn down(&mut self, _: &Down, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.multiline {
+            cx.propagate();
+            return;
+        }
+        let cursor = self.cursor_offset();
+        let line = self.line_for_offset(cursor);
+        if line + 1 >= self.last_layout.len() {
+            self.move_to(self.content.len(), cx);
+        } else {
+            let offset = self.offset_for_line_x(line + 1, self.x_for_offset(cursor));
+            self.move_to(offset, cx);
+        }
+    }
+
+    fn newline(&mut self, _: &Newline, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.multiline {
+            cx.propagate();
+            return;
+        }
+        self.replace_text_in_range(None, "\n", window, cx);
+    }
+
     fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
         self.select_to(self.previous_boundary(self.cursor_offset()), cx);
     }
@@ -170,11 +322,25 @@ impl TextInput {
     }
 
     fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
-        self.move_to(0, cx);
+        if self.multiline {
+            let cursor = self.cursor_offset();
+            let start = self.content[..cursor].rfind('\n').map_or(0, |idx| idx + 1);
+            self.move_to(start, cx);
+        } else {
+            self.move_to(0, cx);
+        }
     }
 
     fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
-        self.move_to(self.content.len(), cx);
+        if self.multiline {
+            let cursor = self.cursor_offset();
+            let end = self.content[cursor..]
+                .find('\n')
+                .map_or(self.content.len(), |idx| cursor + idx);
+            self.move_to(end, cx);
+        } else {
+            self.move_to(self.content.len(), cx);
+        }
     }
 
     fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
@@ -262,12 +428,11 @@ impl TextInput {
     }
 
     fn index_for_mouse_position(&self, position: Point<Pixels>) -> usize {
-        if self.content.is_empty() {
+        if self.content.is_empty() || self.last_layout.is_empty() {
             return 0;
         }
 
-        let (Some(bounds), Some(line)) = (self.last_bounds.as_ref(), self.last_layout.as_ref())
-        else {
+        let Some(bounds) = self.last_bounds.as_ref() else {
             return 0;
         };
         if position.y < bounds.top() {
@@ -276,7 +441,58 @@ impl TextInput {
         if position.y > bounds.bottom() {
             return self.content.len();
         }
-        line.closest_index_for_x(position.x - bounds.left())
+        let line = if self.last_line_height > px(0.) {
+            ((position.y - bounds.top()) / self.last_line_height) as usize
+        } else {
+            0
+        };
+        self.offset_for_line_x(
+            line.min(self.last_layout.len() - 1),
+            position.x - bounds.left(),
+        )
+    }
+
+    // The shaped lines hold the displayed text, which differs from `content`
+    // when the input is obscured.
+    fn display_offset(&self, offset: usize) -> usize {
+        if self.obscure {
+            self.obscured_offset(offset)
+        } else {
+            offset
+        }
+    }
+
+    fn content_offset(&self, display_offset: usize) -> usize {
+        content_offset_in(&self.content, display_offset, self.obscure)
+    }
+
+    fn grapheme_start(&self, offset: usize) -> usize {
+        grapheme_start_in(&self.content, offset)
+    }
+
+    fn line_for_offset(&self, offset: usize) -> usize {
+        let offset = self.display_offset(offset);
+        self.last_line_starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1)
+    }
+
+    fn x_for_offset(&self, offset: usize) -> Pixels {
+        let line = self.line_for_offset(offset);
+        let offset = self.display_offset(offset);
+        match (self.last_layout.get(line), self.last_line_starts.get(line)) {
+            (Some(layout), Some(start)) => layout.x_for_index(offset.saturating_sub(*start)),
+            _ => px(0.),
+        }
+    }
+
+    fn offset_for_line_x(&self, line: usize, x: Pixels) -> usize {
+        match (self.last_layout.get(line), self.last_line_starts.get(line)) {
+            (Some(layout), Some(start)) => {
+                self.content_offset(start + layout.closest_index_for_x(x))
+            }
+            _ => self.content.len(),
+        }
     }
 
     fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
@@ -308,9 +524,7 @@ impl TextInput {
     }
 
     fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
-        let start = self.offset_from_utf16(range_utf16.start);
-        let end = self.offset_from_utf16(range_utf16.end);
-        start..end
+        range_from_utf16_in(&self.content, range_utf16)
     }
 
     fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
@@ -320,27 +534,7 @@ impl TextInput {
     }
 
     fn utf16_offset(&self, offset: usize) -> usize {
-        let mut utf16_offset = 0;
-        for (idx, ch) in self.content.char_indices() {
-            if idx >= offset {
-                break;
-            }
-            utf16_offset += ch.len_utf16();
-        }
-        utf16_offset
-    }
-
-    fn offset_from_utf16(&self, utf16_offset: usize) -> usize {
-        let mut utf8_offset = 0;
-        let mut utf16_count = 0;
-        for ch in self.content.chars() {
-            if utf16_count >= utf16_offset {
-                break;
-            }
-            utf16_count += ch.len_utf16();
-            utf8_offset += ch.len_utf8();
-        }
-        utf8_offset
+        utf16_offset_in(&self.content, offset)
     }
 }
 
@@ -390,6 +584,16 @@ impl EntityInputHandler for TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.auto_pair
+            && self.marked_range.is_none()
+            && range_utf16
+                .as_ref()
+                .is_none_or(|range| self.range_from_utf16(range) == self.selected_range)
+            && let Some(typed) = single_char(new_text)
+            && self.insert_auto_pair(typed, window, cx)
+        {
+            return;
+        }
         self.replace_text_in_range(range_utf16, new_text, window, cx);
     }
 
@@ -407,10 +611,12 @@ impl EntityInputHandler for TextInput {
             .or(self.marked_range.clone())
             .unwrap_or(self.selected_range.clone());
 
-        self.content =
-            self.content[0..range.start].to_owned() + new_text + &self.content[range.end..];
+        (self.content, _) = splice_in(&self.content, range.clone(), new_text);
+        // The IME reports the selection relative to the marked text, not the whole buffer.
         if let Some(selected_range_utf16) = new_selected_range_utf16 {
-            self.selected_range = self.range_from_utf16(&selected_range_utf16);
+            self.selected_range = range.start
+                + offset_from_utf16_in(new_text, selected_range_utf16.start)
+                ..range.start + offset_from_utf16_in(new_text, selected_range_utf16.end);
         } else {
             self.selected_range = range.start + new_text.len()..range.start + new_text.len();
         }
@@ -419,6 +625,7 @@ impl EntityInputHandler for TextInput {
         } else {
             self.marked_range = None;
         }
+        self.error_range = None;
         Self::schedule_redraw(window, cx);
         cx.notify();
     }
@@ -431,22 +638,27 @@ impl EntityInputHandler for TextInput {
         cx: &mut Context<Self>,
     ) -> Option<Bounds<Pixels>> {
         let range = self.range_from_utf16(&range_utf16);
-        let mut min_x = None;
-        let mut max_x = None;
-        if let Some(line) = self.last_layout.as_ref() {
-            min_x = Some(line.x_for_index(range.start));
-            max_x = Some(line.x_for_index(range.end));
-        }
-        match (min_x, max_x) {
-            (Some(min_x), Some(max_x)) => Some(Bounds::from_corners(
-                Point::new(bounds.left() + min_x, bounds.top()),
-                Point::new(bounds.left() + max_x, bounds.bottom()),
-            )),
-            _ => {
-                cx.notify();
-                None
-            }
+        if self.last_layout.is_empty() {
+            cx.notify();
+            return None;
         }
+        let line = self.line_for_offset(range.start);
+        let min_x = self.x_for_offset(range.start);
+        let max_x = if self.line_for_offset(range.end) == line {
+            self.x_for_offset(range.end)
+        } else {
+            self.last_layout[line].width
+        };
+        let (top, bottom) = if self.multiline {
+            let top = bounds.top() + self.last_line_height * line as f32;
+            (top, top + self.last_line_height)
+        } else {
+            (bounds.top(), bounds.bottom())
+        };
+        Some(Bounds::from_corners(
+            Point::new(bounds.left() + min_x, top),
+            Point::new(bounds.left() + max_x, bottom),
+        ))
     }
 
     fn character_index_for_point(
@@ -456,10 +668,10 @@ impl EntityInputHandler for TextInput {
         _cx: &mut Context<Self>,
     ) -> Option<usize> {
         if let Some(bounds) = self.last_bounds
-            && let Some(line) = self.last_layout.as_ref()
+            && !self.last_layout.is_empty()
             && bounds.contains(&point)
         {
-            return Some(line.closest_index_for_x(point.x - bounds.left()));
+            return Some(self.utf16_offset(self.index_for_mouse_position(point)));
         }
         None
     }
@@ -473,6 +685,7 @@ impl Focusable for TextInput {
 
 impl Render for TextInput {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = *cx.theme();
         div()
             .flex()
             .key_context("TextInput")
@@ -482,6 +695,9 @@ impl Render for TextInput {
             .on_action(cx.listener(Self::delete))
             .on_action(cx.listener(Self::left))
             .on_action(cx.listener(Self::right))
+            .on_action(cx.listener(Self::up))
+            .on_action(cx.listener(Self::down))
+            .on_action(cx.listener(Self::newline))
             .on_action(cx.listener(Self::select_left))
             .on_action(cx.listener(Self::select_right))
             .on_action(cx.listener(Self::select_all))
@@ -495,18 +711,18 @@ impl Render for TextInput {
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_move(cx.listener(Self::on_mouse_move))
-            .bg(rgb(0x111827))
-            .text_color(rgb(0xf8fafc))
+            .bg(theme.input_frame)
+            .text_color(theme.input_text)
             .child(
                 div()
-                    .h(px(36.))
+                    .when(self.multiline, |node| node.min_h(px(96.)).py_2())
+                    .when(!self.multiline, |node| node.h(px(36.)).items_center())
                     .w_full()
                     .px_3()
                     .rounded_lg()
-                    .items_center()
-                    .bg(rgb(0x0b1120))
+                    .bg(theme.input_bg)
                     .border_1()
-                    .border_color(rgb(0x1f2937))
+                    .border_color(theme.input_border)
                     .child(TextElement { input: cx.entity() }),
             )
     }
@@ -525,7 +741,20 @@ impl IntoElement for TextElement {
 }
 
 impl TextElement {
-    fn text_bounds(bounds: Bounds<Pixels>, line_height: Pixels) -> (Bounds<Pixels>, Pixels) {
+    fn text_bounds(
+        bounds: Bounds<Pixels>,
+        line_height: Pixels,
+        line_count: usize,
+        multiline: bool,
+    ) -> (Bounds<Pixels>, Pixels) {
+        if multiline {
+            let text_height = line_height * line_count.max(1) as f32;
+            return (
+                Bounds::new(bounds.origin, gpui::size(bounds.size.width, text_height)),
+                line_height,
+            );
+        }
+
         let available_height = bounds.bottom() - bounds.top();
         let text_height = if available_height < line_height {
             available_height
@@ -547,10 +776,101 @@ impl TextElement {
     }
 }
 
+// One bullet per grapheme, so the cursor steps over the mask the same way
+// it steps over the hidden text.
+fn obscured_offset_in(text: &str, offset: usize) -> usize {
+    text[..offset].graphemes(true).count() * OBSCURED_CHAR.len()
+}
+
+// Maps an offset in the displayed text (the bullets when obscured) back to a
+// grapheme boundary in `text`.
+fn content_offset_in(text: &str, display_offset: usize, obscure: bool) -> usize {
+    if obscure {
+        let graphemes = display_offset.div_ceil(OBSCURED_CHAR.len());
+        text.grapheme_indices(true)
+            .nth(graphemes)
+            .map_or(text.len(), |(idx, _)| idx)
+    } else {
+        grapheme_start_in(text, display_offset)
+    }
+}
+
+fn grapheme_start_in(text: &str, offset: usize) -> usize {
+    let offset = offset.min(text.len());
+    text.grapheme_indices(true)
+        .map(|(idx, _)| idx)
+        .chain([text.len()])
+        .take_while(|idx| *idx <= offset)
+        .last()
+        .unwrap_or(0)
+}
+
+// Replaces `range` with `new_text`, returning the new text and the range the
+// insertion occupies in it.
+fn splice_in(text: &str, range: Range<usize>, new_text: &str) -> (String, Range<usize>) {
+    let spliced = text[..range.start].to_owned() + new_text + &text[range.end..];
+    (spliced, range.start..range.start + new_text.len())
+}
+
+fn range_from_utf16_in(text: &str, range_utf16: &Range<usize>) -> Range<usize> {
+    offset_from_utf16_in(text, range_utf16.start)..offset_from_utf16_in(text, range_utf16.end)
+}
+
+fn utf16_offset_in(text: &str, offset: usize) -> usize {
+    let mut utf16_offset = 0;
+    for (idx, ch) in text.char_indices() {
+        if idx >= offset {
+            break;
+        }
+        utf16_offset += ch.len_utf16();
+    }
+    utf16_offset
+}
+
+// An offset that lands inside a surrogate pair rounds up to the end of that char,
+// so the result is always a char boundary.
+fn offset_from_utf16_in(text: &str, utf16_offset: usize) -> usize {
+    let mut utf8_offset = 0;
+    let mut utf16_count = 0;
+    for ch in text.chars() {
+        if utf16_count >= utf16_offset {
+            break;
+        }
+        utf16_count += ch.len_utf16();
+        utf8_offset += ch.len_utf8();
+    }
+    utf8_offset
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    let ch = chars.next()?;
+    chars.next().is_none().then_some(ch)
+}
+
+fn runs_for_range(runs: &[TextRun], range: Range<usize>) -> Vec<TextRun> {
+    let mut sliced = Vec::new();
+    let mut run_start = 0;
+    for run in runs {
+        let run_end = run_start + run.len;
+        let start = run_start.max(range.start);
+        let end = run_end.min(range.end);
+        if start < end {
+            sliced.push(TextRun {
+                len: end - start,
+                ..run.clone()
+            });
+        }
+        run_start = run_end;
+    }
+    sliced
+}
+
 struct PrepaintState {
-    line: Option<ShapedLine>,
+    lines: Vec<ShapedLine>,
+    line_starts: Vec<usize>,
     cursor: Option<PaintQuad>,
-    selection: Option<PaintQuad>,
+    selections: Vec<PaintQuad>,
 }
 
 impl Element for TextElement {
@@ -572,9 +892,15 @@ impl Element for TextElement {
         window: &mut Window,
         cx: &mut App,
     ) -> (LayoutId, Self::RequestLayoutState) {
+        let input = self.input.read(cx);
         let mut style = Style::default();
         style.size.width = gpui::relative(1.).into();
-        style.size.height = gpui::relative(1.).into();
+        if input.multiline {
+            let line_count = input.content.split('\n').count();
+            style.size.height = (window.line_height() * line_count as f32).into();
+        } else {
+            style.size.height = gpui::relative(1.).into();
+        }
         (window.request_layout(style, [], cx), ())
     }
 
@@ -594,11 +920,10 @@ impl Element for TextElement {
         let mut cursor = input.cursor_offset();
         let mut marked_range = input.marked_range.clone();
         let line_height = window.line_height();
-        let (text_bounds, text_height) = Self::text_bounds(bounds, line_height);
 
         if input.obscure && !display_text.is_empty() {
-            let char_count = input.content.chars().count();
-            display_text = OBSCURED_CHAR.repeat(char_count);
+            let grapheme_count = input.content.graphemes(true).count();
+            display_text = OBSCURED_CHAR.repeat(grapheme_count);
             selected_range = input.obscured_range(&input.selected_range);
             cursor = if input.selection_reversed {
                 input.obscured_offset(input.selected_range.start)
@@ -612,7 +937,10 @@ impl Element for TextElement {
         }
 
         let (display_text, text_color) = if display_text.is_empty() {
-            (input.placeholder.clone(), hsla(0., 0., 1., 0.35))
+            (
+                input.placeholder.clone(),
+                cx.theme().input_placeholder.into(),
+            )
         } else {
             (display_text, style.color)
         };
@@ -625,23 +953,42 @@ impl Element for TextElement {
             underline: None,
             strikethrough: None,
         };
-        let runs = if let Some(marked_range) = marked_range.as_ref() {
+        let underlined = match (marked_range, input.error_range.clone()) {
+            (Some(marked_range), _) => Some((
+                marked_range,
+                UnderlineStyle {
+                    color: Some(run.color),
+                    thickness: px(1.0),
+                    wavy: false,
+                },
+            )),
+            (None, Some(error_range))
+                if !input.obscure && error_range.end <= display_text.len() =>
+            {
+                Some((
+                    error_range,
+                    UnderlineStyle {
+                        color: Some(cx.theme().danger.into()),
+                        thickness: px(1.0),
+                        wavy: true,
+                    },
+                ))
+            }
+            _ => None,
+        };
+        let runs = if let Some((underlined_range, underline)) = underlined {
             vec![
                 TextRun {
-                    len: marked_range.start,
+                    len: underlined_range.start,
                     ..run.clone()
                 },
                 TextRun {
-                    len: marked_range.end - marked_range.start,
-                    underline: Some(UnderlineStyle {
-                        color: Some(run.color),
-                        thickness: px(1.0),
-                        wavy: false,
-                    }),
+                    len: underlined_range.end - underlined_range.start,
+                    underline: Some(underline),
                     ..run.clone()
                 },
                 TextRun {
-                    len: display_text.len() - marked_range.end,
+                    len: display_text.len() - underlined_range.end,
                     ..run
                 },
             ]
@@ -652,47 +999,81 @@ impl Element for TextElement {
             vec![run]
         };
 
+        let mut line_starts = vec![0];
+        if input.multiline {
+            line_starts.extend(display_text.match_indices('\n').map(|(idx, _)| idx + 1));
+        }
         let font_size = style.font_size.to_pixels(window.rem_size());
-        let shared_text: SharedString = display_text.clone().into();
-        let line = window
-            .text_system()
-            .shape_line(shared_text, font_size, &runs, None);
-
-        let cursor_pos = line.x_for_index(cursor);
-        let (selection, cursor) = if selected_range.is_empty() {
+        let lines: Vec<ShapedLine> = line_starts
+            .iter()
+            .enumerate()
+            .map(|(idx, start)| {
+                let end = line_starts
+                    .get(idx + 1)
+                    .map_or(display_text.len(), |next| next - 1);
+                let shared_text: SharedString = display_text[*start..end].to_string().into();
+                window.text_system().shape_line(
+                    shared_text,
+                    font_size,
+                    &runs_for_range(&runs, *start..end),
+                    None,
+                )
+            })
+            .collect();
+        let (text_bounds, text_height) =
+            Self::text_bounds(bounds, line_height, lines.len(), input.multiline);
+
+        let line_index = |offset: usize| {
+            line_starts
+                .partition_point(|start| *start <= offset)
+                .saturating_sub(1)
+        };
+        let line_top = |line: usize| text_bounds.top() + text_height * line as f32;
+        let (selections, cursor) = if selected_range.is_empty() {
+            let line = line_index(cursor);
+            let cursor_pos = lines[line].x_for_index(cursor - line_starts[line]);
             (
-                None,
+                Vec::new(),
                 Some(fill(
                     Bounds::new(
-                        Point::new(text_bounds.left() + cursor_pos, text_bounds.top()),
+                        Point::new(text_bounds.left() + cursor_pos, line_top(line)),
                         gpui::size(px(2.), text_height),
                     ),
                     gpui::blue(),
                 )),
             )
         } else {
-            (
-                Some(fill(
-                    Bounds::from_corners(
-                        Point::new(
-                            text_bounds.left() + line.x_for_index(selected_range.start),
-                            text_bounds.top(),
-                        ),
-                        Point::new(
-                            text_bounds.left() + line.x_for_index(selected_range.end),
-                            text_bounds.bottom(),
+            let first = line_index(selected_range.start);
+            let last = line_index(selected_range.end);
+            let selections = (first..=last)
+                .map(|line| {
+                    let start_x = if line == first {
+                        lines[line].x_for_index(selected_range.start - line_starts[line])
+                    } else {
+                        px(0.)
+                    };
+                    let end_x = if line == last {
+                        lines[line].x_for_index(selected_range.end - line_starts[line])
+                    } else {
+                        lines[line].width + px(4.)
+                    };
+                    fill(
+                        Bounds::from_corners(
+                            Point::new(text_bounds.left() + start_x, line_top(line)),
+                            Point::new(text_bounds.left() + end_x, line_top(line) + text_height),
                         ),
-                    ),
-                    rgba(0x3311ff30),
-                )),
-                None,
-            )
+                        cx.theme().selection,
+                    )
+                })
+                .collect();
+            (selections, None)
         };
 
         PrepaintState {
-            line: Some(line),
+            lines,
+            line_starts,
             cursor,
-            selection,
+            selections,
         }
     }
 
@@ -706,20 +1087,28 @@ impl Element for TextElement {
         window: &mut Window,
         cx: &mut App,
     ) {
-        let focus_handle = self.input.read(cx).focus_handle.clone();
+        let input = self.input.read(cx);
+        let focus_handle = input.focus_handle.clone();
+        let multiline = input.multiline;
         window.handle_input(
             &focus_handle,
             ElementInputHandler::new(bounds, self.input.clone()),
             cx,
         );
-        if let Some(selection) = prepaint.selection.take() {
+        for selection in prepaint.selections.drain(..) {
             window.paint_quad(selection)
         }
-        let line = prepaint.line.take().unwrap();
+        let lines = std::mem::take(&mut prepaint.lines);
         let line_height = window.line_height();
-        let (text_bounds, text_height) = Self::text_bounds(bounds, line_height);
-        line.paint(text_bounds.origin, text_height, window, cx)
-            .unwrap();
+        let (text_bounds, text_height) =
+            Self::text_bounds(bounds, line_height, lines.len(), multiline);
+        for (idx, line) in lines.iter().enumerate() {
+            let origin = Point::new(
+                text_bounds.left(),
+                text_bounds.top() + text_height * idx as f32,
+            );
+            line.paint(origin, text_height, window, cx).unwrap();
+        }
 
         if focus_handle.is_focused(window)
             && let Some(cursor) = prepaint.cursor.take()
@@ -727,9 +1116,135 @@ impl Element for TextElement {
             window.paint_quad(cursor);
         }
 
+        let line_starts = std::mem::take(&mut prepaint.line_starts);
         self.input.update(cx, |input, _cx| {
-            input.last_layout = Some(line);
+            input.last_layout = lines;
+            input.last_line_starts = line_starts;
+            input.last_line_height = text_height;
             input.last_bounds = Some(text_bounds);
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    const FLAG: &str = "\u{1F1EF}\u{1F1F5}";
+    const E_ACUTE: &str = "e\u{301}";
+
+    // "a" 0..1, family 1..19, flag 19..27, e + accent 27..30, "z" 30..31.
+    fn sample() -> String {
+        format!("a{FAMILY}{FLAG}{E_ACUTE}z")
+    }
+
+    #[test]
+    fn grapheme_start_snaps_back_to_cluster_start() {
+        let text = sample();
+        assert_eq!(grapheme_start_in(&text, 0), 0);
+        assert_eq!(grapheme_start_in(&text, 5), 1);
+        assert_eq!(grapheme_start_in(&text, 18), 1);
+        assert_eq!(grapheme_start_in(&text, 23), 19);
+        assert_eq!(grapheme_start_in(&text, 28), 27);
+        assert_eq!(grapheme_start_in(&text, 30), 30);
+        assert_eq!(grapheme_start_in(&text, 31), 31);
+        assert_eq!(grapheme_start_in(&text, 100), 31);
+        assert_eq!(grapheme_start_in("", 3), 0);
+    }
+
+    #[test]
+    fn obscured_offsets_count_one_bullet_per_grapheme() {
+        let text = sample();
+        let bullet = OBSCURED_CHAR.len();
+        assert_eq!(obscured_offset_in(&text, 1), bullet);
+        assert_eq!(obscured_offset_in(&text, 19), 2 * bullet);
+        assert_eq!(obscured_offset_in(&text, 30), 4 * bullet);
+        assert_eq!(obscured_offset_in(&text, text.len()), 5 * bullet);
+    }
+
+    #[test]
+    fn content_offset_maps_display_offsets_to_boundaries() {
+        let text = sample();
+        let bullet = OBSCURED_CHAR.len();
+        assert_eq!(content_offset_in(&text, 2 * bullet, true), 19);
+        // Inside a bullet rounds up to the next grapheme.
+        assert_eq!(content_offset_in(&text, bullet + 1, true), 19);
+        assert_eq!(content_offset_in(&text, 5 * bullet, true), text.len());
+        for offset in 0..=text.len() {
+            let display = obscured_offset_in(&text, grapheme_start_in(&text, offset));
+            assert_eq!(
+                content_offset_in(&text, display, true),
+                grapheme_start_in(&text, offset)
+            );
+        }
+        assert_eq!(content_offset_in(&text, 10, false), 1);
+        assert_eq!(content_offset_in(&text, 29, false), 27);
+    }
+
+    #[test]
+    fn utf16_offsets_round_trip() {
+        let text = sample();
+        assert_eq!(utf16_offset_in(&text, 1), 1);
+        assert_eq!(utf16_offset_in(&text, 19), 9);
+        assert_eq!(utf16_offset_in(&text, 27), 13);
+        assert_eq!(utf16_offset_in(&text, 30), 15);
+        assert_eq!(utf16_offset_in(&text, text.len()), 16);
+        for (offset, _) in text.char_indices() {
+            assert_eq!(
+                offset_from_utf16_in(&text, utf16_offset_in(&text, offset)),
+                offset
+            );
+        }
+        assert_eq!(offset_from_utf16_in(&text, 16), text.len());
+        assert_eq!(offset_from_utf16_in(&text, 99), text.len());
+    }
+
+    #[test]
+    fn utf16_offset_inside_surrogate_pair_rounds_up() {
+        let text = sample();
+        // 2 is the low half of the first emoji, 10 the low half of the flag's first letter.
+        assert_eq!(offset_from_utf16_in(&text, 2), 5);
+        assert_eq!(offset_from_utf16_in(&text, 10), 23);
+        assert!(text.is_char_boundary(offset_from_utf16_in(&text, 2)));
+    }
+
+    #[test]
+    fn replace_utf16_range_swaps_whole_clusters() {
+        let text = sample();
+        let range = range_from_utf16_in(&text, &(9..13));
+        assert_eq!(range, 19..27);
+        let (replaced, inserted) = splice_in(&text, range, "\u{1F1EB}\u{1F1F7}");
+        assert_eq!(replaced, format!("a{FAMILY}\u{1F1EB}\u{1F1F7}{E_ACUTE}z"));
+        assert_eq!(inserted, 19..27);
+
+        let range = range_from_utf16_in(&replaced, &(1..9));
+        let (replaced, inserted) = splice_in(&replaced, range, "");
+        assert_eq!(replaced, format!("a\u{1F1EB}\u{1F1F7}{E_ACUTE}z"));
+        assert_eq!(inserted, 1..1);
+    }
+
+    #[test]
+    fn replace_with_combining_mark_joins_previous_grapheme() {
+        let text = "ez";
+        let range = range_from_utf16_in(text, &(1..1));
+        let (replaced, inserted) = splice_in(text, range, "\u{301}");
+        assert_eq!(replaced, format!("{E_ACUTE}z"));
+        assert_eq!(inserted, 1..3);
+        // The cursor after the accent sits on the cluster boundary.
+        assert_eq!(grapheme_start_in(&replaced, inserted.end), 3);
+        assert_eq!(grapheme_start_in(&replaced, 1), 0);
+        assert_eq!(utf16_offset_in(&replaced, inserted.end), 2);
+    }
+
+    #[test]
+    fn replace_at_end_of_text() {
+        let text = sample();
+        let range = range_from_utf16_in(&text, &(16..16));
+        assert_eq!(range, text.len()..text.len());
+        let (replaced, inserted) = splice_in(&text, range, FLAG);
+        assert_eq!(replaced, format!("{text}{FLAG}"));
+        assert_eq!(inserted.end, replaced.len());
+        assert_eq!(grapheme_start_in(&replaced, inserted.end), replaced.len());
+    }
+}
@@ -1,66 +1,320 @@
+mod mysql;
 mod postgres;
+pub mod schema_diff;
+pub mod sql;
+pub mod sqlstate;
+mod ssh;
 
 use std::{
+    collections::VecDeque,
     future::Future,
     pin::Pin,
-    sync::mpsc::{self, Sender as BlockingSender},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender as BlockingSender},
+    },
     thread,
+    time::Duration,
 };
 
 use anyhow::Error;
 use async_channel::Sender;
-use dbmiru_core::Result;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use dbmiru_core::{
+    Result,
+    profiles::{ConnectionProfile, DEFAULT_ROW_LIMIT, ProfileKind},
+};
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+    task::JoinHandle,
+};
 
+pub use mysql::MySqlAdapter;
 pub use postgres::PostgresAdapter;
 
-pub const ROW_LIMIT: usize = 1000;
+pub const ROW_LIMIT: usize = DEFAULT_ROW_LIMIT;
 pub const PREVIEW_LIMIT: usize = 50;
+pub const STATS_SAMPLE_ROWS: usize = 100_000;
 
 pub type ConnectionClosedFuture = Pin<Box<dyn Future<Output = Option<String>> + Send>>;
+pub type CancelFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+pub type QueryCanceller = Box<dyn Fn() -> CancelFuture + Send + Sync>;
+
+#[derive(Clone)]
+pub struct ViewMetadata {
+    pub name: String,
+    pub materialized: bool,
+}
+
+#[derive(Clone)]
+pub struct IndexMetadata {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub primary: bool,
+}
+
+#[derive(Clone)]
+pub struct ForeignKeyMetadata {
+    pub name: String,
+    pub column: String,
+    pub target_schema: String,
+    pub target_table: String,
+    pub target_column: String,
+}
+
+#[derive(Clone)]
+pub struct ServerInfo {
+    pub version: String,
+    pub database: String,
+    pub user: String,
+}
+
+#[derive(Clone)]
+pub struct SessionActivity {
+    pub pid: i64,
+    pub user: String,
+    pub database: String,
+    pub application: String,
+    pub client: String,
+    pub state: String,
+    pub wait_event: Option<String>,
+    // Time spent in the current query; None unless the session is running one.
+    pub query_duration: Option<Duration>,
+    pub query: String,
+    // The backend of the connection that fetched the list.
+    pub current: bool,
+}
+
+#[derive(Clone)]
+pub struct ColumnStats {
+    pub rows: u64,
+    pub non_null: u64,
+    pub distinct: u64,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub sampled: bool,
+}
 
 #[derive(Clone)]
 pub struct ColumnMetadata {
     pub name: String,
     pub data_type: String,
+    pub nullable: Option<bool>,
 }
 
 pub enum DbEvent {
     Connected(DbSessionHandle),
     ConnectionFailed(ConnectionError),
+    // A transient connect failure; the worker tries again after a backoff.
+    ConnectRetrying {
+        attempt: u32,
+        retries: u32,
+        error: ConnectionError,
+    },
     ConnectionClosed(Option<String>),
+    ConnectionTested(std::result::Result<String, ConnectionError>),
     QueryFinished(QueryResult),
-    QueryFailed(String),
+    StatementCompleted {
+        affected: u64,
+        tag: String,
+        timings: Vec<StatementTiming>,
+    },
+    QueryFailed(QueryError),
+    QueryCancelled,
+    Notice(String),
+    ServerInfoLoaded {
+        version: String,
+        database: String,
+        user: String,
+    },
+    StatementTimeoutSet(Option<Duration>),
+    TransactionStarted,
+    TransactionEnded {
+        committed: bool,
+    },
     SchemasLoaded(Vec<String>),
     TablesLoaded {
         schema: String,
         tables: Vec<String>,
     },
+    ViewsLoaded {
+        schema: String,
+        views: Vec<ViewMetadata>,
+    },
+    TableSizesLoaded {
+        schema: String,
+        sizes: Vec<(String, u64)>,
+    },
     ColumnsLoaded {
         schema: String,
         table: String,
         columns: Vec<ColumnMetadata>,
     },
+    IndexesLoaded {
+        schema: String,
+        table: String,
+        indexes: Vec<IndexMetadata>,
+    },
+    ForeignKeysLoaded {
+        schema: String,
+        table: String,
+        foreign_keys: Vec<ForeignKeyMetadata>,
+    },
     TablePreviewReady {
         schema: String,
         table: String,
         result: QueryResult,
     },
+    DdlReady {
+        schema: String,
+        table: String,
+        ddl: String,
+    },
+    ColumnStatsReady {
+        schema: String,
+        table: String,
+        column: String,
+        stats: ColumnStats,
+    },
+    BulkInserted {
+        schema: String,
+        table: String,
+        rows: u64,
+    },
+    BulkInsertFailed {
+        schema: String,
+        table: String,
+        message: String,
+    },
+    ActivityLoaded(Vec<SessionActivity>),
+    BackendTerminated {
+        pid: i64,
+        terminated: bool,
+    },
+    Listening {
+        channel: String,
+    },
+    Unlistened {
+        channel: String,
+    },
+    ListenFailed {
+        channel: String,
+        message: String,
+    },
+    // Delivered as soon as the server sends it, independent of running queries.
+    Notification {
+        channel: String,
+        payload: String,
+    },
     MetadataFailed(String),
 }
 
 pub struct QueryResult {
     pub columns: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    pub column_metadata: Vec<ColumnMetadata>,
+    pub rows: Vec<Vec<Cell>>,
     pub row_count: usize,
     pub duration: std::time::Duration,
     pub truncated: bool,
+    pub timings: Vec<StatementTiming>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StatementTiming {
+    pub statement: String,
+    pub prepare: Option<std::time::Duration>,
+    pub execute: std::time::Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Null,
+    Text(String),
 }
 
+impl Cell {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Cell::Null => None,
+            Cell::Text(value) => Some(value),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.as_text().unwrap_or("NULL")
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Cell::Null)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ParamValue {
+    Null,
+    Text(String),
+    Int(i64),
+    Bool(bool),
+}
+
+pub enum ExecuteOutcome {
+    Rows(QueryResult),
+    Completed {
+        affected: u64,
+        tag: String,
+        timings: Vec<StatementTiming>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct QueryError {
+    pub message: String,
+    pub position: Option<usize>,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    // Five-character SQLSTATE reported by the server, when there is one.
+    pub code: Option<String>,
+}
+
+impl QueryError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            position: None,
+            detail: None,
+            hint: None,
+            code: None,
+        }
+    }
+}
+
+impl From<String> for QueryError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for QueryError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
 #[derive(Clone)]
 pub struct ConnectionError {
     pub user_message: String,
     pub detail: String,
+    // Refused connections and timeouts, which may succeed when retried.
+    pub transient: bool,
 }
 
 impl ConnectionError {
@@ -68,8 +322,56 @@ impl ConnectionError {
         Self {
             user_message: user_message.into(),
             detail: detail.into(),
+            transient: false,
         }
     }
+
+    pub fn transient(self) -> Self {
+        Self {
+            transient: true,
+            ..self
+        }
+    }
+
+    pub fn redact(self, secret: &str) -> Self {
+        Self {
+            user_message: redact_secret(&self.user_message, secret),
+            detail: redact_secret(&self.detail, secret),
+            ..self
+        }
+    }
+}
+
+// Row queries fetch `limit + 1` rows: the rows to show, and whether the extra
+// row proved there are more.
+pub(crate) fn truncate_rows<T>(rows: &[T], limit: usize) -> (&[T], bool) {
+    (&rows[..rows.len().min(limit)], rows.len() > limit)
+}
+
+// Secrets of one or two characters are only masked where they stand alone, so
+// a password like "a" does not turn every "a" in the message into asterisks.
+pub(crate) fn redact_secret(text: &str, secret: &str) -> String {
+    const MASK: &str = "********";
+    if secret.is_empty() {
+        return text.to_string();
+    }
+    if secret.chars().count() > 2 {
+        return text.replace(secret, MASK);
+    }
+    let is_word = |ch: Option<char>| ch.is_some_and(char::is_alphanumeric);
+    let mut redacted = String::with_capacity(text.len());
+    let mut copied = 0;
+    for (start, _) in text.match_indices(secret) {
+        let end = start + secret.len();
+        if is_word(text[..start].chars().next_back()) || is_word(text[end..].chars().next()) {
+            continue;
+        }
+        redacted.push_str(&text[copied..start]);
+        redacted.push_str(MASK);
+        copied = end;
+    }
+    redacted.push_str(&text[copied..]);
+    redacted
 }
 
 #[async_trait::async_trait]
@@ -78,17 +380,122 @@ pub trait DbAdapter: Send {
         &mut self,
     ) -> std::result::Result<Option<ConnectionClosedFuture>, ConnectionError>;
     async fn disconnect(&mut self);
-    async fn execute(&mut self, sql: String, limit: usize) -> Result<QueryResult>;
+    fn connect_timeout(&self) -> Option<Duration> {
+        None
+    }
+    fn query_canceller(&self) -> Option<QueryCanceller> {
+        None
+    }
+    fn take_notices(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+    fn keepalive_interval(&self) -> Option<Duration> {
+        None
+    }
+    fn connect_retries(&self) -> u32 {
+        0
+    }
+    fn retry_delay(&self, _attempt: u32) -> Duration {
+        Duration::ZERO
+    }
+    // An unconnected adapter for the same profile; the worker opens it to run
+    // metadata commands that arrive while a query is executing.
+    fn metadata_adapter(&self) -> Option<Box<dyn DbAdapter>> {
+        None
+    }
+    async fn ping(&mut self) -> Result<()> {
+        Ok(())
+    }
+    fn statement_timeout(&self) -> Option<Duration> {
+        None
+    }
+    async fn set_statement_timeout(&mut self, _timeout: Option<Duration>) -> Result<()> {
+        Ok(())
+    }
+    async fn execute(
+        &mut self,
+        sql: String,
+        limit: usize,
+        params: Vec<ParamValue>,
+    ) -> Result<ExecuteOutcome>;
+    async fn server_version(&mut self) -> Result<String> {
+        match self
+            .execute("SELECT version()".into(), 1, Vec::new())
+            .await?
+        {
+            ExecuteOutcome::Rows(result) => result
+                .rows
+                .first()
+                .and_then(|row| row.first())
+                .map(|cell| cell.as_str().to_string())
+                .ok_or_else(|| anyhow::anyhow!("version query returned no rows")),
+            ExecuteOutcome::Completed { .. } => {
+                Err(anyhow::anyhow!("version query returned no result set"))
+            }
+        }
+    }
+    async fn fetch_server_info(&mut self) -> Result<ServerInfo>;
     async fn fetch_schemas(&mut self) -> Result<Vec<String>>;
     async fn fetch_tables(&mut self, schema: String) -> Result<Vec<String>>;
+    async fn fetch_table_sizes(&mut self, _schema: String) -> Result<Vec<(String, u64)>> {
+        Ok(Vec::new())
+    }
+    async fn fetch_views(&mut self, _schema: String) -> Result<Vec<ViewMetadata>> {
+        Ok(Vec::new())
+    }
     async fn fetch_columns(&mut self, schema: String, table: String)
     -> Result<Vec<ColumnMetadata>>;
+    async fn fetch_indexes(
+        &mut self,
+        _schema: String,
+        _table: String,
+    ) -> Result<Vec<IndexMetadata>> {
+        Ok(Vec::new())
+    }
+    async fn fetch_foreign_keys(
+        &mut self,
+        _schema: String,
+        _table: String,
+    ) -> Result<Vec<ForeignKeyMetadata>> {
+        Ok(Vec::new())
+    }
     async fn preview_table(
         &mut self,
         schema: String,
         table: String,
         limit: usize,
     ) -> Result<QueryResult>;
+    async fn fetch_create_table(&mut self, schema: String, table: String) -> Result<String>;
+    async fn fetch_column_stats(
+        &mut self,
+        schema: String,
+        table: String,
+        column: String,
+        sample: bool,
+    ) -> Result<ColumnStats>;
+    // `None` values are inserted as NULL; the worker wraps the call in a transaction.
+    async fn insert_rows(
+        &mut self,
+        schema: String,
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+    ) -> Result<u64>;
+    async fn fetch_activity(&mut self) -> Result<Vec<SessionActivity>> {
+        Ok(Vec::new())
+    }
+    async fn terminate_backend(&mut self, _pid: i64) -> Result<bool> {
+        Err(anyhow::anyhow!("terminating sessions is not supported"))
+    }
+    // Called before `connect`; adapters that support LISTEN send
+    // `DbEvent::Notification`s for the connection they open through it.
+    fn forward_notifications(&mut self, _event_tx: Sender<DbEvent>) {}
+    async fn listen(&mut self, _channel: String) -> Result<()> {
+        Err(anyhow::anyhow!("LISTEN/NOTIFY is not supported"))
+    }
+    async fn unlisten(&mut self, _channel: String) -> Result<()> {
+        Err(anyhow::anyhow!("LISTEN/NOTIFY is not supported"))
+    }
 }
 
 pub struct DbSessionHandle {
@@ -104,11 +511,34 @@ impl DbSessionHandle {
         }
     }
 
-    pub fn execute(&self, sql: String) {
-        let _ = self.commands.send(DbCommand::Execute {
-            sql,
-            limit: ROW_LIMIT,
-        });
+    pub fn execute(&self, sql: String, limit: usize, params: Vec<ParamValue>) {
+        let _ = self
+            .commands
+            .send(DbCommand::Execute { sql, limit, params });
+    }
+
+    pub fn cancel_query(&self) {
+        let _ = self.commands.send(DbCommand::Cancel);
+    }
+
+    pub fn set_statement_timeout(&self, timeout: Option<Duration>) {
+        let _ = self.commands.send(DbCommand::SetStatementTimeout(timeout));
+    }
+
+    pub fn begin_transaction(&self) {
+        let _ = self.commands.send(DbCommand::Begin);
+    }
+
+    pub fn commit_transaction(&self) {
+        let _ = self.commands.send(DbCommand::Commit);
+    }
+
+    pub fn rollback_transaction(&self) {
+        let _ = self.commands.send(DbCommand::Rollback);
+    }
+
+    pub fn load_server_info(&self) {
+        let _ = self.commands.send(DbCommand::FetchServerInfo);
     }
 
     pub fn load_schemas(&self) {
@@ -119,12 +549,32 @@ impl DbSessionHandle {
         let _ = self.commands.send(DbCommand::FetchTables { schema });
     }
 
+    pub fn load_table_sizes(&self, schema: String) {
+        let _ = self.commands.send(DbCommand::FetchTableSizes { schema });
+    }
+
+    pub fn load_views(&self, schema: String) {
+        let _ = self.commands.send(DbCommand::FetchViews { schema });
+    }
+
     pub fn load_columns(&self, schema: String, table: String) {
         let _ = self
             .commands
             .send(DbCommand::FetchColumns { schema, table });
     }
 
+    pub fn load_indexes(&self, schema: String, table: String) {
+        let _ = self
+            .commands
+            .send(DbCommand::FetchIndexes { schema, table });
+    }
+
+    pub fn load_foreign_keys(&self, schema: String, table: String) {
+        let _ = self
+            .commands
+            .send(DbCommand::FetchForeignKeys { schema, table });
+    }
+
     pub fn preview_table(&self, schema: String, table: String, limit: usize) {
         let _ = self.commands.send(DbCommand::PreviewTable {
             schema,
@@ -133,6 +583,54 @@ impl DbSessionHandle {
         });
     }
 
+    pub fn load_create_table(&self, schema: String, table: String) {
+        let _ = self
+            .commands
+            .send(DbCommand::FetchCreateTable { schema, table });
+    }
+
+    pub fn load_column_stats(&self, schema: String, table: String, column: String, sample: bool) {
+        let _ = self.commands.send(DbCommand::FetchColumnStats {
+            schema,
+            table,
+            column,
+            sample,
+        });
+    }
+
+    pub fn bulk_insert(
+        &self,
+        schema: String,
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+        in_transaction: bool,
+    ) {
+        let _ = self.commands.send(DbCommand::BulkInsert {
+            schema,
+            table,
+            columns,
+            rows,
+            in_transaction,
+        });
+    }
+
+    pub fn load_activity(&self) {
+        let _ = self.commands.send(DbCommand::FetchActivity);
+    }
+
+    pub fn terminate_backend(&self, pid: i64) {
+        let _ = self.commands.send(DbCommand::TerminateBackend { pid });
+    }
+
+    pub fn listen(&self, channel: String) {
+        let _ = self.commands.send(DbCommand::Listen { channel });
+    }
+
+    pub fn unlisten(&self, channel: String) {
+        let _ = self.commands.send(DbCommand::Unlisten { channel });
+    }
+
     pub fn disconnect(&self) {
         let _ = self.commands.send(DbCommand::Disconnect);
     }
@@ -151,23 +649,227 @@ enum DbCommand {
     Execute {
         sql: String,
         limit: usize,
+        params: Vec<ParamValue>,
     },
+    FetchServerInfo,
     FetchSchemas,
     FetchTables {
         schema: String,
     },
+    FetchTableSizes {
+        schema: String,
+    },
+    FetchViews {
+        schema: String,
+    },
     FetchColumns {
         schema: String,
         table: String,
     },
+    FetchIndexes {
+        schema: String,
+        table: String,
+    },
+    FetchForeignKeys {
+        schema: String,
+        table: String,
+    },
     PreviewTable {
         schema: String,
         table: String,
         limit: usize,
     },
+    FetchCreateTable {
+        schema: String,
+        table: String,
+    },
+    FetchColumnStats {
+        schema: String,
+        table: String,
+        column: String,
+        sample: bool,
+    },
+    BulkInsert {
+        schema: String,
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+        in_transaction: bool,
+    },
+    FetchActivity,
+    TerminateBackend {
+        pid: i64,
+    },
+    Listen {
+        channel: String,
+    },
+    Unlisten {
+        channel: String,
+    },
+    Cancel,
+    SetStatementTimeout(Option<Duration>),
+    Begin,
+    Commit,
+    Rollback,
     Disconnect,
 }
 
+impl DbCommand {
+    fn is_metadata(&self) -> bool {
+        matches!(
+            self,
+            DbCommand::FetchServerInfo
+                | DbCommand::FetchSchemas
+                | DbCommand::FetchTables { .. }
+                | DbCommand::FetchTableSizes { .. }
+                | DbCommand::FetchViews { .. }
+                | DbCommand::FetchColumns { .. }
+                | DbCommand::FetchIndexes { .. }
+                | DbCommand::FetchForeignKeys { .. }
+                | DbCommand::PreviewTable { .. }
+                | DbCommand::FetchCreateTable { .. }
+                | DbCommand::FetchColumnStats { .. }
+        )
+    }
+}
+
+// Second connection of a session, opened on first use while a query executes.
+struct MetadataConnection {
+    adapter: Box<dyn DbAdapter>,
+    // Set once the connection's driver future finishes; None while disconnected.
+    closed: Option<Arc<AtomicBool>>,
+    // The session's `SetStatementTimeout` override, applied on every (re)connect.
+    statement_timeout: Option<Option<Duration>>,
+}
+
+impl MetadataConnection {
+    fn is_connected(&self) -> bool {
+        self.closed
+            .as_ref()
+            .is_some_and(|closed| !closed.load(Ordering::SeqCst))
+    }
+
+    async fn connect(&mut self) -> bool {
+        if self.is_connected() {
+            return true;
+        }
+        self.disconnect().await;
+        match connect_adapter(self.adapter.as_mut()).await {
+            Ok(connection_future) => {
+                let closed = Arc::new(AtomicBool::new(false));
+                if let Some(future) = connection_future {
+                    let closed = closed.clone();
+                    tokio::spawn(async move {
+                        future.await;
+                        closed.store(true, Ordering::SeqCst);
+                    });
+                }
+                self.closed = Some(closed);
+                if let Some(timeout) = self.statement_timeout {
+                    self.set_statement_timeout(timeout).await;
+                }
+                true
+            }
+            Err(err) => {
+                tracing::warn!("Failed to open metadata connection: {}", err.detail);
+                false
+            }
+        }
+    }
+
+    async fn set_statement_timeout(&mut self, timeout: Option<Duration>) {
+        let limit = self.adapter.connect_timeout();
+        match within(limit, self.adapter.set_statement_timeout(timeout)).await {
+            Some(Ok(())) => {}
+            Some(Err(err)) => {
+                tracing::warn!("Failed to set statement_timeout on metadata connection: {err}");
+            }
+            None => self.disconnect().await,
+        }
+    }
+
+    async fn disconnect(&mut self) {
+        if self.closed.take().is_some() {
+            let limit = self.adapter.connect_timeout();
+            within(limit, self.adapter.disconnect()).await;
+        }
+    }
+}
+
+// Command side of the task that owns the metadata connection. Dropping `commands`
+// stops the task; commands it cannot run come back on `returned`.
+struct MetadataWorker {
+    commands: Option<UnboundedSender<DbCommand>>,
+    returned: Option<UnboundedReceiver<DbCommand>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl MetadataWorker {
+    fn spawn(adapter: Option<Box<dyn DbAdapter>>, event_tx: Sender<DbEvent>) -> Self {
+        let Some(adapter) = adapter else {
+            return Self {
+                commands: None,
+                returned: None,
+                task: None,
+            };
+        };
+        let (command_tx, command_rx) = unbounded_channel();
+        let (returned_tx, returned_rx) = unbounded_channel();
+        let connection = MetadataConnection {
+            adapter,
+            closed: None,
+            statement_timeout: None,
+        };
+        let task = tokio::spawn(run_metadata_connection(
+            connection,
+            command_rx,
+            returned_tx,
+            event_tx,
+        ));
+        Self {
+            commands: Some(command_tx),
+            returned: Some(returned_rx),
+            task: Some(task),
+        }
+    }
+
+    async fn close(mut self) {
+        self.commands.take();
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
+
+// Resolves with the next command the metadata task handed back, or never once
+// the task has stopped.
+async fn next_returned(returned: &mut Option<UnboundedReceiver<DbCommand>>) -> DbCommand {
+    if let Some(receiver) = returned {
+        if let Some(command) = receiver.recv().await {
+            return command;
+        }
+        *returned = None;
+    }
+    std::future::pending().await
+}
+
+async fn keepalive_tick(keepalive: Option<Duration>) -> Duration {
+    match keepalive {
+        Some(interval) => {
+            tokio::time::sleep(interval).await;
+            interval
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn within<F: Future>(limit: Option<Duration>, future: F) -> Option<F::Output> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
 pub fn spawn_session<A>(adapter: A, event_tx: Sender<DbEvent>)
 where
     A: DbAdapter + 'static,
@@ -195,6 +897,83 @@ where
     });
 }
 
+pub fn spawn_profile_session(
+    profile: ConnectionProfile,
+    password: String,
+    event_tx: Sender<DbEvent>,
+) {
+    match profile.kind {
+        ProfileKind::Postgres => spawn_session(PostgresAdapter::new(profile, password), event_tx),
+        ProfileKind::MySql => spawn_session(MySqlAdapter::new(profile, password), event_tx),
+    }
+}
+
+pub fn test_profile_connection(
+    profile: ConnectionProfile,
+    password: String,
+    event_tx: Sender<DbEvent>,
+) {
+    let adapter: Box<dyn DbAdapter> = match profile.kind {
+        ProfileKind::Postgres => Box::new(PostgresAdapter::new(profile, password)),
+        ProfileKind::MySql => Box::new(MySqlAdapter::new(profile, password)),
+    };
+    thread::spawn(move || {
+        let outcome = run_connection_test(adapter).unwrap_or_else(|err| {
+            Err(ConnectionError::new(
+                "Failed to start the connection test.",
+                err.to_string(),
+            ))
+        });
+        let _ = event_tx.send_blocking(DbEvent::ConnectionTested(outcome));
+    });
+}
+
+fn run_connection_test(
+    mut adapter: Box<dyn DbAdapter>,
+) -> Result<std::result::Result<String, ConnectionError>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    Ok(runtime.block_on(async move {
+        if let Some(connection_future) = connect_adapter(adapter.as_mut()).await? {
+            tokio::spawn(connection_future);
+        }
+        let outcome = adapter.server_version().await;
+        adapter.disconnect().await;
+        outcome.map_err(|err| {
+            ConnectionError::new("Connected, but the test query failed.", err.to_string())
+        })
+    }))
+}
+
+async fn connect_adapter(
+    adapter: &mut dyn DbAdapter,
+) -> std::result::Result<Option<ConnectionClosedFuture>, ConnectionError> {
+    let connection_future = match adapter.connect_timeout() {
+        Some(limit) => tokio::time::timeout(limit, adapter.connect())
+            .await
+            .unwrap_or_else(|_| {
+                Err(ConnectionError::new(
+                    "Connection timed out.",
+                    format!("no response within {} s", limit.as_secs()),
+                )
+                .transient())
+            })?,
+        None => adapter.connect().await?,
+    };
+    if let Some(timeout) = adapter.statement_timeout()
+        && let Err(err) = adapter.set_statement_timeout(Some(timeout)).await
+    {
+        adapter.disconnect().await;
+        return Err(ConnectionError::new(
+            "Failed to set statement_timeout.",
+            err.to_string(),
+        ));
+    }
+    Ok(connection_future)
+}
+
 fn run_worker(
     mut adapter: Box<dyn DbAdapter>,
     ready_tx: BlockingSender<UnboundedSender<DbCommand>>,
@@ -207,11 +986,31 @@ fn run_worker(
     runtime.block_on(async move {
         let (command_tx, mut command_rx) = unbounded_channel::<DbCommand>();
 
-        let connection_future = match adapter.connect().await {
-            Ok(connection_future) => connection_future,
-            Err(error) => {
-                let _ = event_tx.send(DbEvent::ConnectionFailed(error)).await;
-                return Ok::<(), Error>(());
+        adapter.forward_notifications(event_tx.clone());
+        let retries = adapter.connect_retries();
+        let mut attempt = 0;
+        let connection_future = loop {
+            match connect_adapter(adapter.as_mut()).await {
+                Ok(connection_future) => break connection_future,
+                Err(error) if error.transient && attempt < retries => {
+                    attempt += 1;
+                    tracing::info!(
+                        "Connect failed ({}), retry {attempt}/{retries}",
+                        error.detail
+                    );
+                    let _ = event_tx
+                        .send(DbEvent::ConnectRetrying {
+                            attempt,
+                            retries,
+                            error,
+                        })
+                        .await;
+                    tokio::time::sleep(adapter.retry_delay(attempt)).await;
+                }
+                Err(error) => {
+                    let _ = event_tx.send(DbEvent::ConnectionFailed(error)).await;
+                    return Ok::<(), Error>(());
+                }
             }
         };
 
@@ -224,9 +1023,19 @@ fn run_worker(
             spawn_connection_monitor(fut, event_tx.clone());
         }
 
-        process_commands(adapter.as_mut(), &mut command_rx, event_tx.clone()).await;
+        let canceller = adapter.query_canceller();
+        let mut metadata = MetadataWorker::spawn(adapter.metadata_adapter(), event_tx.clone());
+        process_commands(
+            adapter.as_mut(),
+            canceller,
+            &mut metadata,
+            &mut command_rx,
+            event_tx.clone(),
+        )
+        .await;
 
         adapter.disconnect().await;
+        metadata.close().await;
         Ok(())
     })?;
 
@@ -242,94 +1051,792 @@ fn spawn_connection_monitor(future: ConnectionClosedFuture, event_tx: Sender<DbE
 
 async fn process_commands(
     adapter: &mut dyn DbAdapter,
+    canceller: Option<QueryCanceller>,
+    metadata: &mut MetadataWorker,
     command_rx: &mut UnboundedReceiver<DbCommand>,
     event_tx: Sender<DbEvent>,
 ) {
-    while let Some(command) = command_rx.recv().await {
+    let mut pending = VecDeque::new();
+    let keepalive = adapter.keepalive_interval();
+    loop {
+        let command = match pending.pop_front() {
+            Some(command) => Some(command),
+            None => tokio::select! {
+                command = command_rx.recv() => command,
+                command = next_returned(&mut metadata.returned) => {
+                    metadata.commands = None;
+                    Some(command)
+                }
+                interval = keepalive_tick(keepalive) => {
+                    let limit = adapter.connect_timeout().unwrap_or(interval);
+                    let failure = match tokio::time::timeout(limit, adapter.ping()).await {
+                        Ok(Ok(())) => continue,
+                        Ok(Err(err)) => err.to_string(),
+                        Err(_) => format!("no response within {} s", limit.as_secs()),
+                    };
+                    let _ = event_tx
+                        .send(DbEvent::ConnectionClosed(Some(format!(
+                            "Keep-alive failed: {failure}"
+                        ))))
+                        .await;
+                    break;
+                }
+            },
+        };
+        let Some(command) = command else {
+            break;
+        };
         match command {
-            DbCommand::Execute { sql, limit } => match adapter.execute(sql, limit).await {
-                Ok(result) => {
-                    let _ = event_tx.send(DbEvent::QueryFinished(result)).await;
+            DbCommand::Execute { sql, limit, params } => {
+                adapter.take_notices();
+                let mut cancelled = false;
+                let mut commands_closed = false;
+                let outcome = {
+                    let execution = adapter.execute(sql, limit, params);
+                    tokio::pin!(execution);
+                    loop {
+                        tokio::select! {
+                            outcome = &mut execution => break outcome,
+                            command = next_returned(&mut metadata.returned) => {
+                                metadata.commands = None;
+                                pending.push_back(command);
+                            }
+                            command = command_rx.recv(), if !commands_closed => match command {
+                                Some(DbCommand::Cancel) => {
+                                    if let Some(cancel) = canceller.as_ref() {
+                                        cancelled = true;
+                                        let cancel = cancel();
+                                        tokio::spawn(async move {
+                                            if let Err(err) = cancel.await {
+                                                tracing::warn!("Failed to cancel query: {err}");
+                                            }
+                                        });
+                                    }
+                                }
+                                // Metadata commands run concurrently on the metadata
+                                // connection instead of waiting for the query.
+                                Some(command) if command.is_metadata() => {
+                                    let rejected = match &metadata.commands {
+                                        Some(commands) => commands.send(command).err().map(|err| err.0),
+                                        None => Some(command),
+                                    };
+                                    pending.extend(rejected);
+                                }
+                                Some(command) => pending.push_back(command),
+                                None => commands_closed = true,
+                            },
+                        }
+                    }
+                };
+                for notice in adapter.take_notices() {
+                    let _ = event_tx.send(DbEvent::Notice(notice)).await;
+                }
+                match outcome {
+                    Ok(ExecuteOutcome::Rows(result)) => {
+                        let _ = event_tx.send(DbEvent::QueryFinished(result)).await;
+                    }
+                    Ok(ExecuteOutcome::Completed {
+                        affected,
+                        tag,
+                        timings,
+                    }) => {
+                        let _ = event_tx
+                            .send(DbEvent::StatementCompleted {
+                                affected,
+                                tag,
+                                timings,
+                            })
+                            .await;
+                    }
+                    Err(err) if cancelled && is_cancellation(&err) => {
+                        let _ = event_tx.send(DbEvent::QueryCancelled).await;
+                    }
+                    Err(err) => {
+                        let error = err
+                            .downcast::<QueryError>()
+                            .unwrap_or_else(|err| QueryError::new(err.to_string()));
+                        let _ = event_tx.send(DbEvent::QueryFailed(error)).await;
+                    }
+                }
+            }
+            DbCommand::Cancel => {}
+            DbCommand::SetStatementTimeout(timeout) => {
+                match adapter.set_statement_timeout(timeout).await {
+                    Ok(()) => {
+                        if let Some(commands) = &metadata.commands {
+                            let _ = commands.send(DbCommand::SetStatementTimeout(timeout));
+                        }
+                        let _ = event_tx.send(DbEvent::StatementTimeoutSet(timeout)).await;
+                    }
+                    Err(err) => {
+                        let _ = event_tx
+                            .send(DbEvent::Notice(format!(
+                                "Failed to set statement_timeout: {err}"
+                            )))
+                            .await;
+                    }
+                }
+            }
+            DbCommand::Begin => match adapter.execute("BEGIN".into(), 0, Vec::new()).await {
+                Ok(_) => {
+                    let _ = event_tx.send(DbEvent::TransactionStarted).await;
                 }
                 Err(err) => {
-                    let _ = event_tx.send(DbEvent::QueryFailed(err.to_string())).await;
+                    let _ = event_tx
+                        .send(DbEvent::QueryFailed(QueryError::new(format!(
+                            "Failed to begin transaction: {err}"
+                        ))))
+                        .await;
                 }
             },
-            DbCommand::FetchSchemas => match adapter.fetch_schemas().await {
-                Ok(schemas) => {
-                    let _ = event_tx.send(DbEvent::SchemasLoaded(schemas)).await;
+            DbCommand::Commit | DbCommand::Rollback => {
+                let committed = matches!(command, DbCommand::Commit);
+                let sql = if committed { "COMMIT" } else { "ROLLBACK" };
+                match adapter.execute(sql.into(), 0, Vec::new()).await {
+                    Ok(_) => {
+                        let _ = event_tx.send(DbEvent::TransactionEnded { committed }).await;
+                    }
+                    Err(err) => {
+                        let _ = event_tx
+                            .send(DbEvent::QueryFailed(QueryError::new(format!(
+                                "Failed to end transaction: {err}"
+                            ))))
+                            .await;
+                    }
+                }
+            }
+            DbCommand::BulkInsert {
+                schema,
+                table,
+                columns,
+                rows,
+                in_transaction,
+            } => {
+                let event = match run_bulk_insert(
+                    adapter,
+                    schema.clone(),
+                    table.clone(),
+                    columns,
+                    rows,
+                    in_transaction,
+                )
+                .await
+                {
+                    Ok(rows) => DbEvent::BulkInserted {
+                        schema,
+                        table,
+                        rows,
+                    },
+                    Err(message) => DbEvent::BulkInsertFailed {
+                        schema,
+                        table,
+                        message,
+                    },
+                };
+                let _ = event_tx.send(event).await;
+            }
+            DbCommand::Listen { channel } => {
+                let event = match adapter.listen(channel.clone()).await {
+                    Ok(()) => DbEvent::Listening { channel },
+                    Err(err) => DbEvent::ListenFailed {
+                        channel,
+                        message: err.to_string(),
+                    },
+                };
+                let _ = event_tx.send(event).await;
+            }
+            DbCommand::Unlisten { channel } => {
+                let event = match adapter.unlisten(channel.clone()).await {
+                    Ok(()) => DbEvent::Unlistened { channel },
+                    Err(err) => DbEvent::ListenFailed {
+                        channel,
+                        message: err.to_string(),
+                    },
+                };
+                let _ = event_tx.send(event).await;
+            }
+            DbCommand::Disconnect => {
+                adapter.disconnect().await;
+                break;
+            }
+            command => run_metadata_command(adapter, command, &event_tx).await,
+        }
+    }
+}
+
+// After a cancel request the query may still fail for another reason (or finish
+// its statement first); only the server's own cancellation error counts.
+fn is_cancellation(err: &Error) -> bool {
+    postgres::is_query_canceled(err) || mysql::is_query_interrupted(err)
+}
+
+// Inserts all rows or none: in a transaction of its own, or under a savepoint
+// when the session already has one open so that transaction survives a failure.
+async fn run_bulk_insert(
+    adapter: &mut dyn DbAdapter,
+    schema: String,
+    table: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<Option<String>>>,
+    in_transaction: bool,
+) -> std::result::Result<u64, String> {
+    let (begin, commit, rollback) = if in_transaction {
+        (
+            "SAVEPOINT dbmiru_bulk_insert",
+            "RELEASE SAVEPOINT dbmiru_bulk_insert",
+            "ROLLBACK TO SAVEPOINT dbmiru_bulk_insert",
+        )
+    } else {
+        ("BEGIN", "COMMIT", "ROLLBACK")
+    };
+    if let Err(err) = adapter.execute(begin.into(), 0, Vec::new()).await {
+        return Err(format!("Failed to start the insert: {err}"));
+    }
+    let failure = match adapter.insert_rows(schema, table, columns, rows).await {
+        Ok(inserted) => match adapter.execute(commit.into(), 0, Vec::new()).await {
+            Ok(_) => return Ok(inserted),
+            Err(err) => format!("Failed to commit the insert: {err}"),
+        },
+        Err(err) => err.to_string(),
+    };
+    match adapter.execute(rollback.into(), 0, Vec::new()).await {
+        Ok(_) => Err(format!("{failure} No rows were inserted.")),
+        Err(err) => Err(format!("{failure} Rolling back also failed: {err}")),
+    }
+}
+
+// Runs metadata commands on the metadata connection until the session drops the
+// sender. A command that outlasts the connect timeout fails and drops the
+// connection; once it cannot be opened, every command is handed back instead.
+async fn run_metadata_connection(
+    mut connection: MetadataConnection,
+    mut commands: UnboundedReceiver<DbCommand>,
+    returned: UnboundedSender<DbCommand>,
+    event_tx: Sender<DbEvent>,
+) {
+    let mut available = true;
+    while let Some(command) = commands.recv().await {
+        if let DbCommand::SetStatementTimeout(timeout) = command {
+            connection.statement_timeout = Some(timeout);
+            if connection.is_connected() {
+                connection.set_statement_timeout(timeout).await;
+            }
+            continue;
+        }
+        if !available || !connection.connect().await {
+            available = false;
+            let _ = returned.send(command);
+            continue;
+        }
+        let limit = connection.adapter.connect_timeout();
+        let run = run_metadata_command(connection.adapter.as_mut(), command, &event_tx);
+        if within(limit, run).await.is_none() {
+            let secs = limit.map_or(0, |limit| limit.as_secs());
+            let _ = event_tx
+                .send(DbEvent::MetadataFailed(format!(
+                    "Metadata request timed out: no response within {secs} s"
+                )))
+                .await;
+            connection.disconnect().await;
+        }
+    }
+    connection.disconnect().await;
+}
+
+// Metadata and preview commands, run on the session connection or, while a query
+// executes, on the metadata connection.
+async fn run_metadata_command(
+    adapter: &mut dyn DbAdapter,
+    command: DbCommand,
+    event_tx: &Sender<DbEvent>,
+) {
+    match command {
+        DbCommand::FetchServerInfo => match adapter.fetch_server_info().await {
+            Ok(info) => {
+                let _ = event_tx
+                    .send(DbEvent::ServerInfoLoaded {
+                        version: info.version,
+                        database: info.database,
+                        user: info.user,
+                    })
+                    .await;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to load server info: {err}");
+            }
+        },
+        DbCommand::FetchSchemas => match adapter.fetch_schemas().await {
+            Ok(schemas) => {
+                let _ = event_tx.send(DbEvent::SchemasLoaded(schemas)).await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to load schemas: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::FetchTables { schema } => match adapter.fetch_tables(schema.clone()).await {
+            Ok(tables) => {
+                let _ = event_tx
+                    .send(DbEvent::TablesLoaded { schema, tables })
+                    .await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to load tables: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::FetchTableSizes { schema } => {
+            match adapter.fetch_table_sizes(schema.clone()).await {
+                Ok(sizes) => {
+                    let _ = event_tx
+                        .send(DbEvent::TableSizesLoaded { schema, sizes })
+                        .await;
                 }
                 Err(err) => {
                     let _ = event_tx
                         .send(DbEvent::MetadataFailed(format!(
-                            "Failed to load schemas: {err}"
+                            "Failed to load table sizes: {err}"
                         )))
                         .await;
                 }
-            },
-            DbCommand::FetchTables { schema } => match adapter.fetch_tables(schema.clone()).await {
-                Ok(tables) => {
+            }
+        }
+        DbCommand::FetchViews { schema } => match adapter.fetch_views(schema.clone()).await {
+            Ok(views) => {
+                let _ = event_tx.send(DbEvent::ViewsLoaded { schema, views }).await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to load views: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::FetchColumns { schema, table } => {
+            match adapter.fetch_columns(schema.clone(), table.clone()).await {
+                Ok(columns) => {
                     let _ = event_tx
-                        .send(DbEvent::TablesLoaded { schema, tables })
+                        .send(DbEvent::ColumnsLoaded {
+                            schema,
+                            table,
+                            columns,
+                        })
                         .await;
                 }
                 Err(err) => {
                     let _ = event_tx
                         .send(DbEvent::MetadataFailed(format!(
-                            "Failed to load tables: {err}"
+                            "Failed to load columns: {err}"
                         )))
                         .await;
                 }
-            },
-            DbCommand::FetchColumns { schema, table } => {
-                match adapter.fetch_columns(schema.clone(), table.clone()).await {
-                    Ok(columns) => {
-                        let _ = event_tx
-                            .send(DbEvent::ColumnsLoaded {
-                                schema,
-                                table,
-                                columns,
-                            })
-                            .await;
-                    }
-                    Err(err) => {
-                        let _ = event_tx
-                            .send(DbEvent::MetadataFailed(format!(
-                                "Failed to load columns: {err}"
-                            )))
-                            .await;
-                    }
+            }
+        }
+        DbCommand::FetchIndexes { schema, table } => {
+            match adapter.fetch_indexes(schema.clone(), table.clone()).await {
+                Ok(indexes) => {
+                    let _ = event_tx
+                        .send(DbEvent::IndexesLoaded {
+                            schema,
+                            table,
+                            indexes,
+                        })
+                        .await;
+                }
+                Err(err) => {
+                    let _ = event_tx
+                        .send(DbEvent::MetadataFailed(format!(
+                            "Failed to load indexes: {err}"
+                        )))
+                        .await;
                 }
             }
-            DbCommand::PreviewTable {
-                schema,
-                table,
-                limit,
-            } => match adapter
-                .preview_table(schema.clone(), table.clone(), limit)
+        }
+        DbCommand::FetchForeignKeys { schema, table } => {
+            match adapter
+                .fetch_foreign_keys(schema.clone(), table.clone())
                 .await
             {
-                Ok(result) => {
+                Ok(foreign_keys) => {
                     let _ = event_tx
-                        .send(DbEvent::TablePreviewReady {
+                        .send(DbEvent::ForeignKeysLoaded {
                             schema,
                             table,
-                            result,
+                            foreign_keys,
                         })
                         .await;
                 }
                 Err(err) => {
                     let _ = event_tx
                         .send(DbEvent::MetadataFailed(format!(
-                            "Failed to preview table: {err}"
+                            "Failed to load foreign keys: {err}"
                         )))
                         .await;
                 }
-            },
-            DbCommand::Disconnect => {
-                adapter.disconnect().await;
-                break;
             }
         }
+        DbCommand::PreviewTable {
+            schema,
+            table,
+            limit,
+        } => match adapter
+            .preview_table(schema.clone(), table.clone(), limit)
+            .await
+        {
+            Ok(result) => {
+                let _ = event_tx
+                    .send(DbEvent::TablePreviewReady {
+                        schema,
+                        table,
+                        result,
+                    })
+                    .await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to preview table: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::FetchCreateTable { schema, table } => {
+            match adapter
+                .fetch_create_table(schema.clone(), table.clone())
+                .await
+            {
+                Ok(ddl) => {
+                    let _ = event_tx
+                        .send(DbEvent::DdlReady { schema, table, ddl })
+                        .await;
+                }
+                Err(err) => {
+                    let _ = event_tx
+                        .send(DbEvent::MetadataFailed(format!(
+                            "Failed to load DDL: {err}"
+                        )))
+                        .await;
+                }
+            }
+        }
+        DbCommand::FetchColumnStats {
+            schema,
+            table,
+            column,
+            sample,
+        } => match adapter
+            .fetch_column_stats(schema.clone(), table.clone(), column.clone(), sample)
+            .await
+        {
+            Ok(stats) => {
+                let _ = event_tx
+                    .send(DbEvent::ColumnStatsReady {
+                        schema,
+                        table,
+                        column,
+                        stats,
+                    })
+                    .await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to load column statistics: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::FetchActivity => match adapter.fetch_activity().await {
+            Ok(sessions) => {
+                let _ = event_tx.send(DbEvent::ActivityLoaded(sessions)).await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to load sessions: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::TerminateBackend { pid } => match adapter.terminate_backend(pid).await {
+            Ok(terminated) => {
+                let _ = event_tx
+                    .send(DbEvent::BackendTerminated { pid, terminated })
+                    .await;
+            }
+            Err(err) => {
+                let _ = event_tx
+                    .send(DbEvent::MetadataFailed(format!(
+                        "Failed to terminate session {pid}: {err}"
+                    )))
+                    .await;
+            }
+        },
+        DbCommand::Execute { .. }
+        | DbCommand::BulkInsert { .. }
+        | DbCommand::Listen { .. }
+        | DbCommand::Unlisten { .. }
+        | DbCommand::Cancel
+        | DbCommand::SetStatementTimeout(_)
+        | DbCommand::Begin
+        | DbCommand::Commit
+        | DbCommand::Rollback
+        | DbCommand::Disconnect => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Metadata adapter double: records its calls, hangs `fetch_schemas` while
+    // `hang` is set, and lets the test drop the connection through `close`.
+    #[derive(Clone, Default)]
+    struct FakeAdapter {
+        log: Arc<std::sync::Mutex<Vec<String>>>,
+        hang: Arc<AtomicBool>,
+        close: Arc<std::sync::Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
+    }
+
+    impl FakeAdapter {
+        fn record(&self, call: impl Into<String>) {
+            self.log.lock().unwrap().push(call.into());
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DbAdapter for FakeAdapter {
+        async fn connect(
+            &mut self,
+        ) -> std::result::Result<Option<ConnectionClosedFuture>, ConnectionError> {
+            self.record("connect");
+            let (close_tx, close_rx) = tokio::sync::oneshot::channel();
+            *self.close.lock().unwrap() = Some(close_tx);
+            Ok(Some(Box::pin(async move {
+                let _ = close_rx.await;
+                None
+            })))
+        }
+        async fn disconnect(&mut self) {
+            self.record("disconnect");
+        }
+        fn connect_timeout(&self) -> Option<Duration> {
+            Some(Duration::from_millis(50))
+        }
+        async fn set_statement_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+            self.record(format!("timeout {timeout:?}"));
+            Ok(())
+        }
+        async fn execute(
+            &mut self,
+            _: String,
+            _: usize,
+            _: Vec<ParamValue>,
+        ) -> Result<ExecuteOutcome> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn fetch_server_info(&mut self) -> Result<ServerInfo> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn fetch_schemas(&mut self) -> Result<Vec<String>> {
+            self.record("schemas");
+            if self.hang.load(Ordering::SeqCst) {
+                std::future::pending::<()>().await;
+            }
+            Ok(Vec::new())
+        }
+        async fn fetch_tables(&mut self, _: String) -> Result<Vec<String>> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn fetch_columns(&mut self, _: String, _: String) -> Result<Vec<ColumnMetadata>> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn preview_table(&mut self, _: String, _: String, _: usize) -> Result<QueryResult> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn fetch_create_table(&mut self, _: String, _: String) -> Result<String> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn fetch_column_stats(
+            &mut self,
+            _: String,
+            _: String,
+            _: String,
+            _: bool,
+        ) -> Result<ColumnStats> {
+            Err(anyhow::anyhow!("unused"))
+        }
+        async fn insert_rows(
+            &mut self,
+            _: String,
+            _: String,
+            _: Vec<String>,
+            _: Vec<Vec<Option<String>>>,
+        ) -> Result<u64> {
+            Err(anyhow::anyhow!("unused"))
+        }
+    }
+
+    #[test]
+    fn metadata_connection_recovers_from_hung_and_dropped_connections() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let adapter = FakeAdapter::default();
+        runtime.block_on(async {
+            let (event_tx, event_rx) = async_channel::unbounded();
+            let worker = MetadataWorker::spawn(Some(Box::new(adapter.clone())), event_tx);
+            let commands = worker.commands.clone().unwrap();
+            let timeout = Some(Duration::from_secs(5));
+            commands
+                .send(DbCommand::SetStatementTimeout(timeout))
+                .unwrap();
+
+            adapter.hang.store(true, Ordering::SeqCst);
+            commands.send(DbCommand::FetchSchemas).unwrap();
+            let Ok(DbEvent::MetadataFailed(message)) = event_rx.recv().await else {
+                panic!("expected the hung request to fail");
+            };
+            assert!(message.contains("timed out"), "{message}");
+
+            adapter.hang.store(false, Ordering::SeqCst);
+            commands.send(DbCommand::FetchSchemas).unwrap();
+            assert!(matches!(
+                event_rx.recv().await,
+                Ok(DbEvent::SchemasLoaded(_))
+            ));
+
+            // The server drops the connection; the next command opens a new one.
+            adapter.close.lock().unwrap().take();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            commands.send(DbCommand::FetchSchemas).unwrap();
+            assert!(matches!(
+                event_rx.recv().await,
+                Ok(DbEvent::SchemasLoaded(_))
+            ));
+
+            drop(commands);
+            worker.close().await;
+        });
+        let applied = "timeout Some(5s)";
+        assert_eq!(
+            *adapter.log.lock().unwrap(),
+            [
+                "connect",
+                applied,
+                "schemas",
+                "disconnect",
+                "connect",
+                applied,
+                "schemas",
+                "disconnect",
+                "connect",
+                applied,
+                "schemas",
+                "disconnect",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_server_cancellations_count_as_cancelled() {
+        let postgres = |code: &str| -> Error {
+            QueryError {
+                code: Some(code.into()),
+                ..QueryError::new("ERROR: canceling statement due to user request")
+            }
+            .into()
+        };
+        assert!(is_cancellation(&postgres("57014")));
+        assert!(!is_cancellation(&postgres("23505")));
+        assert!(!is_cancellation(&anyhow::anyhow!("connection reset")));
+
+        let mysql = |code: u16| -> Error {
+            let server = mysql_async::Error::Server(mysql_async::ServerError {
+                code,
+                message: "Query execution was interrupted".into(),
+                state: "70100".into(),
+            });
+            Error::new(server).context(QueryError::new("Statement 2 failed: interrupted"))
+        };
+        assert!(is_cancellation(&mysql(1317)));
+        assert!(!is_cancellation(&mysql(1213)));
+        // The worker still sees the QueryError the adapter built.
+        let error = mysql(1213).downcast::<QueryError>().ok().unwrap();
+        assert_eq!(error.message, "Statement 2 failed: interrupted");
+    }
+
+    #[test]
+    fn activity_commands_stay_on_the_query_connection() {
+        assert!(DbCommand::FetchSchemas.is_metadata());
+        assert!(!DbCommand::FetchActivity.is_metadata());
+        assert!(!DbCommand::TerminateBackend { pid: 1 }.is_metadata());
+    }
+
+    #[test]
+    fn truncate_rows_uses_the_extra_row_as_the_signal() {
+        let rows: Vec<usize> = (0..51).collect();
+        let (shown, truncated) = truncate_rows(&rows[..49], 50);
+        assert_eq!((shown.len(), truncated), (49, false));
+        let (shown, truncated) = truncate_rows(&rows[..50], 50);
+        assert_eq!((shown.len(), truncated), (50, false));
+        let (shown, truncated) = truncate_rows(&rows, 50);
+        assert_eq!((shown.len(), truncated), (50, true));
+        assert_eq!(shown.last(), Some(&49));
+        assert_eq!(truncate_rows::<usize>(&[], 50), (&[][..], false));
+    }
+
+    #[test]
+    fn redact_secret_masks_every_occurrence() {
+        assert_eq!(
+            redact_secret("user=app password=s3cret; retry with s3cret", "s3cret"),
+            "user=app password=********; retry with ********"
+        );
+    }
+
+    #[test]
+    fn redact_secret_with_empty_secret_keeps_text() {
+        assert_eq!(
+            redact_secret("connection refused", ""),
+            "connection refused"
+        );
+    }
+
+    #[test]
+    fn redact_secret_short_secret_only_masks_standalone_occurrences() {
+        assert_eq!(
+            redact_secret("password authentication failed for user \"a\"", "a"),
+            "password authentication failed for user \"********\""
+        );
+        assert_eq!(
+            redact_secret("database \"ab\" (password=ab) is abandoned", "ab"),
+            "database \"********\" (password=********) is abandoned"
+        );
+        assert_eq!(redact_secret("no such host", "o"), "no such host");
+    }
+
+    #[test]
+    fn wrong_password_detail_does_not_contain_password() {
+        let password = "hunter2!";
+        let error = ConnectionError::new(
+            format!("Authentication failed for postgres://app:{password}@db/app"),
+            format!(
+                "error connecting to postgres://app:{password}@db/app: password authentication failed for user \"app\""
+            ),
+        )
+        .transient()
+        .redact(password);
+        assert!(!error.user_message.contains(password));
+        assert!(!error.detail.contains(password));
+        assert!(error.detail.contains("app:********@db"));
+        assert!(error.transient);
     }
 }
@@ -0,0 +1,848 @@
+use std::{
+    net::Ipv4Addr,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use dbmiru_core::profiles::{ConnectionProfile, ProfileKind, SslMode};
+use mysql_async::{
+    Column, Conn, Opts, OptsBuilder, Params, QueryResult as MySqlQueryResult, Row, SslOpts, Value,
+    consts::{ColumnFlags, ColumnType},
+    prelude::{Protocol, Queryable},
+};
+
+use crate::{
+    Cell, ColumnMetadata, ColumnStats, ConnectionClosedFuture, ConnectionError, DbAdapter,
+    ExecuteOutcome, ForeignKeyMetadata, IndexMetadata, ParamValue, QueryCanceller, QueryError,
+    QueryResult, ROW_LIMIT, Result, STATS_SAMPLE_ROWS, ServerInfo, SessionActivity,
+    StatementTiming, ViewMetadata, redact_secret,
+    sql::{
+        cap_row_query, insert_batch_rows, insert_rows_statement, qualified_table_name,
+        quote_identifier, split_statements, statement_tag,
+    },
+    ssh::SshForward,
+    truncate_rows,
+};
+
+const BINARY_CHARSET: u16 = 63;
+const ER_QUERY_INTERRUPTED: u16 = 1317;
+const ER_QUERY_TIMEOUT: u16 = 3024;
+
+pub struct MySqlAdapter {
+    profile: ConnectionProfile,
+    password: String,
+    conn: Option<Conn>,
+    opts: Option<Opts>,
+    tunnel: Option<SshForward>,
+    statement_timeout: Option<Duration>,
+}
+
+impl MySqlAdapter {
+    pub fn new(profile: ConnectionProfile, password: String) -> Self {
+        Self {
+            statement_timeout: profile.statement_timeout(),
+            profile,
+            password,
+            conn: None,
+            opts: None,
+            tunnel: None,
+        }
+    }
+
+    fn opts(&self, ssl: bool) -> Opts {
+        let database = Some(self.profile.database.clone()).filter(|db| !db.is_empty());
+        let (host, port, ssl_opts) = match &self.tunnel {
+            Some(forward) => (
+                Ipv4Addr::LOCALHOST.to_string(),
+                forward.local_port(),
+                SslOpts::default()
+                    .with_danger_tls_hostname_override(Some(self.profile.host.clone())),
+            ),
+            None => (
+                self.profile.host.clone(),
+                self.profile.port,
+                SslOpts::default(),
+            ),
+        };
+        OptsBuilder::default()
+            .ip_or_hostname(host)
+            .tcp_port(port)
+            .user(Some(self.profile.username.clone()))
+            .pass(Some(self.password.clone()))
+            .db_name(database)
+            .prefer_socket(false)
+            .ssl_opts(ssl.then_some(ssl_opts))
+            .init(if self.profile.read_only {
+                vec!["SET SESSION TRANSACTION READ ONLY"]
+            } else {
+                Vec::new()
+            })
+            .into()
+    }
+
+    async fn establish(&mut self, ssl: bool) -> std::result::Result<(), mysql_async::Error> {
+        let opts = self.opts(ssl);
+        let conn = Conn::new(opts.clone()).await?;
+        self.conn = Some(conn);
+        self.opts = Some(opts);
+        Ok(())
+    }
+
+    fn conn(&mut self) -> Result<&mut Conn> {
+        self.conn
+            .as_mut()
+            .ok_or_else(|| anyhow!("Database client is not connected."))
+    }
+}
+
+#[async_trait]
+impl DbAdapter for MySqlAdapter {
+    async fn connect(
+        &mut self,
+    ) -> std::result::Result<Option<ConnectionClosedFuture>, ConnectionError> {
+        if let Some(tunnel) = &self.profile.ssh_tunnel {
+            let forward = SshForward::open(
+                tunnel,
+                &self.profile.host,
+                self.profile.port,
+                self.profile.connect_timeout(),
+            )
+            .await
+            .map_err(|err| err.redact(&self.password))?;
+            self.tunnel = Some(forward);
+        }
+        let outcome = match self.profile.ssl_mode {
+            SslMode::Disable => self.establish(false).await,
+            SslMode::Require => self.establish(true).await,
+            SslMode::Prefer => match self.establish(true).await {
+                Err(err) if is_tls_error(&err) => {
+                    tracing::info!(
+                        "TLS handshake failed, retrying without TLS: {}",
+                        redact_secret(&err.to_string(), &self.password)
+                    );
+                    self.establish(false).await
+                }
+                outcome => outcome,
+            },
+        };
+        outcome.map(|_| None).map_err(|err| {
+            self.tunnel = None;
+            classify_connection_error(&err).redact(&self.password)
+        })
+    }
+
+    async fn disconnect(&mut self) {
+        if let Some(conn) = self.conn.take()
+            && let Err(err) = conn.disconnect().await
+        {
+            tracing::debug!("MySQL disconnect failed: {err}");
+        }
+        self.tunnel.take();
+    }
+
+    fn connect_timeout(&self) -> Option<Duration> {
+        Some(self.profile.connect_timeout())
+    }
+
+    fn keepalive_interval(&self) -> Option<Duration> {
+        self.profile.keepalive_interval()
+    }
+
+    fn connect_retries(&self) -> u32 {
+        self.profile.connect_retries
+    }
+
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        self.profile.retry_delay(attempt)
+    }
+
+    fn metadata_adapter(&self) -> Option<Box<dyn DbAdapter>> {
+        Some(Box::new(Self::new(
+            self.profile.clone(),
+            self.password.clone(),
+        )))
+    }
+
+    async fn ping(&mut self) -> Result<()> {
+        self.conn()?.ping().await?;
+        Ok(())
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    async fn set_statement_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        let millis = timeout.map_or(0, |timeout| timeout.as_millis());
+        self.conn()?
+            .query_drop(format!("SET SESSION max_execution_time = {millis}"))
+            .await?;
+        self.statement_timeout = timeout;
+        Ok(())
+    }
+
+    fn query_canceller(&self) -> Option<QueryCanceller> {
+        let connection_id = self.conn.as_ref()?.id();
+        let opts = self.opts.clone()?;
+        Some(Box::new(move || {
+            let opts = opts.clone();
+            Box::pin(async move {
+                let mut conn = Conn::new(opts).await?;
+                conn.query_drop(format!("KILL QUERY {connection_id}"))
+                    .await?;
+                conn.disconnect().await?;
+                Ok(())
+            })
+        }))
+    }
+
+    async fn execute(
+        &mut self,
+        sql: String,
+        limit: usize,
+        params: Vec<ParamValue>,
+    ) -> Result<ExecuteOutcome> {
+        let statements = split_statements(ProfileKind::MySql, &sql);
+        let tag = match statements.last() {
+            Some(statement) => statement_tag(statement),
+            None => return Err(anyhow!("No SQL statement to execute.")),
+        };
+        if !params.is_empty() && statements.len() > 1 {
+            return Err(anyhow!("Bind parameters require a single statement."));
+        }
+        let statement_timeout = self.statement_timeout;
+        let statement_count = statements.len();
+        let conn = self.conn()?;
+        let started = Instant::now();
+        let mut params = Some(params).filter(|params| !params.is_empty());
+        let mut affected = 0;
+        let mut last = None;
+        let mut timings = Vec::with_capacity(statement_count);
+        // One statement at a time, like Postgres, so a failure names its statement
+        // and each one gets its own timing.
+        for (index, statement) in statements.into_iter().enumerate() {
+            let sql = cap_row_query(ProfileKind::MySql, statement, limit)
+                .unwrap_or_else(|| statement.to_string());
+            let statement_started = Instant::now();
+            let outcome = async {
+                match params.take() {
+                    None => collect_last_result(conn.query_iter(sql).await?, limit).await,
+                    Some(params) => {
+                        let params =
+                            Params::Positional(params.into_iter().map(param_value).collect());
+                        collect_last_result(conn.exec_iter(sql, params).await?, limit).await
+                    }
+                }
+            };
+            let failed = (statement_count > 1).then_some(index + 1);
+            let (statement_affected, result) = outcome
+                .await
+                .map_err(|err| query_error(err, statement_timeout, failed))?;
+            timings.push(StatementTiming {
+                statement: statement.to_string(),
+                prepare: None,
+                execute: statement_started.elapsed(),
+            });
+            affected = statement_affected;
+            if result.is_some() {
+                last = result;
+            }
+        }
+
+        let Some((columns, rows)) = last else {
+            return Ok(ExecuteOutcome::Completed {
+                affected,
+                tag,
+                timings,
+            });
+        };
+        Ok(ExecuteOutcome::Rows(QueryResult {
+            timings,
+            ..render_result(&columns, &rows, limit, started.elapsed())
+        }))
+    }
+
+    async fn server_version(&mut self) -> Result<String> {
+        let conn = self.conn()?;
+        conn.query_first::<String, _>("select version()")
+            .await?
+            .ok_or_else(|| anyhow!("version query returned no rows"))
+    }
+
+    async fn fetch_server_info(&mut self) -> Result<ServerInfo> {
+        const SQL: &str = "select version(), database(), current_user()";
+        let conn = self.conn()?;
+        let (version, database, user) = conn
+            .query_first::<(String, Option<String>, String), _>(SQL)
+            .await?
+            .ok_or_else(|| anyhow!("server info query returned no rows"))?;
+        Ok(ServerInfo {
+            version,
+            database: database.unwrap_or_default(),
+            user,
+        })
+    }
+
+    async fn fetch_schemas(&mut self) -> Result<Vec<String>> {
+        const SQL: &str = "
+            select schema_name
+            from information_schema.schemata
+            where schema_name not in ('mysql', 'information_schema', 'performance_schema', 'sys')
+            order by schema_name
+        ";
+        let conn = self.conn()?;
+        Ok(conn.query::<String, _>(SQL).await?)
+    }
+
+    async fn fetch_tables(&mut self, schema: String) -> Result<Vec<String>> {
+        const SQL: &str = "
+            select table_name
+            from information_schema.tables
+            where table_schema = ? and table_type = 'BASE TABLE'
+            order by table_name
+        ";
+        let conn = self.conn()?;
+        Ok(conn.exec::<String, _, _>(SQL, (schema,)).await?)
+    }
+
+    async fn fetch_table_sizes(&mut self, schema: String) -> Result<Vec<(String, u64)>> {
+        const SQL: &str = "
+            select table_name, table_rows
+            from information_schema.tables
+            where table_schema = ? and table_type = 'BASE TABLE' and table_rows is not null
+        ";
+        let conn = self.conn()?;
+        Ok(conn.exec::<(String, u64), _, _>(SQL, (schema,)).await?)
+    }
+
+    async fn fetch_views(&mut self, schema: String) -> Result<Vec<ViewMetadata>> {
+        const SQL: &str = "
+            select table_name
+            from information_schema.tables
+            where table_schema = ? and table_type = 'VIEW'
+            order by table_name
+        ";
+        let conn = self.conn()?;
+        let names = conn.exec::<String, _, _>(SQL, (schema,)).await?;
+        Ok(names
+            .into_iter()
+            .map(|name| ViewMetadata {
+                name,
+                materialized: false,
+            })
+            .collect())
+    }
+
+    async fn fetch_columns(
+        &mut self,
+        schema: String,
+        table: String,
+    ) -> Result<Vec<ColumnMetadata>> {
+        const SQL: &str = "
+            select
+                column_name,
+                column_type,
+                is_nullable
+            from information_schema.columns
+            where table_schema = ?
+              and table_name = ?
+            order by ordinal_position
+        ";
+        let conn = self.conn()?;
+        let rows = conn
+            .exec::<(String, String, String), _, _>(SQL, (schema, table))
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(name, data_type, is_nullable)| ColumnMetadata {
+                name,
+                data_type,
+                nullable: Some(is_nullable == "YES"),
+            })
+            .collect())
+    }
+
+    async fn fetch_indexes(&mut self, schema: String, table: String) -> Result<Vec<IndexMetadata>> {
+        const SQL: &str = "
+            select index_name, column_name, non_unique
+            from information_schema.statistics
+            where table_schema = ?
+              and table_name = ?
+            order by index_name = 'PRIMARY' desc, index_name, seq_in_index
+        ";
+        let conn = self.conn()?;
+        let rows = conn
+            .exec::<(String, Option<String>, i64), _, _>(SQL, (schema, table))
+            .await?;
+        let mut indexes: Vec<IndexMetadata> = Vec::new();
+        for (name, column, non_unique) in rows {
+            let index = match indexes.last_mut() {
+                Some(index) if index.name == name => index,
+                _ => {
+                    indexes.push(IndexMetadata {
+                        primary: name == "PRIMARY",
+                        unique: non_unique == 0,
+                        columns: Vec::new(),
+                        name,
+                    });
+                    indexes.last_mut().expect("index was just pushed")
+                }
+            };
+            index.columns.extend(column);
+        }
+        Ok(indexes)
+    }
+
+    async fn fetch_foreign_keys(
+        &mut self,
+        schema: String,
+        table: String,
+    ) -> Result<Vec<ForeignKeyMetadata>> {
+        const SQL: &str = "
+            select
+                kcu.constraint_name,
+                kcu.column_name,
+                kcu.referenced_table_schema,
+                kcu.referenced_table_name,
+                kcu.referenced_column_name
+            from information_schema.key_column_usage kcu
+            join information_schema.referential_constraints rc
+              on rc.constraint_schema = kcu.constraint_schema
+             and rc.constraint_name = kcu.constraint_name
+             and rc.table_name = kcu.table_name
+            where kcu.table_schema = ?
+              and kcu.table_name = ?
+            order by kcu.constraint_name, kcu.ordinal_position
+        ";
+        let conn = self.conn()?;
+        let rows = conn
+            .exec::<(String, String, String, String, String), _, _>(SQL, (schema, table))
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(
+                |(name, column, target_schema, target_table, target_column)| ForeignKeyMetadata {
+                    name,
+                    column,
+                    target_schema,
+                    target_table,
+                    target_column,
+                },
+            )
+            .collect())
+    }
+
+    async fn preview_table(
+        &mut self,
+        schema: String,
+        table: String,
+        limit: usize,
+    ) -> Result<QueryResult> {
+        let limit = limit.min(ROW_LIMIT);
+        // One extra row tells a table of exactly `limit` rows apart from a longer one.
+        let sql = format!(
+            "select * from {} limit {}",
+            qualified_table_name(ProfileKind::MySql, &schema, &table),
+            limit + 1
+        );
+        let conn = self.conn()?;
+        let started = Instant::now();
+        let mut result = conn.query_iter(sql).await?;
+        let columns = result.columns_ref().to_vec();
+        let rows: Vec<Row> = result.collect().await?;
+        result.drop_result().await?;
+
+        Ok(render_result(&columns, &rows, limit, started.elapsed()))
+    }
+
+    async fn fetch_create_table(&mut self, schema: String, table: String) -> Result<String> {
+        let sql = format!(
+            "show create table {}",
+            qualified_table_name(ProfileKind::MySql, &schema, &table)
+        );
+        let conn = self.conn()?;
+        let (_, ddl) = conn
+            .query_first::<(String, String), _>(sql)
+            .await?
+            .ok_or_else(|| anyhow!("{schema}.{table} does not exist"))?;
+        Ok(format!("{ddl};"))
+    }
+
+    async fn fetch_column_stats(
+        &mut self,
+        schema: String,
+        table: String,
+        column: String,
+        sample: bool,
+    ) -> Result<ColumnStats> {
+        let column = quote_identifier(ProfileKind::MySql, &column);
+        let table = qualified_table_name(ProfileKind::MySql, &schema, &table);
+        let source = if sample {
+            format!("(select {column} from {table} limit {STATS_SAMPLE_ROWS}) sample")
+        } else {
+            table
+        };
+        let sql = format!(
+            "select count(*), count({column}), count(distinct {column}), \
+             cast(min({column}) as char), cast(max({column}) as char) from {source}"
+        );
+        let conn = self.conn()?;
+        let (rows, non_null, distinct, min, max) = conn
+            .query_first::<(u64, u64, u64, Option<String>, Option<String>), _>(sql)
+            .await?
+            .ok_or_else(|| anyhow!("the statistics query returned no rows"))?;
+        Ok(ColumnStats {
+            rows,
+            non_null,
+            distinct,
+            min,
+            max,
+            sampled: sample && rows as usize >= STATS_SAMPLE_ROWS,
+        })
+    }
+
+    async fn insert_rows(
+        &mut self,
+        schema: String,
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+    ) -> Result<u64> {
+        let conn = self.conn()?;
+        let mut inserted = 0;
+        let mut first_row = 0;
+        for batch in rows.chunks(insert_batch_rows(columns.len())) {
+            let sql = insert_rows_statement(
+                ProfileKind::MySql,
+                &schema,
+                &table,
+                &columns,
+                &[],
+                batch.len(),
+            );
+            let params = batch
+                .iter()
+                .flatten()
+                .map(|value| {
+                    value
+                        .clone()
+                        .map_or(Value::NULL, |text| Value::Bytes(text.into_bytes()))
+                })
+                .collect();
+            conn.exec_drop(sql, Params::Positional(params))
+                .await
+                .map_err(|err| {
+                    anyhow!("Rows {}-{}: {err}", first_row + 1, first_row + batch.len())
+                })?;
+            inserted += conn.affected_rows();
+            first_row += batch.len();
+        }
+        Ok(inserted)
+    }
+
+    async fn fetch_activity(&mut self) -> Result<Vec<SessionActivity>> {
+        const SQL: &str = "
+            select id, user, coalesce(host, ''), coalesce(db, ''), command, time, state, info,
+                id = connection_id()
+            from information_schema.processlist
+            where command <> 'Daemon'
+            order by command = 'Query' desc, time desc
+        ";
+        type Row = (
+            u64,
+            String,
+            String,
+            String,
+            String,
+            i64,
+            Option<String>,
+            Option<String>,
+            bool,
+        );
+        let conn = self.conn()?;
+        let rows = conn.query::<Row, _>(SQL).await?;
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, user, host, database, command, time, state, info, current)| SessionActivity {
+                    pid: id as i64,
+                    user,
+                    database,
+                    application: String::new(),
+                    client: host,
+                    query_duration: (command == "Query")
+                        .then(|| Duration::from_secs(time.max(0) as u64)),
+                    state: command,
+                    wait_event: state.filter(|state| !state.is_empty()),
+                    query: info.unwrap_or_default(),
+                    current,
+                },
+            )
+            .collect())
+    }
+
+    async fn terminate_backend(&mut self, pid: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        conn.query_drop(format!("kill connection {pid}")).await?;
+        Ok(true)
+    }
+}
+
+// Reads every result set row by row, keeping at most `limit + 1` rows of each
+// (enough for `truncate_rows`) so uncapped statements never load in full.
+async fn collect_last_result<P: Protocol>(
+    mut result: MySqlQueryResult<'_, '_, P>,
+    limit: usize,
+) -> Result<(u64, Option<(Vec<Column>, Vec<Row>)>)> {
+    let mut last = None;
+    let mut affected = result.affected_rows();
+    while !result.is_empty() {
+        let columns = result.columns_ref().to_vec();
+        affected = result.affected_rows();
+        let mut rows = Vec::new();
+        result
+            .for_each(|row| {
+                if rows.len() <= limit {
+                    rows.push(row);
+                }
+            })
+            .await?;
+        last = (!columns.is_empty()).then_some((columns, rows));
+    }
+    Ok((affected, last))
+}
+
+// `failed` is the 1-based index of the statement that failed in a multi-statement
+// run. The driver error stays in the chain for `is_query_interrupted`.
+fn query_error(
+    err: anyhow::Error,
+    statement_timeout: Option<Duration>,
+    failed: Option<usize>,
+) -> anyhow::Error {
+    let mut error = match (err.downcast_ref::<mysql_async::Error>(), statement_timeout) {
+        (Some(mysql_async::Error::Server(server_err)), Some(timeout))
+            if server_err.code == ER_QUERY_TIMEOUT =>
+        {
+            QueryError::new(format!(
+                "Query exceeded statement_timeout ({} s)",
+                timeout.as_secs()
+            ))
+        }
+        (Some(mysql_async::Error::Server(server_err)), _) => QueryError {
+            code: Some(server_err.state.clone()),
+            ..QueryError::new(err.to_string())
+        },
+        _ => QueryError::new(err.to_string()),
+    };
+    if let Some(index) = failed {
+        error.message = format!("Statement {index} failed: {}", error.message);
+    }
+    err.context(error)
+}
+
+// `KILL QUERY` ends the running statement with ER_QUERY_INTERRUPTED.
+pub(crate) fn is_query_interrupted(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<mysql_async::Error>(),
+            Some(mysql_async::Error::Server(server_err)) if server_err.code == ER_QUERY_INTERRUPTED
+        )
+    })
+}
+
+fn param_value(param: ParamValue) -> Value {
+    match param {
+        ParamValue::Null => Value::NULL,
+        ParamValue::Text(value) => Value::Bytes(value.into_bytes()),
+        ParamValue::Int(value) => Value::Int(value),
+        ParamValue::Bool(value) => Value::Int(value.into()),
+    }
+}
+
+fn render_result(
+    columns: &[Column],
+    rows: &[Row],
+    limit: usize,
+    duration: Duration,
+) -> QueryResult {
+    let (shown, truncated) = truncate_rows(rows, limit);
+    QueryResult {
+        columns: columns
+            .iter()
+            .map(|column| column.name_str().into_owned())
+            .collect(),
+        column_metadata: columns
+            .iter()
+            .map(|column| ColumnMetadata {
+                name: column.name_str().into_owned(),
+                data_type: column_type_name(column).into(),
+                nullable: Some(!column.flags().contains(ColumnFlags::NOT_NULL_FLAG)),
+            })
+            .collect(),
+        rows: shown.iter().map(|row| render_row(row, columns)).collect(),
+        row_count: rows.len(),
+        duration,
+        truncated,
+        timings: Vec::new(),
+    }
+}
+
+fn render_row(row: &Row, columns: &[Column]) -> Vec<Cell> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| match row.as_ref(idx) {
+            Some(value) => render_value(value, column),
+            None => Cell::Text("<err>".into()),
+        })
+        .collect()
+}
+
+fn render_value(value: &Value, column: &Column) -> Cell {
+    let text = match value {
+        Value::NULL => return Cell::Null,
+        Value::Bytes(bytes) if is_binary_column(column) => {
+            let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+            format!("0x{hex}")
+        }
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        Value::Int(value) => value.to_string(),
+        Value::UInt(value) => value.to_string(),
+        Value::Float(value) => value.to_string(),
+        Value::Double(value) => value.to_string(),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            if column.column_type() == ColumnType::MYSQL_TYPE_DATE {
+                format!("{year:04}-{month:02}-{day:02}")
+            } else if *micros > 0 {
+                format!(
+                    "{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}.{micros:06}"
+                )
+            } else {
+                format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}")
+            }
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micros) => {
+            let sign = if *negative { "-" } else { "" };
+            let hours = days * 24 + u32::from(*hours);
+            if *micros > 0 {
+                format!("{sign}{hours:02}:{minutes:02}:{seconds:02}.{micros:06}")
+            } else {
+                format!("{sign}{hours:02}:{minutes:02}:{seconds:02}")
+            }
+        }
+    };
+    Cell::Text(text)
+}
+
+fn column_type_name(column: &Column) -> &'static str {
+    let unsigned = column.flags().contains(ColumnFlags::UNSIGNED_FLAG);
+    match column.column_type() {
+        ColumnType::MYSQL_TYPE_TINY if unsigned => "tinyint unsigned",
+        ColumnType::MYSQL_TYPE_TINY => "tinyint",
+        ColumnType::MYSQL_TYPE_SHORT if unsigned => "smallint unsigned",
+        ColumnType::MYSQL_TYPE_SHORT => "smallint",
+        ColumnType::MYSQL_TYPE_INT24 if unsigned => "mediumint unsigned",
+        ColumnType::MYSQL_TYPE_INT24 => "mediumint",
+        ColumnType::MYSQL_TYPE_LONG if unsigned => "int unsigned",
+        ColumnType::MYSQL_TYPE_LONG => "int",
+        ColumnType::MYSQL_TYPE_LONGLONG if unsigned => "bigint unsigned",
+        ColumnType::MYSQL_TYPE_LONGLONG => "bigint",
+        ColumnType::MYSQL_TYPE_FLOAT => "float",
+        ColumnType::MYSQL_TYPE_DOUBLE => "double",
+        ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => "decimal",
+        ColumnType::MYSQL_TYPE_YEAR => "year",
+        ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => "date",
+        ColumnType::MYSQL_TYPE_TIME | ColumnType::MYSQL_TYPE_TIME2 => "time",
+        ColumnType::MYSQL_TYPE_DATETIME | ColumnType::MYSQL_TYPE_DATETIME2 => "datetime",
+        ColumnType::MYSQL_TYPE_TIMESTAMP | ColumnType::MYSQL_TYPE_TIMESTAMP2 => "timestamp",
+        ColumnType::MYSQL_TYPE_BIT => "bit",
+        ColumnType::MYSQL_TYPE_JSON => "json",
+        ColumnType::MYSQL_TYPE_ENUM => "enum",
+        ColumnType::MYSQL_TYPE_SET => "set",
+        ColumnType::MYSQL_TYPE_GEOMETRY => "geometry",
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB
+            if is_binary_column(column) =>
+        {
+            "blob"
+        }
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB => "text",
+        ColumnType::MYSQL_TYPE_STRING if is_binary_column(column) => "binary",
+        ColumnType::MYSQL_TYPE_STRING => "char",
+        ColumnType::MYSQL_TYPE_VARCHAR | ColumnType::MYSQL_TYPE_VAR_STRING
+            if is_binary_column(column) =>
+        {
+            "varbinary"
+        }
+        ColumnType::MYSQL_TYPE_VARCHAR | ColumnType::MYSQL_TYPE_VAR_STRING => "varchar",
+        ColumnType::MYSQL_TYPE_NULL => "null",
+        _ => "unknown",
+    }
+}
+
+fn is_binary_column(column: &Column) -> bool {
+    if column.character_set() != BINARY_CHARSET {
+        return false;
+    }
+    matches!(
+        column.column_type(),
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+            | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+            | ColumnType::MYSQL_TYPE_LONG_BLOB
+            | ColumnType::MYSQL_TYPE_BLOB
+            | ColumnType::MYSQL_TYPE_VAR_STRING
+            | ColumnType::MYSQL_TYPE_STRING
+            | ColumnType::MYSQL_TYPE_VARCHAR
+            | ColumnType::MYSQL_TYPE_GEOMETRY
+            | ColumnType::MYSQL_TYPE_BIT
+    )
+}
+
+fn is_tls_error(err: &mysql_async::Error) -> bool {
+    matches!(
+        err,
+        mysql_async::Error::Driver(mysql_async::DriverError::NoClientSslFlagFromServer)
+            | mysql_async::Error::Io(mysql_async::IoError::Tls(_))
+    )
+}
+
+fn classify_connection_error(err: &mysql_async::Error) -> ConnectionError {
+    let detail = err.to_string();
+    if let mysql_async::Error::Server(server_err) = err {
+        return match server_err.code {
+            1045 => ConnectionError::new("Password authentication failed.", detail),
+            1044 | 1130 => ConnectionError::new("User does not exist or lacks permission.", detail),
+            1049 => ConnectionError::new("Database does not exist.", detail),
+            _ => ConnectionError::new(server_err.message.clone(), detail),
+        };
+    }
+
+    let lower = detail.to_lowercase();
+    if matches!(
+        err,
+        mysql_async::Error::Driver(mysql_async::DriverError::NoClientSslFlagFromServer)
+    ) {
+        ConnectionError::new("The server does not accept SSL connections.", detail)
+    } else if matches!(err, mysql_async::Error::Io(mysql_async::IoError::Tls(_))) {
+        ConnectionError::new(
+            "SSL handshake failed (check the server certificate).",
+            detail,
+        )
+    } else if lower.contains("connection refused") {
+        ConnectionError::new(
+            "Unable to reach the database host (connection refused).",
+            detail,
+        )
+        .transient()
+    } else if lower.contains("timed out") || lower.contains("timeout") {
+        ConnectionError::new("Connection timed out.", detail).transient()
+    } else {
+        ConnectionError::new("Failed to connect to the database.", detail)
+    }
+}
@@ -1,21 +1,42 @@
 use std::{
+    collections::HashMap,
+    future::poll_fn,
+    net::{IpAddr, Ipv4Addr},
+    pin::pin,
     sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{AtomicBool, Ordering},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
+use async_channel::Sender;
 use async_trait::async_trait;
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
-use dbmiru_core::profiles::ConnectionProfile;
-use tokio_postgres::{Client, NoTls, Row, types::Type};
+use bytes::BytesMut;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use dbmiru_core::profiles::{ConnectionProfile, ProfileKind, SslMode};
+use futures_util::TryStreamExt;
+use tokio_postgres::{
+    AsyncMessage, Client, Column, NoTls, Row, Socket, Statement,
+    error::ErrorPosition,
+    tls::{MakeTlsConnect, TlsConnect},
+    types::{Field, FromSql, IsNull, Kind, ToSql, Type, to_sql_checked},
+};
+use tokio_postgres_rustls::MakeRustlsConnect;
 use uuid::Uuid;
 
 use crate::{
-    ColumnMetadata, ConnectionClosedFuture, ConnectionError, DbAdapter, QueryResult, ROW_LIMIT,
-    Result,
+    Cell, ColumnMetadata, ColumnStats, ConnectionClosedFuture, ConnectionError, DbAdapter, DbEvent,
+    ExecuteOutcome, ForeignKeyMetadata, IndexMetadata, ParamValue, QueryCanceller, QueryError,
+    QueryResult, ROW_LIMIT, Result, STATS_SAMPLE_ROWS, ServerInfo, SessionActivity,
+    StatementTiming, ViewMetadata, redact_secret,
+    sql::{
+        CAPPED_QUERY_PREFIX, cap_row_query, insert_batch_rows, insert_rows_statement,
+        qualified_table_name, quote_identifier, split_statements, statement_tag,
+    },
+    ssh::SshForward,
+    truncate_rows,
 };
 
 pub struct PostgresAdapter {
@@ -23,18 +44,74 @@ pub struct PostgresAdapter {
     password: String,
     client: Option<Client>,
     disconnecting: Arc<AtomicBool>,
+    notices: Arc<Mutex<Vec<String>>>,
+    notifications: Option<Sender<DbEvent>>,
+    tunnel: Option<SshForward>,
+    statement_timeout: Option<Duration>,
 }
 
 impl PostgresAdapter {
     pub fn new(profile: ConnectionProfile, password: String) -> Self {
         Self {
+            statement_timeout: profile.statement_timeout(),
             profile,
             password,
             client: None,
             disconnecting: Arc::new(AtomicBool::new(false)),
+            notices: Arc::new(Mutex::new(Vec::new())),
+            notifications: None,
+            tunnel: None,
         }
     }
 
+    async fn establish<T>(
+        &mut self,
+        config: &tokio_postgres::Config,
+        tls: T,
+    ) -> std::result::Result<ConnectionClosedFuture, tokio_postgres::Error>
+    where
+        T: MakeTlsConnect<Socket> + Send,
+        T::Stream: Send + 'static,
+        T::TlsConnect: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let (client, mut connection) = config.connect(tls).await?;
+        let disconnecting = self.disconnecting.clone();
+        let notices = self.notices.clone();
+        let notifications = self.notifications.clone();
+        let monitor = Box::pin(async move {
+            let outcome = loop {
+                match poll_fn(|cx| connection.poll_message(cx)).await {
+                    Some(Ok(AsyncMessage::Notice(notice))) => {
+                        if let Ok(mut notices) = notices.lock() {
+                            notices.push(notice.to_string());
+                        }
+                    }
+                    Some(Ok(AsyncMessage::Notification(notification))) => {
+                        if let Some(event_tx) = notifications.as_ref() {
+                            let _ = event_tx
+                                .send(DbEvent::Notification {
+                                    channel: notification.channel().to_string(),
+                                    payload: notification.payload().to_string(),
+                                })
+                                .await;
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => break Err(err),
+                    None => break Ok(()),
+                }
+            };
+            if disconnecting.load(Ordering::SeqCst) {
+                None
+            } else {
+                outcome.err().map(|err| err.to_string())
+            }
+        });
+        self.client = Some(client);
+        Ok(monitor)
+    }
+
     fn client(&mut self) -> Result<&mut Client> {
         self.client
             .as_mut()
@@ -49,49 +126,242 @@ impl DbAdapter for PostgresAdapter {
     ) -> std::result::Result<Option<ConnectionClosedFuture>, ConnectionError> {
         let mut config = tokio_postgres::Config::new();
         config.host(&self.profile.host);
-        config.port(self.profile.port);
         config.user(&self.profile.username);
         config.dbname(&self.profile.database);
         config.password(&self.password);
+        config.connect_timeout(self.profile.connect_timeout());
+        if self.profile.read_only {
+            config.options("-c default_transaction_read_only=on");
+        }
+        if let Some(tunnel) = &self.profile.ssh_tunnel {
+            let forward = SshForward::open(
+                tunnel,
+                &self.profile.host,
+                self.profile.port,
+                self.profile.connect_timeout(),
+            )
+            .await
+            .map_err(|err| err.redact(&self.password))?;
+            config.hostaddr(IpAddr::V4(Ipv4Addr::LOCALHOST));
+            config.port(forward.local_port());
+            self.tunnel = Some(forward);
+        } else {
+            config.port(self.profile.port);
+        }
 
-        let (client, connection) = match config.connect(NoTls).await {
-            Ok(conn) => conn,
-            Err(err) => return Err(classify_connection_error(&err)),
-        };
-        let disconnecting = self.disconnecting.clone();
-        let monitor = Box::pin(async move {
-            let outcome = connection.await;
-            if disconnecting.load(Ordering::SeqCst) {
-                None
-            } else {
-                outcome.err().map(|err| err.to_string())
+        let outcome = match self.profile.ssl_mode {
+            SslMode::Disable => {
+                config.ssl_mode(tokio_postgres::config::SslMode::Disable);
+                self.establish(&config, NoTls).await
             }
-        });
-        self.client = Some(client);
-        Ok(Some(monitor))
+            SslMode::Prefer | SslMode::Require => {
+                let tls = tls_connector().map_err(|err| {
+                    ConnectionError::new("Failed to initialize TLS.", err.to_string())
+                        .redact(&self.password)
+                })?;
+                if self.profile.ssl_mode == SslMode::Require {
+                    config.ssl_mode(tokio_postgres::config::SslMode::Require);
+                    self.establish(&config, tls).await
+                } else {
+                    config.ssl_mode(tokio_postgres::config::SslMode::Prefer);
+                    match self.establish(&config, tls).await {
+                        Err(err) if is_tls_error(&err) => {
+                            tracing::info!(
+                                "TLS handshake failed, retrying without TLS: {}",
+                                redact_secret(&err.to_string(), &self.password)
+                            );
+                            config.ssl_mode(tokio_postgres::config::SslMode::Disable);
+                            self.establish(&config, NoTls).await
+                        }
+                        outcome => outcome,
+                    }
+                }
+            }
+        };
+        outcome.map(Some).map_err(|err| {
+            self.tunnel = None;
+            classify_connection_error(&err).redact(&self.password)
+        })
     }
 
     async fn disconnect(&mut self) {
         self.disconnecting.store(true, Ordering::SeqCst);
         self.client.take();
+        self.tunnel.take();
+    }
+
+    fn connect_timeout(&self) -> Option<Duration> {
+        Some(self.profile.connect_timeout())
+    }
+
+    fn keepalive_interval(&self) -> Option<Duration> {
+        self.profile.keepalive_interval()
+    }
+
+    fn connect_retries(&self) -> u32 {
+        self.profile.connect_retries
+    }
+
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        self.profile.retry_delay(attempt)
+    }
+
+    fn metadata_adapter(&self) -> Option<Box<dyn DbAdapter>> {
+        Some(Box::new(Self::new(
+            self.profile.clone(),
+            self.password.clone(),
+        )))
+    }
+
+    async fn ping(&mut self) -> Result<()> {
+        match self.client()?.simple_query("SELECT 1").await {
+            // The server answered, so the socket is alive even if the statement
+            // was refused (e.g. inside an aborted transaction).
+            Err(err) if err.as_db_error().is_some() => Ok(()),
+            outcome => outcome.map(|_| ()).map_err(Into::into),
+        }
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    async fn set_statement_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        let millis = timeout.map_or(0, |timeout| timeout.as_millis());
+        self.client()?
+            .batch_execute(&format!("SET statement_timeout = {millis}"))
+            .await?;
+        self.statement_timeout = timeout;
+        Ok(())
+    }
+
+    fn take_notices(&mut self) -> Vec<String> {
+        self.notices
+            .lock()
+            .map(|mut notices| std::mem::take(&mut *notices))
+            .unwrap_or_default()
     }
 
-    async fn execute(&mut self, sql: String, limit: usize) -> Result<QueryResult> {
+    fn query_canceller(&self) -> Option<QueryCanceller> {
+        let token = self.client.as_ref()?.cancel_token();
+        // The cancel request opens a connection of its own, so it needs the same
+        // TLS setup as the session; the token keeps the negotiated sslmode.
+        let tls = match self.profile.ssl_mode {
+            SslMode::Disable => None,
+            SslMode::Prefer | SslMode::Require => match tls_connector() {
+                Ok(tls) => Some(tls),
+                Err(err) => {
+                    tracing::warn!("Failed to initialize TLS for query cancellation: {err}");
+                    return None;
+                }
+            },
+        };
+        Some(Box::new(move || {
+            let token = token.clone();
+            let tls = tls.clone();
+            Box::pin(async move {
+                match tls {
+                    Some(tls) => token.cancel_query(tls).await?,
+                    None => token.cancel_query(NoTls).await?,
+                }
+                Ok(())
+            })
+        }))
+    }
+
+    async fn execute(
+        &mut self,
+        sql: String,
+        limit: usize,
+        params: Vec<ParamValue>,
+    ) -> Result<ExecuteOutcome> {
+        let statements = split_statements(ProfileKind::Postgres, &sql);
+        if statements.is_empty() {
+            return Err(anyhow!("No SQL statement to execute."));
+        }
         let client = self.client()?;
         let started = Instant::now();
-        match client.query(sql.as_str(), &[]).await {
-            Ok(rows) => {
-                let (columns, data_rows) = convert_rows(&rows, limit);
-                Ok(QueryResult {
-                    columns,
-                    rows: data_rows,
-                    row_count: rows.len(),
-                    duration: started.elapsed(),
-                    truncated: rows.len() > limit,
-                })
+        let statement_count = statements.len();
+        let mut last = StatementOutput::Affected(0);
+        let mut last_statement = "";
+        let mut timings = Vec::with_capacity(statement_count);
+        for (index, statement) in statements.into_iter().enumerate() {
+            let capped = cap_row_query(ProfileKind::Postgres, statement, limit);
+            let statement_started = Instant::now();
+            match run_statement(
+                client,
+                capped.as_deref().unwrap_or(statement),
+                &params,
+                limit,
+            )
+            .await
+            {
+                Ok((output, prepare)) => {
+                    timings.push(StatementTiming {
+                        statement: statement.to_string(),
+                        prepare: Some(prepare),
+                        execute: statement_started.elapsed().saturating_sub(prepare),
+                    });
+                    last = output;
+                    last_statement = statement;
+                }
+                Err(err) => {
+                    let summary = match (err.as_db_error(), self.statement_timeout) {
+                        (Some(db), Some(timeout)) if is_statement_timeout(db) => {
+                            format!("Query exceeded statement_timeout ({} s)", timeout.as_secs())
+                        }
+                        (Some(db), _) => format!("{}: {}", db.severity(), db.message()),
+                        (None, _) => err.to_string(),
+                    };
+                    let message = if statement_count > 1 {
+                        format!("Statement {} failed: {summary}", index + 1)
+                    } else {
+                        summary
+                    };
+                    let db_error = err.as_db_error();
+                    return Err(QueryError {
+                        message,
+                        position: error_position(&err, &sql, statement, capped.is_some()),
+                        detail: db_error.and_then(|db| db.detail()).map(str::to_string),
+                        hint: db_error.and_then(|db| db.hint()).map(str::to_string),
+                        code: db_error.map(|db| db.code().code().to_string()),
+                    }
+                    .into());
+                }
             }
-            Err(err) => Err(err.into()),
         }
+        let (statement, rows) = match last {
+            StatementOutput::Rows(statement, rows) => (statement, rows),
+            StatementOutput::Affected(affected) => {
+                return Ok(ExecuteOutcome::Completed {
+                    affected,
+                    tag: statement_tag(last_statement),
+                    timings,
+                });
+            }
+        };
+        let (shown, truncated) = truncate_rows(&rows, limit);
+        let column_metadata = result_column_metadata(client, statement.columns()).await;
+        Ok(ExecuteOutcome::Rows(QueryResult {
+            columns: column_names(statement.columns()),
+            column_metadata,
+            rows: convert_rows(shown),
+            row_count: rows.len(),
+            duration: started.elapsed(),
+            truncated,
+            timings,
+        }))
+    }
+
+    async fn fetch_server_info(&mut self) -> Result<ServerInfo> {
+        const SQL: &str = "select version(), current_database()::text, current_user::text";
+        let client = self.client()?;
+        let row = client.query_one(SQL, &[]).await?;
+        Ok(ServerInfo {
+            version: row.try_get(0)?,
+            database: row.try_get(1)?,
+            user: row.try_get(2)?,
+        })
     }
 
     async fn fetch_schemas(&mut self) -> Result<Vec<String>> {
@@ -124,30 +394,181 @@ impl DbAdapter for PostgresAdapter {
             .collect())
     }
 
+    async fn fetch_table_sizes(&mut self, schema: String) -> Result<Vec<(String, u64)>> {
+        // reltuples is the planner's estimate; -1 means the table was never analyzed.
+        const SQL: &str = "
+            select c.relname::text, c.reltuples::int8
+            from pg_class c
+            join pg_namespace n on n.oid = c.relnamespace
+            where n.nspname = $1 and c.relkind in ('r', 'p') and c.reltuples >= 0
+        ";
+        let client = self.client()?;
+        let rows = client.query(SQL, &[&schema]).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let name: String = row.try_get(0).ok()?;
+                let estimate: i64 = row.try_get(1).ok()?;
+                Some((name, estimate.max(0) as u64))
+            })
+            .collect())
+    }
+
+    async fn fetch_views(&mut self, schema: String) -> Result<Vec<ViewMetadata>> {
+        const SQL: &str = "
+            select table_name::text, false
+            from information_schema.views
+            where table_schema = $1
+            union all
+            select matviewname::text, true
+            from pg_catalog.pg_matviews
+            where schemaname = $1
+            order by 1
+        ";
+        let client = self.client()?;
+        let rows = client.query(SQL, &[&schema]).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(
+                |row| match (row.try_get::<_, String>(0), row.try_get::<_, bool>(1)) {
+                    (Ok(name), Ok(materialized)) => Some(ViewMetadata { name, materialized }),
+                    _ => None,
+                },
+            )
+            .collect())
+    }
+
     async fn fetch_columns(
         &mut self,
         schema: String,
         table: String,
     ) -> Result<Vec<ColumnMetadata>> {
+        // `format_type` keeps lengths and precision, e.g. `character varying(100)`.
         const SQL: &str = "
             select
-                column_name,
-                data_type
-            from information_schema.columns
-            where table_schema = $1
-              and table_name = $2
-            order by ordinal_position
+                c.column_name::text,
+                format_type(a.atttypid, a.atttypmod),
+                c.is_nullable = 'YES'
+            from information_schema.columns c
+            join pg_catalog.pg_namespace n on n.nspname = c.table_schema
+            join pg_catalog.pg_class r on r.relnamespace = n.oid and r.relname = c.table_name
+            join pg_catalog.pg_attribute a on a.attrelid = r.oid and a.attname = c.column_name
+            where c.table_schema = $1
+              and c.table_name = $2
+            order by c.ordinal_position
+        ";
+        // Materialized views are missing from information_schema.columns.
+        const MATVIEW_SQL: &str = "
+            select
+                a.attname::text,
+                format_type(a.atttypid, a.atttypmod),
+                not a.attnotnull
+            from pg_catalog.pg_attribute a
+            join pg_catalog.pg_matviews m
+              on a.attrelid = format('%I.%I', m.schemaname, m.matviewname)::regclass
+            where m.schemaname = $1
+              and m.matviewname = $2
+              and a.attnum > 0
+              and not a.attisdropped
+            order by a.attnum
         ";
         let client = self.client()?;
-        let rows = client.query(SQL, &[&schema, &table]).await?;
+        let mut rows = client.query(SQL, &[&schema, &table]).await?;
+        if rows.is_empty() {
+            rows = client.query(MATVIEW_SQL, &[&schema, &table]).await?;
+        }
         Ok(rows
             .into_iter()
-            .filter_map(
-                |row| match (row.try_get::<_, String>(0), row.try_get::<_, String>(1)) {
-                    (Ok(name), Ok(data_type)) => Some(ColumnMetadata { name, data_type }),
+            .filter_map(|row| {
+                match (
+                    row.try_get::<_, String>(0),
+                    row.try_get::<_, String>(1),
+                    row.try_get::<_, bool>(2),
+                ) {
+                    (Ok(name), Ok(data_type), Ok(nullable)) => Some(ColumnMetadata {
+                        name,
+                        data_type,
+                        nullable: Some(nullable),
+                    }),
                     _ => None,
-                },
-            )
+                }
+            })
+            .collect())
+    }
+
+    async fn fetch_indexes(&mut self, schema: String, table: String) -> Result<Vec<IndexMetadata>> {
+        const SQL: &str = "
+            select
+                i.relname::text,
+                array(
+                    select a.attname::text
+                    from unnest(ix.indkey) with ordinality as k(attnum, position)
+                    join pg_catalog.pg_attribute a
+                      on a.attrelid = ix.indrelid and a.attnum = k.attnum
+                    order by k.position
+                ),
+                ix.indisunique,
+                ix.indisprimary
+            from pg_catalog.pg_index ix
+            join pg_catalog.pg_class t on t.oid = ix.indrelid
+            join pg_catalog.pg_class i on i.oid = ix.indexrelid
+            join pg_catalog.pg_namespace n on n.oid = t.relnamespace
+            where n.nspname = $1
+              and t.relname = $2
+            order by ix.indisprimary desc, i.relname
+        ";
+        let client = self.client()?;
+        let rows = client.query(SQL, &[&schema, &table]).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(IndexMetadata {
+                    name: row.try_get(0).ok()?,
+                    columns: row.try_get(1).ok()?,
+                    unique: row.try_get(2).ok()?,
+                    primary: row.try_get(3).ok()?,
+                })
+            })
+            .collect())
+    }
+
+    async fn fetch_foreign_keys(
+        &mut self,
+        schema: String,
+        table: String,
+    ) -> Result<Vec<ForeignKeyMetadata>> {
+        const SQL: &str = "
+            select
+                kcu.constraint_name::text,
+                kcu.column_name::text,
+                target.table_schema::text,
+                target.table_name::text,
+                target.column_name::text
+            from information_schema.key_column_usage kcu
+            join information_schema.referential_constraints rc
+              on rc.constraint_schema = kcu.constraint_schema
+             and rc.constraint_name = kcu.constraint_name
+            join information_schema.key_column_usage target
+              on target.constraint_schema = rc.unique_constraint_schema
+             and target.constraint_name = rc.unique_constraint_name
+             and target.ordinal_position = kcu.position_in_unique_constraint
+            where kcu.table_schema = $1
+              and kcu.table_name = $2
+            order by kcu.constraint_name, kcu.ordinal_position
+        ";
+        let client = self.client()?;
+        let rows = client.query(SQL, &[&schema, &table]).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(ForeignKeyMetadata {
+                    name: row.try_get(0).ok()?,
+                    column: row.try_get(1).ok()?,
+                    target_schema: row.try_get(2).ok()?,
+                    target_table: row.try_get(3).ok()?,
+                    target_column: row.try_get(4).ok()?,
+                })
+            })
             .collect())
     }
 
@@ -157,48 +578,503 @@ impl DbAdapter for PostgresAdapter {
         table: String,
         limit: usize,
     ) -> Result<QueryResult> {
+        let limit = limit.min(ROW_LIMIT);
+        // One extra row tells a table of exactly `limit` rows apart from a longer one.
         let sql = format!(
             "select * from {} limit {}",
-            qualified_table_name(&schema, &table),
-            limit.min(ROW_LIMIT)
+            qualified_table_name(ProfileKind::Postgres, &schema, &table),
+            limit + 1
         );
         let client = self.client()?;
         let started = Instant::now();
-        match client.query(sql.as_str(), &[]).await {
+        let statement = client.prepare(sql.as_str()).await?;
+        match client.query(&statement, &[]).await {
             Ok(rows) => {
-                let (columns, data_rows) = convert_rows(&rows, limit);
+                let (shown, truncated) = truncate_rows(&rows, limit);
+                let column_metadata = result_column_metadata(client, statement.columns()).await;
                 Ok(QueryResult {
-                    columns,
-                    rows: data_rows,
+                    columns: column_names(statement.columns()),
+                    column_metadata,
+                    rows: convert_rows(shown),
                     row_count: rows.len(),
                     duration: started.elapsed(),
-                    truncated: rows.len() == limit,
+                    truncated,
+                    timings: Vec::new(),
                 })
             }
             Err(err) => Err(err.into()),
         }
     }
-}
 
-fn convert_rows(rows: &[Row], limit: usize) -> (Vec<String>, Vec<Vec<String>>) {
-    let columns = rows
-        .first()
-        .map(|row| {
-            row.columns()
-                .iter()
-                .map(|col| col.name().to_string())
-                .collect()
+    async fn fetch_create_table(&mut self, schema: String, table: String) -> Result<String> {
+        const COLUMNS_SQL: &str = "
+            select
+                a.attname::text,
+                format_type(a.atttypid, a.atttypmod),
+                c.column_default::text,
+                a.attnotnull
+            from pg_attribute a
+            join pg_class t on t.oid = a.attrelid
+            join pg_namespace n on n.oid = t.relnamespace
+            left join information_schema.columns c
+              on c.table_schema = n.nspname
+             and c.table_name = t.relname
+             and c.column_name = a.attname
+            where n.nspname = $1
+              and t.relname = $2
+              and a.attnum > 0
+              and not a.attisdropped
+            order by a.attnum
+        ";
+        const CONSTRAINTS_SQL: &str = "
+            select con.conname::text, pg_get_constraintdef(con.oid)
+            from pg_constraint con
+            join pg_class t on t.oid = con.conrelid
+            join pg_namespace n on n.oid = t.relnamespace
+            where n.nspname = $1
+              and t.relname = $2
+              and con.contype in ('p', 'u', 'f', 'c')
+            order by
+                case con.contype when 'p' then 0 when 'u' then 1 when 'f' then 2 else 3 end,
+                con.conname
+        ";
+        let client = self.client()?;
+        let columns = client.query(COLUMNS_SQL, &[&schema, &table]).await?;
+        if columns.is_empty() {
+            return Err(anyhow!("{schema}.{table} has no columns or does not exist"));
+        }
+        let constraints = client.query(CONSTRAINTS_SQL, &[&schema, &table]).await?;
+
+        let mut lines = Vec::with_capacity(columns.len() + constraints.len());
+        for row in &columns {
+            let name: String = row.try_get(0)?;
+            let data_type: String = row.try_get(1)?;
+            let default: Option<String> = row.try_get(2)?;
+            let not_null: bool = row.try_get(3)?;
+            let mut line = format!(
+                "    {} {data_type}",
+                quote_identifier(ProfileKind::Postgres, &name)
+            );
+            if let Some(default) = default {
+                line.push_str(&format!(" DEFAULT {default}"));
+            }
+            if not_null {
+                line.push_str(" NOT NULL");
+            }
+            lines.push(line);
+        }
+        for row in &constraints {
+            let name: String = row.try_get(0)?;
+            let definition: String = row.try_get(1)?;
+            lines.push(format!(
+                "    CONSTRAINT {} {definition}",
+                quote_identifier(ProfileKind::Postgres, &name)
+            ));
+        }
+        Ok(format!(
+            "CREATE TABLE {} (\n{}\n);",
+            qualified_table_name(ProfileKind::Postgres, &schema, &table),
+            lines.join(",\n")
+        ))
+    }
+
+    async fn fetch_column_stats(
+        &mut self,
+        schema: String,
+        table: String,
+        column: String,
+        sample: bool,
+    ) -> Result<ColumnStats> {
+        // Pick aggregates from the column's (base) type category up front:
+        // a failing min()/count(distinct) would abort an open transaction.
+        const TYPE_SQL: &str = "
+            select base.typcategory::text, base.typname::text, t.reltuples::float8
+            from pg_attribute a
+            join pg_class t on t.oid = a.attrelid
+            join pg_namespace n on n.oid = t.relnamespace
+            join pg_type ty on ty.oid = a.atttypid
+            join pg_type base on base.oid =
+                case when ty.typtype = 'd' then ty.typbasetype else ty.oid end
+            where n.nspname = $1 and t.relname = $2 and a.attname = $3
+                and a.attnum > 0 and not a.attisdropped";
+
+        let client = self.client()?;
+        let row = client
+            .query_opt(TYPE_SQL, &[&schema, &table, &column])
+            .await?
+            .ok_or_else(|| anyhow!("column {column} not found in {schema}.{table}"))?;
+        let category: String = row.try_get(0)?;
+        let type_name: String = row.try_get(1)?;
+        let estimated_rows: f64 = row.try_get(2)?;
+
+        let quoted = quote_identifier(ProfileKind::Postgres, &column);
+        let distinct = if category == "G" || matches!(type_name.as_str(), "json" | "xml") {
+            format!("{quoted}::text")
+        } else {
+            quoted.clone()
+        };
+        let min_max = if matches!(category.as_str(), "N" | "D" | "S" | "T" | "E") {
+            format!("min({quoted})::text, max({quoted})::text")
+        } else {
+            "null::text, null::text".to_string()
+        };
+        let sampling = if sample && estimated_rows > STATS_SAMPLE_ROWS as f64 {
+            let percent = 100.0 * STATS_SAMPLE_ROWS as f64 / estimated_rows;
+            format!(" tablesample system ({percent:.4})")
+        } else {
+            String::new()
+        };
+        let sql = format!(
+            "select count(*), count({quoted}), count(distinct {distinct}), {min_max} from {}{sampling}",
+            qualified_table_name(ProfileKind::Postgres, &schema, &table)
+        );
+        let row = client.query_one(sql.as_str(), &[]).await?;
+        let rows: i64 = row.try_get(0)?;
+        let non_null: i64 = row.try_get(1)?;
+        let distinct: i64 = row.try_get(2)?;
+        Ok(ColumnStats {
+            rows: rows as u64,
+            non_null: non_null as u64,
+            distinct: distinct as u64,
+            min: row.try_get(3)?,
+            max: row.try_get(4)?,
+            sampled: !sampling.is_empty(),
         })
-        .unwrap_or_default();
+    }
+
+    async fn insert_rows(
+        &mut self,
+        schema: String,
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+    ) -> Result<u64> {
+        // format_type gives a castable name even for arrays, domains, and enums.
+        const TYPE_SQL: &str = "
+            select a.attname::text, format_type(a.atttypid, a.atttypmod)
+            from pg_catalog.pg_attribute a
+            where a.attrelid = $1::text::regclass and a.attnum > 0 and not a.attisdropped
+        ";
+        let qualified = qualified_table_name(ProfileKind::Postgres, &schema, &table);
+        let client = self.client()?;
+        let types: HashMap<String, String> = client
+            .query(TYPE_SQL, &[&qualified])
+            .await?
+            .into_iter()
+            .filter_map(|row| Some((row.try_get(0).ok()?, row.try_get(1).ok()?)))
+            .collect();
+        let column_types = columns
+            .iter()
+            .map(|column| {
+                types
+                    .get(column)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("column {column} does not exist in {schema}.{table}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut inserted = 0;
+        let mut first_row = 0;
+        for batch in rows.chunks(insert_batch_rows(columns.len())) {
+            let sql = insert_rows_statement(
+                ProfileKind::Postgres,
+                &schema,
+                &table,
+                &columns,
+                &column_types,
+                batch.len(),
+            );
+            let params: Vec<ParamValue> = batch
+                .iter()
+                .flatten()
+                .map(|value| value.clone().map_or(ParamValue::Null, ParamValue::Text))
+                .collect();
+            let params: Vec<&(dyn ToSql + Sync)> = params
+                .iter()
+                .map(|param| param as &(dyn ToSql + Sync))
+                .collect();
+            inserted += client.execute(sql.as_str(), &params).await.map_err(|err| {
+                let rows = format!("Rows {}-{}", first_row + 1, first_row + batch.len());
+                match err.as_db_error() {
+                    Some(db) => match db.detail() {
+                        Some(detail) => {
+                            anyhow!("{rows}: {}: {} ({detail})", db.severity(), db.message())
+                        }
+                        None => anyhow!("{rows}: {}: {}", db.severity(), db.message()),
+                    },
+                    None => anyhow!("{rows}: {err}"),
+                }
+            })?;
+            first_row += batch.len();
+        }
+        Ok(inserted)
+    }
+
+    async fn fetch_activity(&mut self) -> Result<Vec<SessionActivity>> {
+        const SQL: &str = "
+            select pid::int8, coalesce(usename::text, ''), coalesce(datname::text, ''),
+                coalesce(application_name, ''), coalesce(client_addr::text, 'local'),
+                coalesce(state, ''), wait_event_type || ': ' || wait_event,
+                case when state = 'active'
+                    then extract(epoch from clock_timestamp() - query_start)::float8 end,
+                coalesce(query, ''), pid = pg_backend_pid()
+            from pg_stat_activity
+            where backend_type = 'client backend'
+            order by state = 'active' desc, query_start desc nulls last
+        ";
+        let client = self.client()?;
+        let rows = client.query(SQL, &[]).await?;
+        rows.into_iter()
+            .map(|row| {
+                let elapsed: Option<f64> = row.try_get(7)?;
+                Ok(SessionActivity {
+                    pid: row.try_get(0)?,
+                    user: row.try_get(1)?,
+                    database: row.try_get(2)?,
+                    application: row.try_get(3)?,
+                    client: row.try_get(4)?,
+                    state: row.try_get(5)?,
+                    wait_event: row.try_get(6)?,
+                    query_duration: elapsed.map(|secs| Duration::from_secs_f64(secs.max(0.))),
+                    query: row.try_get(8)?,
+                    current: row.try_get(9)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn terminate_backend(&mut self, pid: i64) -> Result<bool> {
+        let client = self.client()?;
+        let pid = i32::try_from(pid)?;
+        let row = client
+            .query_one("select pg_terminate_backend($1)", &[&pid])
+            .await?;
+        Ok(row.try_get(0)?)
+    }
+
+    fn forward_notifications(&mut self, event_tx: Sender<DbEvent>) {
+        self.notifications = Some(event_tx);
+    }
+
+    async fn listen(&mut self, channel: String) -> Result<()> {
+        let sql = format!(
+            "listen {}",
+            quote_identifier(ProfileKind::Postgres, &channel)
+        );
+        self.client()?.batch_execute(&sql).await?;
+        Ok(())
+    }
+
+    async fn unlisten(&mut self, channel: String) -> Result<()> {
+        let sql = format!(
+            "unlisten {}",
+            quote_identifier(ProfileKind::Postgres, &channel)
+        );
+        self.client()?.batch_execute(&sql).await?;
+        Ok(())
+    }
+}
+
+// Postgres reports a 1-based character position within the statement it ran;
+// translate it into a 0-based character offset into the full editor text.
+fn error_position(
+    err: &tokio_postgres::Error,
+    sql: &str,
+    statement: &str,
+    capped: bool,
+) -> Option<usize> {
+    let ErrorPosition::Original(position) = err.as_db_error()?.position()? else {
+        return None;
+    };
+    let mut position = (*position as usize).saturating_sub(1);
+    if capped {
+        position = position.checked_sub(CAPPED_QUERY_PREFIX.len())?;
+    }
+    let statement_start = (statement.as_ptr() as usize).checked_sub(sql.as_ptr() as usize)?;
+    let preceding = sql.get(..statement_start)?.chars().count();
+    Some(preceding + position)
+}
+
+enum StatementOutput {
+    Rows(Statement, Vec<Row>),
+    Affected(u64),
+}
+
+// A cancel request ends the running statement with SQLSTATE 57014.
+pub(crate) fn is_query_canceled(err: &anyhow::Error) -> bool {
+    use tokio_postgres::error::SqlState;
 
-    let mut rendered_rows = Vec::new();
-    for row in rows.iter().take(limit) {
-        rendered_rows.push(render_row(row));
+    err.downcast_ref::<QueryError>()
+        .and_then(|error| error.code.as_deref())
+        == Some(SqlState::QUERY_CANCELED.code())
+}
+
+fn is_statement_timeout(error: &tokio_postgres::error::DbError) -> bool {
+    use tokio_postgres::error::SqlState;
+
+    error.code() == &SqlState::QUERY_CANCELED && error.message().contains("statement timeout")
+}
+
+async fn run_statement(
+    client: &Client,
+    sql: &str,
+    params: &[ParamValue],
+    limit: usize,
+) -> std::result::Result<(StatementOutput, Duration), tokio_postgres::Error> {
+    let started = Instant::now();
+    let statement = client.prepare(sql).await?;
+    let prepare = started.elapsed();
+    let params: Vec<&(dyn ToSql + Sync)> = params
+        .iter()
+        .take(statement.params().len())
+        .map(|param| param as &(dyn ToSql + Sync))
+        .collect();
+    if statement.columns().is_empty() {
+        client
+            .execute(&statement, &params)
+            .await
+            .map(|affected| (StatementOutput::Affected(affected), prepare))
+    } else {
+        let stream = client.query_raw(&statement, params).await?;
+        let mut stream = pin!(stream);
+        let mut rows = Vec::new();
+        while rows.len() <= limit {
+            match stream.try_next().await? {
+                Some(row) => rows.push(row),
+                None => break,
+            }
+        }
+        Ok((StatementOutput::Rows(statement, rows), prepare))
+    }
+}
+
+impl ToSql for ParamValue {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        match (self, ty) {
+            (ParamValue::Null, _) => Ok(IsNull::Yes),
+            (ParamValue::Bool(value), &Type::BOOL) => value.to_sql(ty, out),
+            (ParamValue::Int(value), &Type::INT2) => i16::try_from(*value)?.to_sql(ty, out),
+            (ParamValue::Int(value), &Type::INT4) => i32::try_from(*value)?.to_sql(ty, out),
+            (ParamValue::Int(value), &Type::INT8) => value.to_sql(ty, out),
+            (ParamValue::Int(value), &Type::FLOAT4) => (*value as f32).to_sql(ty, out),
+            (ParamValue::Int(value), &Type::FLOAT8) => (*value as f64).to_sql(ty, out),
+            (ParamValue::Text(value), _) => text_to_sql(value, ty, out),
+            (ParamValue::Int(value), _) if <&str as ToSql>::accepts(ty) => {
+                value.to_string().as_str().to_sql(ty, out)
+            }
+            (ParamValue::Bool(value), _) if <&str as ToSql>::accepts(ty) => {
+                value.to_string().as_str().to_sql(ty, out)
+            }
+            (ParamValue::Int(_), _) => {
+                Err(format!("cannot bind an int to a {ty} parameter").into())
+            }
+            (ParamValue::Bool(_), _) => {
+                Err(format!("cannot bind a bool to a {ty} parameter").into())
+            }
+        }
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+fn text_to_sql(
+    value: &str,
+    ty: &Type,
+    out: &mut BytesMut,
+) -> std::result::Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    match *ty {
+        Type::BOOL => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => true.to_sql(ty, out),
+            "false" | "f" | "no" | "n" | "0" => false.to_sql(ty, out),
+            _ => Err(format!("'{value}' is not a boolean").into()),
+        },
+        Type::INT2 => value.trim().parse::<i16>()?.to_sql(ty, out),
+        Type::INT4 => value.trim().parse::<i32>()?.to_sql(ty, out),
+        Type::INT8 => value.trim().parse::<i64>()?.to_sql(ty, out),
+        Type::FLOAT4 => value.trim().parse::<f32>()?.to_sql(ty, out),
+        Type::FLOAT8 => value.trim().parse::<f64>()?.to_sql(ty, out),
+        Type::UUID => Uuid::parse_str(value.trim())?.to_sql(ty, out),
+        Type::DATE => NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")?.to_sql(ty, out),
+        Type::TIMESTAMP => parse_timestamp(value)?.to_sql(ty, out),
+        Type::TIMESTAMPTZ => match DateTime::parse_from_rfc3339(value.trim()) {
+            Ok(timestamp) => timestamp.with_timezone(&Utc).to_sql(ty, out),
+            Err(_) => parse_timestamp(value)?.and_utc().to_sql(ty, out),
+        },
+        Type::JSON | Type::JSONB => {
+            serde_json::from_str::<serde_json::Value>(value)?.to_sql(ty, out)
+        }
+        _ if <&str as ToSql>::accepts(ty) => value.to_sql(ty, out),
+        _ => {
+            Err(format!("cannot bind text to a {ty} parameter; add a cast such as $1::text").into())
+        }
+    }
+}
+
+fn parse_timestamp(value: &str) -> std::result::Result<NaiveDateTime, chrono::ParseError> {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+}
+
+async fn result_column_metadata(client: &Client, columns: &[Column]) -> Vec<ColumnMetadata> {
+    const SQL: &str = "
+        select attrelid, attnum, attnotnull
+        from pg_catalog.pg_attribute
+        where attrelid = any($1) and attnum > 0
+    ";
+    let mut table_oids: Vec<u32> = columns.iter().filter_map(|col| col.table_oid()).collect();
+    table_oids.sort_unstable();
+    table_oids.dedup();
+
+    let mut not_null = HashMap::new();
+    if !table_oids.is_empty() {
+        match client.query(SQL, &[&table_oids]).await {
+            Ok(attributes) => {
+                for attribute in attributes {
+                    if let (Ok(table_oid), Ok(column_id), Ok(is_not_null)) = (
+                        attribute.try_get::<_, u32>(0),
+                        attribute.try_get::<_, i16>(1),
+                        attribute.try_get::<_, bool>(2),
+                    ) {
+                        not_null.insert((table_oid, column_id), is_not_null);
+                    }
+                }
+            }
+            Err(err) => tracing::debug!("Failed to look up column nullability: {err}"),
+        }
     }
-    (columns, rendered_rows)
+
+    columns
+        .iter()
+        .map(|col| ColumnMetadata {
+            name: col.name().to_string(),
+            data_type: col.type_().name().to_string(),
+            nullable: col
+                .table_oid()
+                .zip(col.column_id())
+                .and_then(|key| not_null.get(&key))
+                .map(|is_not_null| !is_not_null),
+        })
+        .collect()
 }
 
-fn render_row(row: &Row) -> Vec<String> {
+fn column_names(columns: &[Column]) -> Vec<String> {
+    columns.iter().map(|col| col.name().to_string()).collect()
+}
+
+fn convert_rows(rows: &[Row]) -> Vec<Vec<Cell>> {
+    rows.iter().map(render_row).collect()
+}
+
+fn render_row(row: &Row) -> Vec<Cell> {
     let mut values = Vec::with_capacity(row.len());
     for (idx, column) in row.columns().iter().enumerate() {
         values.push(render_cell(row, idx, column.type_()));
@@ -206,7 +1082,7 @@ fn render_row(row: &Row) -> Vec<String> {
     values
 }
 
-fn render_cell(row: &Row, idx: usize, ty: &Type) -> String {
+fn render_cell(row: &Row, idx: usize, ty: &Type) -> Cell {
     match *ty {
         Type::BOOL => format_optional(row.try_get::<_, Option<bool>>(idx)),
         Type::INT2 => format_optional(row.try_get::<_, Option<i16>>(idx)),
@@ -214,6 +1090,10 @@ fn render_cell(row: &Row, idx: usize, ty: &Type) -> String {
         Type::INT8 => format_optional(row.try_get::<_, Option<i64>>(idx)),
         Type::FLOAT4 => format_optional(row.try_get::<_, Option<f32>>(idx)),
         Type::FLOAT8 => format_optional(row.try_get::<_, Option<f64>>(idx)),
+        Type::NUMERIC => format_optional(
+            row.try_get::<_, Option<PgNumeric>>(idx)
+                .map(|opt| opt.map(|value| value.0)),
+        ),
         Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME => {
             format_optional(row.try_get::<_, Option<String>>(idx))
         }
@@ -229,6 +1109,19 @@ fn render_cell(row: &Row, idx: usize, ty: &Type) -> String {
             row.try_get::<_, Option<NaiveDate>>(idx)
                 .map(|opt| opt.map(|d| d.to_string())),
         ),
+        Type::TIME => format_optional(row.try_get::<_, Option<NaiveTime>>(idx)),
+        Type::TIMETZ => format_optional(
+            row.try_get::<_, Option<PgTimeTz>>(idx)
+                .map(|opt| opt.map(|value| value.0)),
+        ),
+        Type::INTERVAL => format_optional(
+            row.try_get::<_, Option<PgInterval>>(idx)
+                .map(|opt| opt.map(|value| value.0)),
+        ),
+        Type::INET | Type::CIDR => format_optional(
+            row.try_get::<_, Option<PgInet>>(idx)
+                .map(|opt| opt.map(|value| value.0)),
+        ),
         Type::UUID => format_optional(
             row.try_get::<_, Option<Uuid>>(idx)
                 .map(|opt| opt.map(|v| v.to_string())),
@@ -241,30 +1134,55 @@ fn render_cell(row: &Row, idx: usize, ty: &Type) -> String {
             row.try_get::<_, Option<Vec<u8>>>(idx)
                 .map(|opt| opt.map(|bytes| format_bytea(&bytes))),
         ),
-        _ => format_optional(
-            row.try_get::<_, Option<String>>(idx)
-                .map(|opt| opt.or_else(|| Some("<unsupported>".into()))),
-        ),
+        Type::BOOL_ARRAY => render_array(row, idx, |value: bool| value.to_string()),
+        Type::INT2_ARRAY => render_array(row, idx, |value: i16| value.to_string()),
+        Type::INT4_ARRAY => render_array(row, idx, |value: i32| value.to_string()),
+        Type::INT8_ARRAY => render_array(row, idx, |value: i64| value.to_string()),
+        Type::FLOAT4_ARRAY => render_array(row, idx, |value: f32| value.to_string()),
+        Type::FLOAT8_ARRAY => render_array(row, idx, |value: f64| value.to_string()),
+        Type::NUMERIC_ARRAY => render_array(row, idx, |value: PgNumeric| value.0),
+        Type::TEXT_ARRAY | Type::VARCHAR_ARRAY | Type::BPCHAR_ARRAY | Type::NAME_ARRAY => {
+            render_array(row, idx, |value: String| value)
+        }
+        Type::TIMESTAMP_ARRAY => render_array(row, idx, |dt: NaiveDateTime| dt.to_string()),
+        Type::TIMESTAMPTZ_ARRAY => render_array(row, idx, |dt: DateTime<Utc>| dt.to_rfc3339()),
+        Type::DATE_ARRAY => render_array(row, idx, |d: NaiveDate| d.to_string()),
+        Type::TIME_ARRAY => render_array(row, idx, |t: NaiveTime| t.to_string()),
+        Type::TIMETZ_ARRAY => render_array(row, idx, |value: PgTimeTz| value.0),
+        Type::INTERVAL_ARRAY => render_array(row, idx, |value: PgInterval| value.0),
+        Type::INET_ARRAY | Type::CIDR_ARRAY => render_array(row, idx, |value: PgInet| value.0),
+        Type::UUID_ARRAY => render_array(row, idx, |v: Uuid| v.to_string()),
+        Type::JSON_ARRAY | Type::JSONB_ARRAY => {
+            render_array(row, idx, |value: serde_json::Value| value.to_string())
+        }
+        _ => match ty.kind() {
+            Kind::Array(_) => render_array(row, idx, |value: PgText| value.0),
+            _ => match row.try_get::<_, Option<PgText>>(idx) {
+                Ok(value) => format_optional(Ok::<_, ()>(value.map(|value| value.0))),
+                Err(_) => Cell::Text("<unsupported>".into()),
+            },
+        },
     }
 }
 
-fn quote_identifier(value: &str) -> String {
-    let escaped = value.replace('"', "\"\"");
-    format!("\"{escaped}\"")
-}
-
-fn qualified_table_name(schema: &str, table: &str) -> String {
-    format!("{}.{}", quote_identifier(schema), quote_identifier(table))
+fn render_array<'a, T>(row: &'a Row, idx: usize, render: impl Fn(T) -> String) -> Cell
+where
+    T: FromSql<'a>,
+{
+    format_optional(
+        row.try_get::<_, Option<Vec<Option<T>>>>(idx)
+            .map(|opt| opt.map(|values| format_array(values.into_iter().map(|v| v.map(&render))))),
+    )
 }
 
-fn format_optional<T, E>(value: std::result::Result<Option<T>, E>) -> String
+fn format_optional<T, E>(value: std::result::Result<Option<T>, E>) -> Cell
 where
     T: ToString,
 {
     match value {
-        Ok(Some(inner)) => inner.to_string(),
-        Ok(None) => "NULL".into(),
-        Err(_) => "<err>".into(),
+        Ok(Some(inner)) => Cell::Text(inner.to_string()),
+        Ok(None) => Cell::Null,
+        Err(_) => Cell::Text("<err>".into()),
     }
 }
 
@@ -278,6 +1196,380 @@ fn format_bytea(bytes: &[u8]) -> String {
     out
 }
 
+struct PgNumeric(String);
+
+impl<'a> FromSql<'a> for PgNumeric {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        decode_numeric(raw)
+            .map(PgNumeric)
+            .ok_or_else(|| "invalid numeric value".into())
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
+fn decode_numeric(raw: &[u8]) -> Option<String> {
+    use std::fmt::Write;
+
+    const NUMERIC_NEG: u16 = 0x4000;
+    const NUMERIC_NAN: u16 = 0xC000;
+    const NUMERIC_PINF: u16 = 0xD000;
+    const NUMERIC_NINF: u16 = 0xF000;
+
+    let word = |offset: usize| {
+        raw.get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+    let ndigits = word(0)? as usize;
+    let weight = word(2)? as i16 as i32;
+    let sign = word(4)?;
+    let dscale = word(6)? as usize;
+    match sign {
+        NUMERIC_NAN => return Some("NaN".into()),
+        NUMERIC_PINF => return Some("Infinity".into()),
+        NUMERIC_NINF => return Some("-Infinity".into()),
+        _ => {}
+    }
+    let digits = (0..ndigits)
+        .map(|idx| word(8 + idx * 2))
+        .collect::<Option<Vec<u16>>>()?;
+    let digit_at = |idx: i32| {
+        usize::try_from(idx)
+            .ok()
+            .and_then(|idx| digits.get(idx).copied())
+            .unwrap_or(0)
+    };
+
+    let mut out = String::new();
+    if sign == NUMERIC_NEG {
+        out.push('-');
+    }
+    if weight < 0 {
+        out.push('0');
+    } else {
+        for idx in 0..=weight {
+            if idx == 0 {
+                let _ = write!(out, "{}", digit_at(idx));
+            } else {
+                let _ = write!(out, "{:04}", digit_at(idx));
+            }
+        }
+    }
+    if dscale > 0 {
+        let mut fraction = String::with_capacity(dscale + 4);
+        let mut idx = weight + 1;
+        while fraction.len() < dscale {
+            let _ = write!(fraction, "{:04}", digit_at(idx));
+            idx += 1;
+        }
+        fraction.truncate(dscale);
+        out.push('.');
+        out.push_str(&fraction);
+    }
+    Some(out)
+}
+
+type FromSqlError = Box<dyn std::error::Error + Sync + Send>;
+
+// Text rendering for values without a dedicated arm in `render_cell`: enums,
+// composites, anonymous records, domains, and anything `String` already
+// understands. Ranges, multiranges, other pseudo-types, and extension types
+// with a binary format of their own still render as `<unsupported>`.
+struct PgText(String);
+
+impl<'a> FromSql<'a> for PgText {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> std::result::Result<Self, FromSqlError> {
+        decode_text(ty, raw).map(PgText)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Enum(_) | Kind::Composite(_) => true,
+            Kind::Pseudo => *ty == Type::RECORD,
+            Kind::Domain(inner) => Self::accepts(inner),
+            _ => <String as FromSql>::accepts(ty),
+        }
+    }
+}
+
+fn decode_text(ty: &Type, raw: &[u8]) -> std::result::Result<String, FromSqlError> {
+    match ty.kind() {
+        Kind::Enum(_) => return Ok(std::str::from_utf8(raw)?.to_string()),
+        Kind::Domain(inner) => return decode_text(inner, raw),
+        Kind::Composite(fields) => return decode_record(Some(fields), raw),
+        _ => {}
+    }
+    let text = match *ty {
+        Type::BOOL => bool::from_sql(ty, raw)?.to_string(),
+        Type::INT2 => i16::from_sql(ty, raw)?.to_string(),
+        Type::INT4 => i32::from_sql(ty, raw)?.to_string(),
+        Type::INT8 => i64::from_sql(ty, raw)?.to_string(),
+        Type::FLOAT4 => f32::from_sql(ty, raw)?.to_string(),
+        Type::FLOAT8 => f64::from_sql(ty, raw)?.to_string(),
+        Type::NUMERIC => PgNumeric::from_sql(ty, raw)?.0,
+        Type::TIMESTAMP => NaiveDateTime::from_sql(ty, raw)?.to_string(),
+        Type::TIMESTAMPTZ => DateTime::<Utc>::from_sql(ty, raw)?.to_rfc3339(),
+        Type::DATE => NaiveDate::from_sql(ty, raw)?.to_string(),
+        Type::TIME => NaiveTime::from_sql(ty, raw)?.to_string(),
+        Type::TIMETZ => PgTimeTz::from_sql(ty, raw)?.0,
+        Type::INTERVAL => PgInterval::from_sql(ty, raw)?.0,
+        Type::INET | Type::CIDR => PgInet::from_sql(ty, raw)?.0,
+        Type::UUID => Uuid::from_sql(ty, raw)?.to_string(),
+        Type::JSON | Type::JSONB => serde_json::Value::from_sql(ty, raw)?.to_string(),
+        Type::BYTEA => format_bytea(raw),
+        Type::RECORD => decode_record(None, raw)?,
+        _ if <String as FromSql>::accepts(ty) => String::from_sql(ty, raw)?,
+        _ => return Err(format!("unsupported type {}", ty.name()).into()),
+    };
+    Ok(text)
+}
+
+// Composite types carry their field types; anonymous records only have the
+// per-field OIDs on the wire, so those decode when every field is a built-in.
+fn decode_record(
+    fields: Option<&[Field]>,
+    raw: &[u8],
+) -> std::result::Result<String, FromSqlError> {
+    let (count, mut rest) = read_i32(raw).ok_or("invalid record value")?;
+    let count = usize::try_from(count).map_err(|_| "invalid record value")?;
+    if fields.is_some_and(|fields| fields.len() != count) {
+        return Err("record field count does not match its type".into());
+    }
+    let mut out = String::from("(");
+    for idx in 0..count {
+        if idx > 0 {
+            out.push(',');
+        }
+        let (oid, after_oid) = read_i32(rest).ok_or("invalid record value")?;
+        let (length, after_length) = read_i32(after_oid).ok_or("invalid record value")?;
+        rest = after_length;
+        // A negative length marks NULL, which the row literal leaves empty.
+        let Ok(length) = usize::try_from(length) else {
+            continue;
+        };
+        let value = rest.get(..length).ok_or("invalid record value")?;
+        rest = &rest[length..];
+        let text = match fields {
+            Some(fields) => decode_text(fields[idx].type_(), value)?,
+            None => {
+                let ty = Type::from_oid(oid as u32).ok_or("unknown record field type")?;
+                decode_text(&ty, value)?
+            }
+        };
+        if record_field_needs_quotes(&text) {
+            out.push('"');
+            for ch in text.chars() {
+                if ch == '"' || ch == '\\' {
+                    out.push(ch);
+                }
+                out.push(ch);
+            }
+            out.push('"');
+        } else {
+            out.push_str(&text);
+        }
+    }
+    out.push(')');
+    Ok(out)
+}
+
+fn record_field_needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value
+            .chars()
+            .any(|ch| matches!(ch, '"' | '\\' | '(' | ')' | ',') || ch.is_whitespace())
+}
+
+struct PgInterval(String);
+
+impl<'a> FromSql<'a> for PgInterval {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let (micros, rest) = read_i64(raw).ok_or("invalid interval value")?;
+        let (days, rest) = read_i32(rest).ok_or("invalid interval value")?;
+        let (months, _) = read_i32(rest).ok_or("invalid interval value")?;
+        Ok(PgInterval(format_interval(months, days, micros)))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::INTERVAL
+    }
+}
+
+fn format_interval(months: i32, days: i32, micros: i64) -> String {
+    let mut parts = Vec::new();
+    let (years, months) = (months / 12, months % 12);
+    for (value, singular, plural) in [
+        (years, "year", "years"),
+        (months, "mon", "mons"),
+        (days, "day", "days"),
+    ] {
+        if value != 0 {
+            parts.push(format!(
+                "{value} {}",
+                if value == 1 { singular } else { plural }
+            ));
+        }
+    }
+    if micros != 0 || parts.is_empty() {
+        let sign = if micros < 0 { "-" } else { "" };
+        parts.push(format!(
+            "{sign}{}",
+            format_time_of_day(micros.unsigned_abs())
+        ));
+    }
+    parts.join(" ")
+}
+
+fn format_time_of_day(micros: u64) -> String {
+    let seconds = micros / 1_000_000;
+    let fraction = micros % 1_000_000;
+    let mut out = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    if fraction > 0 {
+        out.push_str(format!(".{fraction:06}").trim_end_matches('0'));
+    }
+    out
+}
+
+struct PgTimeTz(String);
+
+impl<'a> FromSql<'a> for PgTimeTz {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let (micros, rest) = read_i64(raw).ok_or("invalid timetz value")?;
+        let (zone, _) = read_i32(rest).ok_or("invalid timetz value")?;
+        let micros = u64::try_from(micros).map_err(|_| "invalid timetz value")?;
+        // The wire format stores the zone as seconds west of UTC.
+        let offset = -zone;
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.unsigned_abs();
+        let mut out = format!("{}{sign}{:02}", format_time_of_day(micros), offset / 3600);
+        if offset % 3600 != 0 {
+            out.push_str(&format!(":{:02}", offset / 60 % 60));
+        }
+        if offset % 60 != 0 {
+            out.push_str(&format!(":{:02}", offset % 60));
+        }
+        Ok(PgTimeTz(out))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TIMETZ
+    }
+}
+
+struct PgInet(String);
+
+impl<'a> FromSql<'a> for PgInet {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        const AF_INET: u8 = 2;
+        const AF_INET6: u8 = 3;
+
+        let [family, bits, _is_cidr, length, address @ ..] = raw else {
+            return Err("invalid inet value".into());
+        };
+        let (address, max_bits) = match (*family, *length as usize) {
+            (AF_INET, 4) => {
+                let octets: [u8; 4] = address.try_into().map_err(|_| "invalid inet value")?;
+                (IpAddr::from(octets), 32)
+            }
+            (AF_INET6, 16) => {
+                let octets: [u8; 16] = address.try_into().map_err(|_| "invalid inet value")?;
+                (IpAddr::from(octets), 128)
+            }
+            _ => return Err("invalid inet value".into()),
+        };
+        if *ty == Type::INET && *bits == max_bits {
+            Ok(PgInet(address.to_string()))
+        } else {
+            Ok(PgInet(format!("{address}/{bits}")))
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::INET || *ty == Type::CIDR
+    }
+}
+
+fn read_i64(raw: &[u8]) -> Option<(i64, &[u8])> {
+    let (bytes, rest) = raw.split_first_chunk::<8>()?;
+    Some((i64::from_be_bytes(*bytes), rest))
+}
+
+fn read_i32(raw: &[u8]) -> Option<(i32, &[u8])> {
+    let (bytes, rest) = raw.split_first_chunk::<4>()?;
+    Some((i32::from_be_bytes(*bytes), rest))
+}
+
+fn format_array(values: impl Iterator<Item = Option<String>>) -> String {
+    let mut out = String::from("{");
+    for (idx, value) in values.enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        match value {
+            Some(value) if array_element_needs_quotes(&value) => {
+                out.push('"');
+                for ch in value.chars() {
+                    if ch == '"' || ch == '\\' {
+                        out.push('\\');
+                    }
+                    out.push(ch);
+                }
+                out.push('"');
+            }
+            Some(value) => out.push_str(&value),
+            None => out.push_str("NULL"),
+        }
+    }
+    out.push('}');
+    out
+}
+
+fn array_element_needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.eq_ignore_ascii_case("NULL")
+        || value
+            .chars()
+            .any(|ch| matches!(ch, ',' | '{' | '}' | '"' | '\\') || ch.is_whitespace())
+}
+
+fn tls_connector() -> Result<MakeRustlsConnect> {
+    let mut roots = rustls::RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    for err in &native.errors {
+        tracing::warn!("Failed to load a system root certificate: {err}");
+    }
+    roots.add_parsable_certificates(native.certs);
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(MakeRustlsConnect::new(config))
+}
+
+fn is_tls_error(err: &tokio_postgres::Error) -> bool {
+    err.as_db_error().is_none() && err.to_string().to_lowercase().contains("tls")
+}
+
 fn classify_connection_error(err: &tokio_postgres::Error) -> ConnectionError {
     use tokio_postgres::error::SqlState;
 
@@ -298,16 +1590,172 @@ fn classify_connection_error(err: &tokio_postgres::Error) -> ConnectionError {
         return ConnectionError::new(db_err.message().to_string(), detail);
     }
 
-    let detail = err.to_string();
+    // Connect errors only name the failed step; the cause (e.g. the refused
+    // socket) is in the source.
+    let detail = match std::error::Error::source(err) {
+        Some(source) => format!("{err}: {source}"),
+        None => err.to_string(),
+    };
     let lower = detail.to_lowercase();
-    if lower.contains("connection refused") {
+    if lower.contains("server does not support tls") {
+        ConnectionError::new("The server does not accept SSL connections.", detail)
+    } else if lower.contains("tls handshake") {
+        ConnectionError::new(
+            "SSL handshake failed (check the server certificate).",
+            detail,
+        )
+    } else if lower.contains("connection refused") {
         ConnectionError::new(
             "Unable to reach the database host (connection refused).",
             detail,
         )
+        .transient()
     } else if lower.contains("timeout") {
-        ConnectionError::new("Connection timed out.", detail)
+        ConnectionError::new("Connection timed out.", detail).transient()
     } else {
         ConnectionError::new("Failed to connect to the database.", detail)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval_wire(months: i32, days: i32, micros: i64) -> Vec<u8> {
+        let mut raw = micros.to_be_bytes().to_vec();
+        raw.extend(days.to_be_bytes());
+        raw.extend(months.to_be_bytes());
+        raw
+    }
+
+    fn timetz_wire(micros: i64, zone_seconds_west: i32) -> Vec<u8> {
+        let mut raw = micros.to_be_bytes().to_vec();
+        raw.extend(zone_seconds_west.to_be_bytes());
+        raw
+    }
+
+    fn interval(months: i32, days: i32, micros: i64) -> String {
+        PgInterval::from_sql(&Type::INTERVAL, &interval_wire(months, days, micros))
+            .unwrap()
+            .0
+    }
+
+    fn timetz(micros: i64, zone_seconds_west: i32) -> String {
+        PgTimeTz::from_sql(&Type::TIMETZ, &timetz_wire(micros, zone_seconds_west))
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn decodes_interval_months_and_days() {
+        // interval '1 mon 2 days'
+        assert_eq!(
+            interval_wire(1, 2, 0),
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 1]
+        );
+        assert_eq!(interval(1, 2, 0), "1 mon 2 days");
+        assert_eq!(
+            interval(26, 1, 3_723_500_000),
+            "2 years 2 mons 1 day 01:02:03.5"
+        );
+        assert_eq!(interval(0, 0, 0), "00:00:00");
+    }
+
+    #[test]
+    fn decodes_negative_intervals() {
+        // interval '-1 days -02:30:00'
+        assert_eq!(interval(0, -1, -9_000_000_000), "-1 days -02:30:00");
+        // interval '-14 mons'
+        assert_eq!(interval(-14, 0, 0), "-1 years -2 mons");
+        assert_eq!(interval(0, 0, -1), "-00:00:00.000001");
+    }
+
+    #[test]
+    fn rejects_short_interval() {
+        assert!(PgInterval::from_sql(&Type::INTERVAL, &[0; 12]).is_err());
+    }
+
+    #[test]
+    fn decodes_timetz_offsets() {
+        // timetz '12:34:56.5+05:30' stores the zone as seconds west of UTC.
+        assert_eq!(timetz(45_296_500_000, -19_800), "12:34:56.5+05:30");
+        assert_eq!(timetz(28_800_000_000, 28_800), "08:00:00-08");
+        assert_eq!(timetz(1_000_000, -20_730), "00:00:01+05:45:30");
+        assert_eq!(timetz(0, 0), "00:00:00+00");
+    }
+
+    #[test]
+    fn decodes_inet_and_cidr() {
+        // inet '192.168.0.1/24': family, bits, is_cidr, length, address.
+        let raw = [2, 24, 0, 4, 192, 168, 0, 1];
+        assert_eq!(
+            PgInet::from_sql(&Type::INET, &raw).unwrap().0,
+            "192.168.0.1/24"
+        );
+        let host = [2, 32, 0, 4, 10, 0, 0, 1];
+        assert_eq!(PgInet::from_sql(&Type::INET, &host).unwrap().0, "10.0.0.1");
+        let cidr = [2, 32, 1, 4, 10, 0, 0, 1];
+        assert_eq!(
+            PgInet::from_sql(&Type::CIDR, &cidr).unwrap().0,
+            "10.0.0.1/32"
+        );
+        let mut loopback = vec![3, 128, 0, 16];
+        loopback.extend(std::net::Ipv6Addr::LOCALHOST.octets());
+        assert_eq!(PgInet::from_sql(&Type::INET, &loopback).unwrap().0, "::1");
+    }
+
+    // Bytes as returned by `numeric_send`.
+    fn numeric(hex: &str) -> Option<String> {
+        let raw = (0..hex.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap())
+            .collect::<Vec<_>>();
+        decode_numeric(&raw)
+    }
+
+    #[test]
+    fn decodes_numeric_zero_and_nan() {
+        assert_eq!(numeric("0000000000000000").as_deref(), Some("0"));
+        assert_eq!(numeric("0000000000000002").as_deref(), Some("0.00"));
+        assert_eq!(numeric("00000000c0000000").as_deref(), Some("NaN"));
+        assert_eq!(numeric("00000000d0000000").as_deref(), Some("Infinity"));
+        assert_eq!(numeric("00000000f0000000").as_deref(), Some("-Infinity"));
+    }
+
+    #[test]
+    fn decodes_negative_numeric() {
+        assert_eq!(
+            numeric("0002000040000001000c1388").as_deref(),
+            Some("-12.5")
+        );
+        assert_eq!(numeric("0001ffff400000040001").as_deref(), Some("-0.0001"));
+    }
+
+    #[test]
+    fn decodes_numeric_scale() {
+        assert_eq!(numeric("000200000000000200011388").as_deref(), Some("1.50"));
+        assert_eq!(numeric("00010001000000000001").as_deref(), Some("10000"));
+        assert_eq!(
+            numeric("0001fffd0000000c007b").as_deref(),
+            Some("0.000000000123")
+        );
+        assert_eq!(
+            numeric("000b000700000009000c0d801ed204d2162e23340d801ed204d2162e2328").as_deref(),
+            Some("123456789012345678901234567890.123456789")
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_numeric() {
+        // Two digits announced, one sent.
+        assert_eq!(numeric("00020000000000020001"), None);
+        assert_eq!(numeric("000000"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_inet() {
+        assert!(PgInet::from_sql(&Type::INET, &[2, 24, 0, 4, 192, 168]).is_err());
+        assert!(PgInet::from_sql(&Type::INET, &[9, 24, 0, 4, 192, 168, 0, 1]).is_err());
+        assert!(PgInet::from_sql(&Type::INET, &[2, 24]).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+
+use crate::ColumnMetadata;
+
+// Table name → columns in ordinal order, as loaded for one side of a diff.
+pub type SchemaSnapshot = BTreeMap<String, Vec<ColumnMetadata>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    // Only on the right (target) side.
+    Added,
+    // Only on the left (base) side.
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Clone)]
+pub struct ColumnDiff {
+    pub name: String,
+    pub kind: DiffKind,
+    pub left: Option<ColumnMetadata>,
+    pub right: Option<ColumnMetadata>,
+}
+
+#[derive(Clone)]
+pub struct TableDiff {
+    pub name: String,
+    pub kind: DiffKind,
+    pub columns: Vec<ColumnDiff>,
+}
+
+// Compares two snapshots table by table, in name order. Columns keep the left
+// side's order, followed by right-only columns in the right side's order.
+pub fn diff_schemas(left: &SchemaSnapshot, right: &SchemaSnapshot) -> Vec<TableDiff> {
+    let mut names: Vec<&String> = left.keys().chain(right.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| match (left.get(name), right.get(name)) {
+            (Some(left), Some(right)) => {
+                let columns = diff_columns(left, right);
+                let kind = if columns
+                    .iter()
+                    .all(|column| column.kind == DiffKind::Unchanged)
+                {
+                    DiffKind::Unchanged
+                } else {
+                    DiffKind::Changed
+                };
+                TableDiff {
+                    name: name.clone(),
+                    kind,
+                    columns,
+                }
+            }
+            (Some(columns), None) => TableDiff {
+                name: name.clone(),
+                kind: DiffKind::Removed,
+                columns: one_sided(columns, DiffKind::Removed),
+            },
+            (None, Some(columns)) => TableDiff {
+                name: name.clone(),
+                kind: DiffKind::Added,
+                columns: one_sided(columns, DiffKind::Added),
+            },
+            (None, None) => unreachable!("name came from one of the snapshots"),
+        })
+        .collect()
+}
+
+pub fn diff_columns(left: &[ColumnMetadata], right: &[ColumnMetadata]) -> Vec<ColumnDiff> {
+    let mut diffs: Vec<ColumnDiff> = left
+        .iter()
+        .map(|column| {
+            let other = right.iter().find(|other| other.name == column.name);
+            let kind = match other {
+                None => DiffKind::Removed,
+                Some(other) if columns_match(column, other) => DiffKind::Unchanged,
+                Some(_) => DiffKind::Changed,
+            };
+            ColumnDiff {
+                name: column.name.clone(),
+                kind,
+                left: Some(column.clone()),
+                right: other.cloned(),
+            }
+        })
+        .collect();
+    diffs.extend(
+        right
+            .iter()
+            .filter(|column| !left.iter().any(|other| other.name == column.name))
+            .map(|column| ColumnDiff {
+                name: column.name.clone(),
+                kind: DiffKind::Added,
+                left: None,
+                right: Some(column.clone()),
+            }),
+    );
+    diffs
+}
+
+// Types compare case-insensitively; nullability only when both sides report it.
+fn columns_match(left: &ColumnMetadata, right: &ColumnMetadata) -> bool {
+    let nullable_matches = match (left.nullable, right.nullable) {
+        (Some(left), Some(right)) => left == right,
+        _ => true,
+    };
+    left.data_type.eq_ignore_ascii_case(&right.data_type) && nullable_matches
+}
+
+fn one_sided(columns: &[ColumnMetadata], kind: DiffKind) -> Vec<ColumnDiff> {
+    columns
+        .iter()
+        .map(|column| {
+            let side = Some(column.clone());
+            let (left, right) = if kind == DiffKind::Removed {
+                (side, None)
+            } else {
+                (None, side)
+            };
+            ColumnDiff {
+                name: column.name.clone(),
+                kind,
+                left,
+                right,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, nullable: Option<bool>) -> ColumnMetadata {
+        ColumnMetadata {
+            name: name.into(),
+            data_type: data_type.into(),
+            nullable,
+        }
+    }
+
+    fn snapshot(tables: &[(&str, Vec<ColumnMetadata>)]) -> SchemaSnapshot {
+        tables
+            .iter()
+            .map(|(name, columns)| (name.to_string(), columns.clone()))
+            .collect()
+    }
+
+    fn kinds(diffs: &[ColumnDiff]) -> Vec<(&str, DiffKind)> {
+        diffs
+            .iter()
+            .map(|diff| (diff.name.as_str(), diff.kind))
+            .collect()
+    }
+
+    #[test]
+    fn tables_added_removed_and_changed() {
+        let left = snapshot(&[
+            ("orders", vec![column("id", "integer", Some(false))]),
+            ("legacy", vec![column("id", "integer", Some(false))]),
+            ("users", vec![column("id", "integer", Some(false))]),
+        ]);
+        let right = snapshot(&[
+            ("orders", vec![column("id", "bigint", Some(false))]),
+            ("audit", vec![column("at", "timestamp", Some(true))]),
+            ("users", vec![column("id", "integer", Some(false))]),
+        ]);
+        let diffs = diff_schemas(&left, &right);
+        let tables = diffs
+            .iter()
+            .map(|table| (table.name.as_str(), table.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tables,
+            [
+                ("audit", DiffKind::Added),
+                ("legacy", DiffKind::Removed),
+                ("orders", DiffKind::Changed),
+                ("users", DiffKind::Unchanged),
+            ]
+        );
+        let audit = &diffs[0].columns[0];
+        assert_eq!(audit.kind, DiffKind::Added);
+        assert!(audit.left.is_none() && audit.right.is_some());
+        let legacy = &diffs[1].columns[0];
+        assert_eq!(legacy.kind, DiffKind::Removed);
+        assert!(legacy.left.is_some() && legacy.right.is_none());
+        assert_eq!(kinds(&diffs[2].columns), [("id", DiffKind::Changed)]);
+    }
+
+    #[test]
+    fn types_compare_case_insensitively() {
+        let left = [column("name", "VARCHAR(100)", Some(true))];
+        let right = [column("name", "varchar(100)", Some(true))];
+        assert_eq!(
+            kinds(&diff_columns(&left, &right)),
+            [("name", DiffKind::Unchanged)]
+        );
+        let right = [column("name", "varchar(120)", Some(true))];
+        assert_eq!(
+            kinds(&diff_columns(&left, &right)),
+            [("name", DiffKind::Changed)]
+        );
+    }
+
+    #[test]
+    fn nullability_only_counts_when_both_sides_know_it() {
+        let left = [
+            column("a", "int", Some(true)),
+            column("b", "int", None),
+            column("c", "int", Some(false)),
+        ];
+        let right = [
+            column("a", "int", Some(false)),
+            column("b", "int", Some(false)),
+            column("c", "int", None),
+        ];
+        assert_eq!(
+            kinds(&diff_columns(&left, &right)),
+            [
+                ("a", DiffKind::Changed),
+                ("b", DiffKind::Unchanged),
+                ("c", DiffKind::Unchanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_keep_left_order_then_right_only_columns() {
+        let left = [
+            column("id", "int", None),
+            column("gone", "int", None),
+            column("name", "text", None),
+        ];
+        let right = [
+            column("new_b", "int", None),
+            column("name", "text", None),
+            column("id", "int", None),
+            column("new_a", "int", None),
+        ];
+        assert_eq!(
+            kinds(&diff_columns(&left, &right)),
+            [
+                ("id", DiffKind::Unchanged),
+                ("gone", DiffKind::Removed),
+                ("name", DiffKind::Unchanged),
+                ("new_b", DiffKind::Added),
+                ("new_a", DiffKind::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn one_sided_tables_keep_column_order() {
+        let right = snapshot(&[(
+            "events",
+            vec![column("z", "int", None), column("a", "int", None)],
+        )]);
+        let diffs = diff_schemas(&SchemaSnapshot::new(), &right);
+        assert_eq!(
+            kinds(&diffs[0].columns),
+            [("z", DiffKind::Added), ("a", DiffKind::Added)]
+        );
+        assert!(diff_schemas(&SchemaSnapshot::new(), &SchemaSnapshot::new()).is_empty());
+    }
+}
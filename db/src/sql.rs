@@ -0,0 +1,1302 @@
+use std::ops::Range;
+
+use dbmiru_core::profiles::ProfileKind;
+
+pub fn quote_identifier(kind: ProfileKind, value: &str) -> String {
+    match kind {
+        ProfileKind::Postgres => format!("\"{}\"", value.replace('"', "\"\"")),
+        ProfileKind::MySql => format!("`{}`", value.replace('`', "``")),
+    }
+}
+
+pub fn qualified_table_name(kind: ProfileKind, schema: &str, table: &str) -> String {
+    format!(
+        "{}.{}",
+        quote_identifier(kind, schema),
+        quote_identifier(kind, table)
+    )
+}
+
+// MySQL strings take backslash escapes, `#` starts a comment, and backticks
+// quote identifiers; Postgres has E'' strings and dollar quoting instead.
+pub fn split_statements(kind: ProfileKind, sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mysql = kind == ProfileKind::MySql;
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut has_code = false;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'#' if mysql => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = skip_block_comment(bytes, idx);
+                continue;
+            }
+            b'\'' => {
+                has_code = true;
+                idx = skip_quoted(bytes, idx, b'\'', mysql || is_escape_string(bytes, idx));
+                continue;
+            }
+            b'"' => {
+                has_code = true;
+                idx = skip_quoted(bytes, idx, b'"', mysql);
+                continue;
+            }
+            b'`' if mysql => {
+                has_code = true;
+                idx = skip_quoted(bytes, idx, b'`', false);
+                continue;
+            }
+            b'$' if !mysql => {
+                has_code = true;
+                if let Some(tag_end) = dollar_tag_end(bytes, idx) {
+                    idx = skip_dollar_quoted(bytes, idx, tag_end);
+                    continue;
+                }
+            }
+            b';' => {
+                if has_code {
+                    statements.push(sql[start..idx].trim());
+                }
+                start = idx + 1;
+                has_code = false;
+            }
+            byte if !byte.is_ascii_whitespace() => has_code = true,
+            _ => {}
+        }
+        idx += 1;
+    }
+
+    if has_code {
+        statements.push(sql[start..].trim());
+    }
+    statements
+}
+
+// Byte range of the statement containing `offset`; a cursor between two
+// statements belongs to the one before it.
+pub fn statement_at(kind: ProfileKind, sql: &str, offset: usize) -> Option<Range<usize>> {
+    let base = sql.as_ptr() as usize;
+    let mut current = None;
+    for statement in split_statements(kind, sql) {
+        let start = statement.as_ptr() as usize - base;
+        if current.is_some() && start > offset {
+            break;
+        }
+        current = Some(start..start + statement.len());
+    }
+    current
+}
+
+pub fn parameter_count(kind: ProfileKind, sql: &str) -> usize {
+    let bytes = sql.as_bytes();
+    let mysql = kind == ProfileKind::MySql;
+    let mut count = 0;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'#' if mysql => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = skip_block_comment(bytes, idx);
+                continue;
+            }
+            b'\'' => {
+                idx = skip_quoted(bytes, idx, b'\'', mysql || is_escape_string(bytes, idx));
+                continue;
+            }
+            b'"' => {
+                idx = skip_quoted(bytes, idx, b'"', mysql);
+                continue;
+            }
+            b'`' if mysql => {
+                idx = skip_quoted(bytes, idx, b'`', false);
+                continue;
+            }
+            b'?' if mysql => count += 1,
+            b'$' if !mysql => {
+                if let Some(tag_end) = dollar_tag_end(bytes, idx) {
+                    idx = skip_dollar_quoted(bytes, idx, tag_end);
+                    continue;
+                }
+                let digits = bytes[idx + 1..]
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_digit())
+                    .count();
+                if digits > 0 && (idx == 0 || !is_identifier_byte(bytes[idx - 1])) {
+                    let number = sql[idx + 1..idx + 1 + digits].parse().unwrap_or(0);
+                    count = count.max(number);
+                    idx += 1 + digits;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    count
+}
+
+pub fn is_row_query(statement: &str) -> bool {
+    let keyword = leading_keyword(statement);
+    ["select", "with", "values", "table"]
+        .iter()
+        .any(|candidate| keyword.eq_ignore_ascii_case(candidate))
+}
+
+pub fn is_read_only_statement(statement: &str) -> bool {
+    let keyword = leading_keyword(statement);
+    [
+        "select", "with", "values", "table", "explain", "show", "describe", "desc",
+    ]
+    .iter()
+    .any(|candidate| keyword.eq_ignore_ascii_case(candidate))
+}
+
+// DROP, TRUNCATE, and DELETE/UPDATE without a top-level WHERE. A WHERE inside a
+// subquery or CTE body does not count, and WITH ... DELETE is judged by its
+// main statement.
+pub fn is_destructive_statement(kind: ProfileKind, statement: &str) -> bool {
+    let mut verb: Option<&str> = None;
+    let mut has_where = false;
+    for_each_word(kind, statement, |depth, word| {
+        if depth > 0 {
+            return;
+        }
+        match verb {
+            None => verb = Some(word),
+            Some(current) if current.eq_ignore_ascii_case("with") => {
+                if [
+                    "select", "insert", "update", "delete", "merge", "values", "table",
+                ]
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                {
+                    verb = Some(word);
+                }
+            }
+            Some(_) => has_where |= word.eq_ignore_ascii_case("where"),
+        }
+    });
+    let Some(verb) = verb else {
+        return false;
+    };
+    let is = |keyword: &str| verb.eq_ignore_ascii_case(keyword);
+    is("drop") || is("truncate") || ((is("delete") || is("update")) && !has_where)
+}
+
+pub const CAPPED_QUERY_PREFIX: &str = "SELECT * FROM (\n";
+
+// Caps a row query without its own LIMIT at `limit + 1` rows so the server
+// stops early. MySQL appends the LIMIT instead of wrapping because derived
+// tables reject duplicate column names (e.g. `SELECT *` over a join).
+pub fn cap_row_query(kind: ProfileKind, statement: &str, limit: usize) -> Option<String> {
+    if !is_row_query(statement) {
+        return None;
+    }
+    let mut capped = true;
+    for_each_word(kind, statement, |depth, word| {
+        let is_any = |keywords: &[&str]| {
+            keywords
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword))
+        };
+        // Data-modifying CTEs must stay at the top level.
+        if (depth == 0 && is_any(&["limit", "offset", "fetch", "into", "for", "lock"]))
+            || is_any(&["insert", "update", "delete", "merge"])
+        {
+            capped = false;
+        }
+    });
+    capped.then(|| match kind {
+        ProfileKind::Postgres => format!(
+            "{CAPPED_QUERY_PREFIX}{statement}\n) _capped LIMIT {}",
+            limit + 1
+        ),
+        ProfileKind::MySql => format!("{statement}\nLIMIT {}", limit + 1),
+    })
+}
+
+// Page `page` of a row query, fetching `limit + 1` rows so the extra one shows
+// whether another page follows. MySQL appends LIMIT/OFFSET for the same reason
+// `cap_row_query` does, so a statement that cannot be capped cannot be paged.
+pub fn page_query(kind: ProfileKind, statement: &str, limit: usize, page: usize) -> Option<String> {
+    let offset = page * limit;
+    match kind {
+        ProfileKind::Postgres => is_row_query(statement).then(|| {
+            format!(
+                "SELECT * FROM (\n{statement}\n) _p LIMIT {} OFFSET {offset}",
+                limit + 1
+            )
+        }),
+        ProfileKind::MySql => {
+            cap_row_query(kind, statement, limit).map(|capped| format!("{capped} OFFSET {offset}"))
+        }
+    }
+}
+
+pub fn statement_tag(statement: &str) -> String {
+    let keyword = leading_keyword(statement).to_ascii_uppercase();
+    if !matches!(keyword.as_str(), "CREATE" | "DROP" | "ALTER") {
+        return keyword;
+    }
+    let rest = statement.trim_start();
+    let rest = rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..].trim_start();
+    let mut words = rest.split_whitespace().map(str::to_ascii_uppercase);
+    match words.next() {
+        Some(word) if matches!(word.as_str(), "OR" | "UNIQUE" | "TEMP" | "TEMPORARY") => {
+            let object = words
+                .find(|word| !matches!(word.as_str(), "REPLACE" | "TEMP" | "TEMPORARY"))
+                .unwrap_or_default();
+            format!("{keyword} {object}")
+        }
+        Some(object) => format!("{keyword} {object}"),
+        None => keyword,
+    }
+}
+
+// Most rows one bulk INSERT statement carries; both servers cap a statement at
+// 65535 placeholders, so wide tables get fewer.
+pub fn insert_batch_rows(columns: usize) -> usize {
+    (u16::MAX as usize / columns.max(1)).clamp(1, 500)
+}
+
+// Multi-row INSERT with one placeholder per value. Postgres placeholders are
+// bound as text and cast to `column_types`, so any type with a text input works.
+pub fn insert_rows_statement(
+    kind: ProfileKind,
+    schema: &str,
+    table: &str,
+    columns: &[String],
+    column_types: &[String],
+    rows: usize,
+) -> String {
+    let names = columns
+        .iter()
+        .map(|column| quote_identifier(kind, column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut index = 0;
+    let values = (0..rows)
+        .map(|_| {
+            let placeholders = (0..columns.len())
+                .map(|column| {
+                    index += 1;
+                    match (kind, column_types.get(column)) {
+                        (ProfileKind::Postgres, Some(ty)) => format!("${index}::text::{ty}"),
+                        (ProfileKind::Postgres, None) => format!("${index}"),
+                        (ProfileKind::MySql, _) => "?".to_string(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("({placeholders})")
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!(
+        "INSERT INTO {} ({names}) VALUES\n{values}",
+        qualified_table_name(kind, schema, table)
+    )
+}
+
+// Words uppercased by `format_sql`. Only reserved words that cannot be plain
+// identifiers are listed, so table and column names keep their spelling.
+const FORMAT_KEYWORDS: &[&str] = &[
+    "all",
+    "alter",
+    "analyze",
+    "and",
+    "any",
+    "as",
+    "asc",
+    "begin",
+    "between",
+    "by",
+    "cascade",
+    "case",
+    "check",
+    "commit",
+    "conflict",
+    "constraint",
+    "create",
+    "cross",
+    "default",
+    "delete",
+    "desc",
+    "distinct",
+    "do",
+    "drop",
+    "else",
+    "end",
+    "except",
+    "exists",
+    "explain",
+    "false",
+    "fetch",
+    "filter",
+    "for",
+    "foreign",
+    "from",
+    "full",
+    "grant",
+    "group",
+    "having",
+    "ilike",
+    "in",
+    "inner",
+    "insert",
+    "intersect",
+    "into",
+    "is",
+    "join",
+    "key",
+    "lateral",
+    "left",
+    "like",
+    "limit",
+    "not",
+    "nothing",
+    "null",
+    "nulls",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "outer",
+    "over",
+    "partition",
+    "primary",
+    "recursive",
+    "references",
+    "returning",
+    "revoke",
+    "right",
+    "rollback",
+    "select",
+    "set",
+    "some",
+    "table",
+    "then",
+    "true",
+    "truncate",
+    "union",
+    "unique",
+    "update",
+    "using",
+    "values",
+    "view",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+// Words that start a new line at the current indentation in DML statements.
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "select",
+    "from",
+    "where",
+    "group",
+    "having",
+    "window",
+    "order",
+    "limit",
+    "offset",
+    "fetch",
+    "union",
+    "intersect",
+    "except",
+    "values",
+    "set",
+    "returning",
+    "insert",
+    "update",
+    "delete",
+    "with",
+    "join",
+    "left",
+    "right",
+    "full",
+    "inner",
+    "cross",
+];
+
+const JOIN_PREFIXES: &[&str] = &[
+    "left", "right", "full", "inner", "cross", "outer", "natural",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FormatToken<'a> {
+    Word(&'a str),
+    // String literals, quoted identifiers, numbers, and parameters.
+    Literal(&'a str),
+    LineComment(&'a str),
+    BlockComment(&'a str),
+    Operator(&'a str),
+    Open,
+    Close,
+    Comma,
+    Semicolon,
+    Dot,
+}
+
+impl FormatToken<'_> {
+    fn is_keyword(self, keywords: &[&str]) -> bool {
+        match self {
+            FormatToken::Word(word) => keywords
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword)),
+            _ => false,
+        }
+    }
+}
+
+// Pretty-prints SQL: reserved words uppercased, one clause per line, AND/OR
+// conditions and subqueries indented, one blank line between statements. Only
+// whitespace and keyword case change, and formatting the output again returns it
+// unchanged. Unterminated strings or comments and unbalanced parentheses are
+// reported instead of guessed at.
+pub fn format_sql(kind: ProfileKind, sql: &str) -> Result<String, String> {
+    let tokens = format_tokens(kind, sql)?;
+    let mut depth = 0usize;
+    for token in &tokens {
+        match token {
+            FormatToken::Open => depth += 1,
+            FormatToken::Close if depth == 0 => return Err("unbalanced parentheses".into()),
+            FormatToken::Close => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return Err("unbalanced parentheses".into());
+    }
+
+    const INDENT: &str = "  ";
+    let mut out = String::with_capacity(sql.len() + sql.len() / 4);
+    // One entry per open parenthesis: the indent to restore at `)` for
+    // subqueries, `None` for inline groups such as calls and IN lists.
+    let mut parens: Vec<Option<usize>> = Vec::new();
+    let mut indent = 0usize;
+    let mut breaks = false;
+    let mut statement_start = true;
+    let mut line_start = true;
+    let mut in_between = false;
+    let mut unary = false;
+    let mut prev: Option<FormatToken> = None;
+
+    for (idx, token) in tokens.iter().copied().enumerate() {
+        let next = tokens.get(idx + 1).copied();
+        let after = |keywords: &[&str]| prev.is_some_and(|prev| prev.is_keyword(keywords));
+        if statement_start && !matches!(token, FormatToken::LineComment(_)) {
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push_str("\n\n");
+                line_start = true;
+            }
+            breaks = token.is_keyword(&[
+                "select", "with", "insert", "update", "delete", "values", "table", "explain",
+            ]);
+            statement_start = false;
+        }
+        let condition = token.is_keyword(&["and", "or"]);
+        let clause = if condition {
+            !in_between
+        } else if token.is_keyword(CLAUSE_KEYWORDS) {
+            // Clause words used inside another construct stay on the line.
+            let embedded = (token.is_keyword(&["join"]) && after(JOIN_PREFIXES))
+                || (token.is_keyword(&["left", "right"]) && next == Some(FormatToken::Open))
+                || (token.is_keyword(&["update", "set"]) && after(&["do", "on", "key", "for"]))
+                || (token.is_keyword(&["from"]) && after(&["delete", "distinct"]))
+                || (token.is_keyword(&["group"]) && after(&["within"]))
+                // MySQL's VALUES(col) in ON DUPLICATE KEY UPDATE.
+                || (token.is_keyword(&["values"])
+                    && matches!(
+                        prev,
+                        Some(FormatToken::Operator(_) | FormatToken::Comma | FormatToken::Open)
+                    ));
+            !embedded
+        } else {
+            false
+        };
+        let new_line =
+            clause && breaks && prev.is_some() && !parens.last().is_some_and(Option::is_none);
+
+        if token == FormatToken::Close {
+            if let Some(Some(outer)) = parens.pop() {
+                indent = outer;
+                if !line_start {
+                    out.push('\n');
+                    line_start = true;
+                }
+            }
+        } else if new_line && !line_start {
+            out.push('\n');
+            line_start = true;
+        }
+        if line_start {
+            out.push_str(&INDENT.repeat(indent + usize::from(new_line && condition)));
+        } else {
+            let glued = unary
+                || matches!(
+                    token,
+                    FormatToken::Close
+                        | FormatToken::Comma
+                        | FormatToken::Semicolon
+                        | FormatToken::Dot
+                        | FormatToken::Operator("::" | "[" | "]")
+                )
+                || matches!(
+                    prev,
+                    Some(FormatToken::Open | FormatToken::Dot | FormatToken::Operator("::" | "["))
+                )
+                // Function calls, but not `IN (`, `AS (`, or `INSERT INTO t (`.
+                || (token == FormatToken::Open
+                    && matches!(prev, Some(FormatToken::Word(_) | FormatToken::Literal(_)))
+                    && (!after(FORMAT_KEYWORDS) || after(&["left", "right"]))
+                    && !(idx >= 2 && tokens[idx - 2].is_keyword(&["into", "table"])));
+            if !glued {
+                out.push(' ');
+            }
+        }
+        line_start = false;
+        unary = false;
+
+        match token {
+            FormatToken::Word(word) if token.is_keyword(FORMAT_KEYWORDS) => {
+                out.push_str(&word.to_ascii_uppercase());
+                if word.eq_ignore_ascii_case("between") {
+                    in_between = true;
+                } else if condition {
+                    in_between = false;
+                }
+            }
+            FormatToken::Operator(text @ ("-" | "+")) => {
+                out.push_str(text);
+                unary = !matches!(
+                    prev,
+                    Some(FormatToken::Word(_) | FormatToken::Literal(_) | FormatToken::Close)
+                ) || after(FORMAT_KEYWORDS);
+            }
+            FormatToken::Word(text)
+            | FormatToken::Literal(text)
+            | FormatToken::BlockComment(text)
+            | FormatToken::Operator(text) => out.push_str(text),
+            FormatToken::LineComment(text) => {
+                out.push_str(text);
+                out.push('\n');
+                line_start = true;
+            }
+            FormatToken::Open => {
+                out.push('(');
+                if breaks && next.is_some_and(|next| next.is_keyword(&["select", "with"])) {
+                    parens.push(Some(indent));
+                    indent += 1;
+                    out.push('\n');
+                    line_start = true;
+                } else {
+                    parens.push(None);
+                }
+            }
+            FormatToken::Close => out.push(')'),
+            FormatToken::Comma => out.push(','),
+            FormatToken::Dot => out.push('.'),
+            FormatToken::Semicolon => {
+                out.push(';');
+                indent = 0;
+                parens.clear();
+                in_between = false;
+                statement_start = true;
+            }
+        }
+        prev = Some(token);
+    }
+    Ok(out.trim_end().to_string())
+}
+
+fn format_tokens(kind: ProfileKind, sql: &str) -> Result<Vec<FormatToken<'_>>, String> {
+    let bytes = sql.as_bytes();
+    let mysql = kind == ProfileKind::MySql;
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let start = idx;
+        let byte = bytes[idx];
+        let token = match byte {
+            byte if byte.is_ascii_whitespace() => {
+                idx += 1;
+                continue;
+            }
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => {
+                idx = skip_line_comment(bytes, idx);
+                FormatToken::LineComment(sql[start..idx].trim_end())
+            }
+            b'#' if mysql => {
+                idx = skip_line_comment(bytes, idx);
+                FormatToken::LineComment(sql[start..idx].trim_end())
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = skip_block_comment(bytes, idx);
+                if !sql[start..idx].ends_with("*/") || idx - start < 4 {
+                    return Err("unterminated comment".into());
+                }
+                FormatToken::BlockComment(&sql[start..idx])
+            }
+            b'\'' | b'"' | b'`' => {
+                let escapes = match byte {
+                    b'\'' => mysql || is_escape_string(bytes, idx),
+                    b'"' => mysql,
+                    _ => false,
+                };
+                idx = quoted_end(bytes, idx, byte, escapes).ok_or("unterminated quoted text")?;
+                FormatToken::Literal(&sql[start..idx])
+            }
+            b'$' if !mysql => match dollar_tag_end(bytes, idx) {
+                Some(tag_end) => {
+                    idx = skip_dollar_quoted(bytes, idx, tag_end);
+                    let tag = &sql[start..=tag_end];
+                    if idx - start < tag.len() * 2 || !sql[start..idx].ends_with(tag) {
+                        return Err("unterminated dollar-quoted string".into());
+                    }
+                    FormatToken::Literal(&sql[start..idx])
+                }
+                None => {
+                    idx += 1;
+                    while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+                        idx += 1;
+                    }
+                    FormatToken::Literal(&sql[start..idx])
+                }
+            },
+            b'0'..=b'9' => {
+                idx = number_end(bytes, idx);
+                FormatToken::Literal(&sql[start..idx])
+            }
+            b'(' => {
+                idx += 1;
+                FormatToken::Open
+            }
+            b')' => {
+                idx += 1;
+                FormatToken::Close
+            }
+            b',' => {
+                idx += 1;
+                FormatToken::Comma
+            }
+            b';' => {
+                idx += 1;
+                FormatToken::Semicolon
+            }
+            b'.' => {
+                idx += 1;
+                FormatToken::Dot
+            }
+            b'[' | b']' => {
+                idx += 1;
+                FormatToken::Operator(&sql[start..idx])
+            }
+            byte if is_identifier_byte(byte) => {
+                while idx < bytes.len() && is_identifier_byte(bytes[idx]) {
+                    idx += 1;
+                }
+                // E'...', N'...', B'...', X'...' literals keep their prefix.
+                if idx - start == 1 && bytes.get(idx) == Some(&b'\'') {
+                    let escapes = mysql || is_escape_string(bytes, idx);
+                    idx =
+                        quoted_end(bytes, idx, b'\'', escapes).ok_or("unterminated quoted text")?;
+                    FormatToken::Literal(&sql[start..idx])
+                } else {
+                    FormatToken::Word(&sql[start..idx])
+                }
+            }
+            _ => {
+                idx += sql[idx..].chars().next().map_or(1, char::len_utf8);
+                while idx < bytes.len()
+                    && b"+-*/<>=~!@#%^&|?:".contains(&bytes[idx])
+                    && !matches!(&bytes[idx..], [b'-', b'-', ..] | [b'/', b'*', ..])
+                    && !(mysql && bytes[idx] == b'#')
+                {
+                    idx += 1;
+                }
+                FormatToken::Operator(&sql[start..idx])
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+// Like `skip_quoted`, but `None` when the closing quote is missing.
+fn quoted_end(bytes: &[u8], start: usize, quote: u8, backslash_escapes: bool) -> Option<usize> {
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        if backslash_escapes && bytes[idx] == b'\\' {
+            idx += 2;
+            continue;
+        }
+        if bytes[idx] == quote {
+            if bytes.get(idx + 1) == Some(&quote) {
+                idx += 2;
+                continue;
+            }
+            return Some(idx + 1);
+        }
+        idx += 1;
+    }
+    None
+}
+
+fn number_end(bytes: &[u8], start: usize) -> usize {
+    let mut idx = start;
+    let digits = |idx: &mut usize| {
+        while *idx < bytes.len() && (bytes[*idx].is_ascii_digit() || bytes[*idx] == b'_') {
+            *idx += 1;
+        }
+    };
+    digits(&mut idx);
+    if bytes.get(idx) == Some(&b'.') && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit) {
+        idx += 1;
+        digits(&mut idx);
+    }
+    if matches!(bytes.get(idx), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(idx + 1), Some(b'+' | b'-')));
+        if bytes.get(idx + 1 + sign).is_some_and(u8::is_ascii_digit) {
+            idx += 1 + sign;
+            digits(&mut idx);
+        }
+    }
+    // Hex and other suffixed forms (0x1F) stay one token.
+    while idx < bytes.len() && is_identifier_byte(bytes[idx]) {
+        idx += 1;
+    }
+    idx
+}
+
+pub fn single_source_table(statement: &str) -> Option<&str> {
+    if !leading_keyword(statement).eq_ignore_ascii_case("select") {
+        return None;
+    }
+    let bytes = statement.as_bytes();
+    let mut depth = 0usize;
+    let mut source = None;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = skip_block_comment(bytes, idx);
+                continue;
+            }
+            b'\'' => {
+                idx = skip_quoted(bytes, idx, b'\'', is_escape_string(bytes, idx));
+                continue;
+            }
+            b'"' | b'`' => {
+                idx = skip_quoted(bytes, idx, bytes[idx], false);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 && source.is_some() => return None,
+            byte if is_identifier_byte(byte) => {
+                let end = bytes[idx..]
+                    .iter()
+                    .position(|byte| !is_identifier_byte(*byte))
+                    .map(|offset| idx + offset)
+                    .unwrap_or(bytes.len());
+                let word = &statement[idx..end];
+                if depth == 0 {
+                    if word.eq_ignore_ascii_case("join") {
+                        return None;
+                    }
+                    if source.is_none() && word.eq_ignore_ascii_case("from") {
+                        let (table, table_end) = table_reference(statement, end)?;
+                        source = Some(table);
+                        idx = table_end;
+                        continue;
+                    }
+                    if source.is_some()
+                        && ["where", "group", "order", "limit", "having", "union"]
+                            .iter()
+                            .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                    {
+                        break;
+                    }
+                }
+                idx = end;
+                continue;
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    source
+}
+
+fn table_reference(statement: &str, start: usize) -> Option<(&str, usize)> {
+    let bytes = statement.as_bytes();
+    let mut idx = start;
+    while idx < bytes.len() && bytes[idx].is_ascii_whitespace() {
+        idx += 1;
+    }
+    let begin = idx;
+    loop {
+        match bytes.get(idx) {
+            Some(b'"') | Some(b'`') => idx = skip_quoted(bytes, idx, bytes[idx], false),
+            Some(byte) if is_identifier_byte(*byte) => {
+                while idx < bytes.len() && is_identifier_byte(bytes[idx]) {
+                    idx += 1;
+                }
+            }
+            _ => return None,
+        }
+        if bytes.get(idx) != Some(&b'.') {
+            break;
+        }
+        idx += 1;
+    }
+    Some((&statement[begin..idx], idx))
+}
+
+fn for_each_word<'a>(kind: ProfileKind, statement: &'a str, mut visit: impl FnMut(usize, &'a str)) {
+    let bytes = statement.as_bytes();
+    let mysql = kind == ProfileKind::MySql;
+    let mut depth = 0usize;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'#' if mysql => {
+                idx = skip_line_comment(bytes, idx);
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = skip_block_comment(bytes, idx);
+                continue;
+            }
+            b'\'' => {
+                idx = skip_quoted(bytes, idx, b'\'', mysql || is_escape_string(bytes, idx));
+                continue;
+            }
+            b'"' => {
+                idx = skip_quoted(bytes, idx, b'"', mysql);
+                continue;
+            }
+            b'`' => {
+                idx = skip_quoted(bytes, idx, b'`', false);
+                continue;
+            }
+            b'$' if !mysql => {
+                if let Some(tag_end) = dollar_tag_end(bytes, idx) {
+                    idx = skip_dollar_quoted(bytes, idx, tag_end);
+                    continue;
+                }
+            }
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            byte if is_identifier_byte(byte) => {
+                let end = bytes[idx..]
+                    .iter()
+                    .position(|byte| !is_identifier_byte(*byte))
+                    .map(|offset| idx + offset)
+                    .unwrap_or(bytes.len());
+                visit(depth, &statement[idx..end]);
+                idx = end;
+                continue;
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+}
+
+// `#` is skipped as a MySQL comment for both dialects: no Postgres statement
+// starts with it.
+fn leading_keyword(sql: &str) -> &str {
+    let bytes = sql.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => idx = skip_line_comment(bytes, idx),
+            b'#' => idx = skip_line_comment(bytes, idx),
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => idx = skip_block_comment(bytes, idx),
+            b'(' => idx += 1,
+            byte if byte.is_ascii_whitespace() => idx += 1,
+            _ => break,
+        }
+    }
+    let end = bytes[idx..]
+        .iter()
+        .position(|byte| !is_identifier_byte(*byte))
+        .map(|offset| idx + offset)
+        .unwrap_or(bytes.len());
+    &sql[idx..end]
+}
+
+fn skip_line_comment(bytes: &[u8], start: usize) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map(|offset| start + offset + 1)
+        .unwrap_or(bytes.len())
+}
+
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut idx = start;
+    while idx < bytes.len() {
+        if bytes[idx] == b'/' && bytes.get(idx + 1) == Some(&b'*') {
+            depth += 1;
+            idx += 2;
+        } else if bytes[idx] == b'*' && bytes.get(idx + 1) == Some(&b'/') {
+            depth -= 1;
+            idx += 2;
+            if depth == 0 {
+                return idx;
+            }
+        } else {
+            idx += 1;
+        }
+    }
+    bytes.len()
+}
+
+fn is_escape_string(bytes: &[u8], quote: usize) -> bool {
+    quote > 0
+        && matches!(bytes[quote - 1], b'E' | b'e')
+        && (quote < 2 || !is_identifier_byte(bytes[quote - 2]))
+}
+
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, backslash_escapes: bool) -> usize {
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        if backslash_escapes && bytes[idx] == b'\\' {
+            idx += 2;
+            continue;
+        }
+        if bytes[idx] == quote {
+            if bytes.get(idx + 1) == Some(&quote) {
+                idx += 2;
+                continue;
+            }
+            return idx + 1;
+        }
+        idx += 1;
+    }
+    bytes.len()
+}
+
+fn dollar_tag_end(bytes: &[u8], start: usize) -> Option<usize> {
+    if start > 0 && is_identifier_byte(bytes[start - 1]) {
+        return None;
+    }
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'$' => return Some(idx),
+            byte if byte.is_ascii_digit() && idx == start + 1 => return None,
+            byte if is_identifier_byte(byte) => idx += 1,
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn skip_dollar_quoted(bytes: &[u8], start: usize, tag_end: usize) -> usize {
+    let tag = &bytes[start..=tag_end];
+    let body_start = tag_end + 1;
+    bytes[body_start..]
+        .windows(tag.len())
+        .position(|window| window == tag)
+        .map(|offset| body_start + offset + tag.len())
+        .unwrap_or(bytes.len())
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMAT_CORPUS: &[&str] = &[
+        "with recent as (select id, total from orders where created_at > now() - interval '7 days'), big as (select * from recent where total > 100) select r.id, b.total from recent r join big b on b.id = r.id",
+        "select o.id, c.name from orders o left outer join customers c on c.id = o.customer_id inner join items i using (order_id) cross join lateral (select 1) x where o.total > 10 and (c.name like 'A%' or c.name is null) order by o.id desc limit 10 offset 5",
+        "select id, case when total > 100 then 'big' when total > 10 then 'medium' else 'small' end as size from orders",
+        "select customer_id, count(*) filter (where total > 100) as big, sum(total) over (partition by customer_id order by created_at rows between unbounded preceding and current row) from orders group by customer_id, total, created_at having count(*) > 1",
+        "create function add_one(i int) returns int as $$ begin\n  return i + 1; -- keep; this\nend; $$ language plpgsql",
+        "select $tag$ it's; $$ nested $tag$, 'a''b', E'\\';', \"Mixed Case\" from t",
+        "-- leading comment\nselect 1 /* inline; comment */ , 2 -- trailing\n; /* between */ select 3;",
+        "insert into t (a, b) values (1, 'x'), (2, 'y') on conflict (a) do update set b = excluded.b returning *; update t set b = null where a in (select a from u); delete from t where a = any($1)",
+        "select * from a where exists (select 1 from b where b.id = a.id and b.x between 1 and 2) union all select * from c",
+    ];
+
+    #[test]
+    fn format_sql_is_idempotent() {
+        for kind in [ProfileKind::Postgres, ProfileKind::MySql] {
+            for sql in FORMAT_CORPUS {
+                let once = format_sql(kind, sql).unwrap();
+                let twice = format_sql(kind, &once).unwrap();
+                assert_eq!(once, twice, "{sql}");
+            }
+        }
+        let mysql = "select `order`, \"it\\\"s\" from t # note; here\nwhere a = 'x\\'y'";
+        let once = format_sql(ProfileKind::MySql, mysql).unwrap();
+        assert_eq!(format_sql(ProfileKind::MySql, &once).unwrap(), once);
+    }
+
+    #[test]
+    fn format_sql_only_changes_whitespace_and_keyword_case() {
+        let squash = |sql: &str| {
+            sql.chars()
+                .filter(|ch| !ch.is_whitespace())
+                .collect::<String>()
+                .to_lowercase()
+        };
+        for sql in FORMAT_CORPUS {
+            let formatted = format_sql(ProfileKind::Postgres, sql).unwrap();
+            assert_eq!(squash(&formatted), squash(sql), "{sql}");
+        }
+    }
+
+    #[test]
+    fn format_sql_keeps_quoted_text_verbatim() {
+        let formatted = format_sql(
+            ProfileKind::Postgres,
+            "select 'select  from', \"Where\" from t where x = $$ and   or $$",
+        )
+        .unwrap();
+        assert!(formatted.contains("'select  from'"));
+        assert!(formatted.contains("\"Where\""));
+        assert!(formatted.contains("$$ and   or $$"));
+        assert!(formatted.starts_with("SELECT"));
+    }
+
+    #[test]
+    fn format_sql_separates_statements() {
+        assert_eq!(
+            format_sql(ProfileKind::Postgres, "select 1; select 2;").unwrap(),
+            "SELECT 1;\n\nSELECT 2;"
+        );
+    }
+
+    #[test]
+    fn format_sql_rejects_unbalanced_input() {
+        for sql in [
+            "select (1",
+            "select 1)",
+            "select ((1)",
+            "select count(*)) from t",
+        ] {
+            assert_eq!(
+                format_sql(ProfileKind::Postgres, sql),
+                Err("unbalanced parentheses".into()),
+                "{sql}"
+            );
+        }
+        for sql in ["select 'open", "select \"open", r"select E'x\'"] {
+            assert_eq!(
+                format_sql(ProfileKind::Postgres, sql),
+                Err("unterminated quoted text".into()),
+                "{sql}"
+            );
+        }
+        for sql in ["select $$ open", "select $a$ x $b$"] {
+            assert_eq!(
+                format_sql(ProfileKind::Postgres, sql),
+                Err("unterminated dollar-quoted string".into()),
+                "{sql}"
+            );
+        }
+        assert_eq!(
+            format_sql(ProfileKind::Postgres, "select 1 /* open"),
+            Err("unterminated comment".into())
+        );
+        assert_eq!(
+            format_sql(ProfileKind::MySql, "select `open"),
+            Err("unterminated quoted text".into())
+        );
+        // A quote escaped with a backslash leaves a MySQL string open.
+        assert_eq!(
+            format_sql(ProfileKind::MySql, "select 'x\\'"),
+            Err("unterminated quoted text".into())
+        );
+    }
+
+    fn split(sql: &str) -> Vec<&str> {
+        split_statements(ProfileKind::Postgres, sql)
+    }
+
+    #[test]
+    fn split_statements_keeps_semicolons_in_strings() {
+        assert_eq!(split("SELECT ';'; SELECT 2"), ["SELECT ';'", "SELECT 2"]);
+        assert_eq!(split("SELECT 'it''s; ok'"), ["SELECT 'it''s; ok'"]);
+        assert_eq!(
+            split(r"SELECT E'\';'; SELECT 2"),
+            [r"SELECT E'\';'", "SELECT 2"]
+        );
+        // Outside E'' a backslash is an ordinary character.
+        assert_eq!(split(r"SELECT '\'; SELECT 2"), [r"SELECT '\'", "SELECT 2"]);
+    }
+
+    #[test]
+    fn split_statements_keeps_dollar_quoted_bodies() {
+        assert_eq!(
+            split("SELECT $$;$$; SELECT 2"),
+            ["SELECT $$;$$", "SELECT 2"]
+        );
+        let function = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN; RETURN 1; END $body$ LANGUAGE plpgsql";
+        assert_eq!(
+            split(&format!("{function};\nSELECT f()")),
+            [function, "SELECT f()"]
+        );
+        assert_eq!(
+            split("SELECT $a$ $b$; $b$ $a$; SELECT 2"),
+            ["SELECT $a$ $b$; $b$ $a$", "SELECT 2"]
+        );
+        // Positional parameters are not dollar quotes.
+        assert_eq!(split("SELECT $1; SELECT $2"), ["SELECT $1", "SELECT $2"]);
+    }
+
+    #[test]
+    fn split_statements_skips_comments() {
+        assert_eq!(
+            split("SELECT 1 -- not; here\n; SELECT 2"),
+            ["SELECT 1 -- not; here", "SELECT 2"]
+        );
+        assert_eq!(
+            split("SELECT /* a; /* nested; */ b; */ 1; SELECT 2"),
+            ["SELECT /* a; /* nested; */ b; */ 1", "SELECT 2"]
+        );
+        assert_eq!(
+            split("-- only a comment;\n/* and; another */"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn split_statements_keeps_quoted_identifiers() {
+        assert_eq!(
+            split(r#"SELECT "a;b", "c""d;" FROM t; SELECT 2"#),
+            [r#"SELECT "a;b", "c""d;" FROM t"#, "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn split_statements_drops_empty_statements() {
+        assert_eq!(split("SELECT 1;"), ["SELECT 1"]);
+        assert_eq!(
+            split(";;  SELECT 1 ;; ;\n SELECT 2"),
+            ["SELECT 1", "SELECT 2"]
+        );
+        assert_eq!(split("  \n "), Vec::<&str>::new());
+        assert_eq!(split("SELECT 1; SELECT 2 "), ["SELECT 1", "SELECT 2"]);
+    }
+
+    #[test]
+    fn statement_at_picks_statement_under_cursor() {
+        let sql = "SELECT 1;\n\nSELECT 2;";
+        assert_eq!(statement_at(ProfileKind::Postgres, sql, 0), Some(0..8));
+        assert_eq!(statement_at(ProfileKind::Postgres, sql, 9), Some(0..8));
+        assert_eq!(statement_at(ProfileKind::Postgres, sql, 12), Some(11..19));
+        assert_eq!(statement_at(ProfileKind::Postgres, "", 0), None);
+    }
+
+    #[test]
+    fn split_statements_mysql_backslash_escapes() {
+        let sql = r#"SELECT 'it\'s; fine'; SELECT "a\";b"; SELECT 3"#;
+        assert_eq!(
+            split_statements(ProfileKind::MySql, sql),
+            [r"SELECT 'it\'s; fine'", r#"SELECT "a\";b""#, "SELECT 3"]
+        );
+    }
+
+    #[test]
+    fn split_statements_mysql_backticks_and_hash_comments() {
+        let sql = "SELECT `a;b` FROM t; # not; a statement\nSELECT 2 # trailing;\n";
+        assert_eq!(
+            split_statements(ProfileKind::MySql, sql),
+            [
+                "SELECT `a;b` FROM t",
+                "# not; a statement\nSELECT 2 # trailing;"
+            ]
+        );
+        assert_eq!(
+            split_statements(ProfileKind::MySql, "# only a comment; here\n"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn split_statements_mysql_ignores_dollar_quotes() {
+        assert_eq!(
+            split_statements(ProfileKind::MySql, "SELECT $$; SELECT $$"),
+            ["SELECT $$", "SELECT $$"]
+        );
+    }
+
+    #[test]
+    fn statement_at_uses_dialect() {
+        let sql = "SELECT 'a\\';b'; SELECT 2";
+        assert_eq!(statement_at(ProfileKind::MySql, sql, 20), Some(16..24));
+        assert_eq!(statement_at(ProfileKind::Postgres, sql, 20), Some(12..24));
+    }
+
+    #[test]
+    fn destructive_check_sees_past_mysql_escapes() {
+        let sql = r"DELETE FROM t WHERE note = 'it\'s'";
+        assert!(!is_destructive_statement(ProfileKind::MySql, sql));
+        let sql = "DELETE FROM t # WHERE id = 1";
+        assert!(is_destructive_statement(ProfileKind::MySql, sql));
+        assert!(is_read_only_statement("# comment\nSELECT 1"));
+    }
+
+    #[test]
+    fn page_query_appends_limit_offset_on_mysql() {
+        let sql = "SELECT * FROM a JOIN b USING (id)";
+        assert_eq!(
+            page_query(ProfileKind::MySql, sql, 50, 2).as_deref(),
+            Some("SELECT * FROM a JOIN b USING (id)\nLIMIT 51 OFFSET 100")
+        );
+        assert_eq!(
+            page_query(ProfileKind::Postgres, sql, 50, 2).as_deref(),
+            Some("SELECT * FROM (\nSELECT * FROM a JOIN b USING (id)\n) _p LIMIT 51 OFFSET 100")
+        );
+        assert_eq!(
+            page_query(ProfileKind::MySql, "SELECT 1 LIMIT 5", 50, 0),
+            None
+        );
+        assert_eq!(
+            page_query(ProfileKind::MySql, "SELECT * FROM t FOR UPDATE", 50, 0),
+            None
+        );
+        assert_eq!(
+            page_query(ProfileKind::Postgres, "DELETE FROM t", 50, 0),
+            None
+        );
+    }
+}
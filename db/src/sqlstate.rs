@@ -0,0 +1,236 @@
+pub struct SqlStateInfo {
+    pub name: &'static str,
+    pub explanation: &'static str,
+}
+
+const CODES: &[(&str, &str, &str)] = &[
+    (
+        "23505",
+        "unique_violation",
+        "Another row already has this value in a primary key or UNIQUE column.",
+    ),
+    (
+        "23503",
+        "foreign_key_violation",
+        "The value does not exist in the referenced table, or other rows still reference this row.",
+    ),
+    (
+        "23502",
+        "not_null_violation",
+        "A NOT NULL column was given no value or NULL.",
+    ),
+    (
+        "23514",
+        "check_violation",
+        "The row does not satisfy a CHECK constraint on the table.",
+    ),
+    (
+        "23P01",
+        "exclusion_violation",
+        "The row conflicts with an existing row under an exclusion constraint.",
+    ),
+    (
+        "42P01",
+        "undefined_table",
+        "The table or view does not exist, or is not in the search path; check the schema prefix and spelling.",
+    ),
+    (
+        "42703",
+        "undefined_column",
+        "The column does not exist in the tables the query reads; check the spelling and the table alias.",
+    ),
+    (
+        "42883",
+        "undefined_function",
+        "No function matches this name and argument types; an explicit cast may be needed.",
+    ),
+    ("3F000", "invalid_schema_name", "The schema does not exist."),
+    (
+        "42601",
+        "syntax_error",
+        "The SQL could not be parsed; look near the marked position.",
+    ),
+    (
+        "42501",
+        "insufficient_privilege",
+        "The current user lacks permission for this object or operation.",
+    ),
+    (
+        "42P07",
+        "duplicate_table",
+        "A table or other relation with this name already exists.",
+    ),
+    (
+        "42701",
+        "duplicate_column",
+        "A column with this name already exists in the table.",
+    ),
+    (
+        "42702",
+        "ambiguous_column",
+        "More than one table in the query has a column with this name; qualify it with a table name or alias.",
+    ),
+    (
+        "42804",
+        "datatype_mismatch",
+        "The value's type does not match what the column or expression expects.",
+    ),
+    (
+        "42803",
+        "grouping_error",
+        "A selected column must appear in GROUP BY or be used inside an aggregate function.",
+    ),
+    (
+        "22P02",
+        "invalid_text_representation",
+        "A value could not be converted to the column's type, e.g. text into an integer or UUID.",
+    ),
+    (
+        "22001",
+        "string_data_right_truncation",
+        "The value is longer than the column allows.",
+    ),
+    (
+        "22003",
+        "numeric_value_out_of_range",
+        "The number is too large or too small for the column's type.",
+    ),
+    ("22012", "division_by_zero", "The query divided by zero."),
+    (
+        "22007",
+        "invalid_datetime_format",
+        "A date or time value is not in a format the server understands.",
+    ),
+    (
+        "22008",
+        "datetime_field_overflow",
+        "A date or time field is out of range, e.g. month 13.",
+    ),
+    (
+        "25P02",
+        "in_failed_sql_transaction",
+        "An earlier statement in this transaction failed; roll back before running more statements.",
+    ),
+    (
+        "25006",
+        "read_only_sql_transaction",
+        "The transaction or server is read-only, so data cannot be modified.",
+    ),
+    (
+        "40001",
+        "serialization_failure",
+        "The transaction conflicted with a concurrent one; retrying it usually succeeds.",
+    ),
+    (
+        "40P01",
+        "deadlock_detected",
+        "Two transactions were waiting on each other's locks and this one was aborted; retry it.",
+    ),
+    (
+        "55P03",
+        "lock_not_available",
+        "A lock could not be acquired right away (NOWAIT or lock_timeout).",
+    ),
+    (
+        "57014",
+        "query_canceled",
+        "The query was cancelled by the user or by statement_timeout.",
+    ),
+    ("53100", "disk_full", "The server ran out of disk space."),
+    (
+        "23000",
+        "integrity_constraint_violation",
+        "The row breaks a constraint, such as a duplicate key or a missing foreign key reference.",
+    ),
+    (
+        "42S02",
+        "table_not_found",
+        "The table or view does not exist; check the database prefix and spelling.",
+    ),
+    (
+        "42S22",
+        "column_not_found",
+        "The column does not exist in the tables the query reads; check the spelling and the table alias.",
+    ),
+    (
+        "42S01",
+        "table_already_exists",
+        "A table with this name already exists.",
+    ),
+    (
+        "42000",
+        "syntax_error_or_access_rule_violation",
+        "The SQL could not be parsed, or the current user lacks permission for it.",
+    ),
+];
+
+const CLASSES: &[(&str, &str, &str)] = &[
+    (
+        "08",
+        "connection_exception",
+        "The connection to the server failed or was lost.",
+    ),
+    (
+        "0A",
+        "feature_not_supported",
+        "The server does not support this feature.",
+    ),
+    (
+        "22",
+        "data_exception",
+        "A value is invalid for its type or out of range.",
+    ),
+    (
+        "23",
+        "integrity_constraint_violation",
+        "The row breaks a constraint on the table.",
+    ),
+    (
+        "25",
+        "invalid_transaction_state",
+        "The statement is not allowed in the current transaction state.",
+    ),
+    (
+        "28",
+        "invalid_authorization_specification",
+        "The user could not be authenticated.",
+    ),
+    (
+        "40",
+        "transaction_rollback",
+        "The transaction was rolled back; retrying it may succeed.",
+    ),
+    (
+        "42",
+        "syntax_error_or_access_rule_violation",
+        "The SQL is invalid, refers to something that does not exist, or is not permitted.",
+    ),
+    (
+        "53",
+        "insufficient_resources",
+        "The server ran out of a resource such as memory, disk, or connections.",
+    ),
+    (
+        "54",
+        "program_limit_exceeded",
+        "The statement exceeds a server limit, e.g. too many columns or too complex.",
+    ),
+    (
+        "57",
+        "operator_intervention",
+        "The query was cancelled or the server is shutting down.",
+    ),
+];
+
+// Looks up the exact code first, then its two-character class.
+pub fn describe(code: &str) -> Option<SqlStateInfo> {
+    let code = code.to_ascii_uppercase();
+    CODES
+        .iter()
+        .find(|(known, _, _)| *known == code)
+        .or_else(|| {
+            let class = code.get(..2)?;
+            CLASSES.iter().find(|(known, _, _)| *known == class)
+        })
+        .map(|(_, name, explanation)| SqlStateInfo { name, explanation })
+}
@@ -0,0 +1,126 @@
+use std::{
+    net::{Ipv4Addr, TcpListener},
+    process::Stdio,
+    time::Duration,
+};
+
+use dbmiru_core::profiles::SshTunnel;
+use tokio::{
+    io::AsyncReadExt,
+    net::TcpStream,
+    process::{Child, Command},
+    time::{Instant, sleep},
+};
+
+use crate::ConnectionError;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(crate) struct SshForward {
+    child: Child,
+    local_port: u16,
+}
+
+impl SshForward {
+    pub(crate) async fn open(
+        tunnel: &SshTunnel,
+        target_host: &str,
+        target_port: u16,
+        timeout: Duration,
+    ) -> Result<Self, ConnectionError> {
+        let local_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .map_err(|err| {
+                ConnectionError::new(
+                    "Failed to reserve a local port for the SSH tunnel.",
+                    err.to_string(),
+                )
+            })?;
+
+        let mut command = Command::new("ssh");
+        command
+            .arg("-N")
+            .args(["-o", "BatchMode=yes"])
+            .args(["-o", "ExitOnForwardFailure=yes"])
+            .args(["-o", "LogLevel=ERROR"])
+            .args([
+                "-o",
+                &format!("ConnectTimeout={}", timeout.as_secs().max(1)),
+            ])
+            .args(["-p", &tunnel.port.to_string()])
+            .args([
+                "-L",
+                &format!(
+                    "{}:{local_port}:{target_host}:{target_port}",
+                    Ipv4Addr::LOCALHOST
+                ),
+            ]);
+        if let Some(key_path) = tunnel.key_path.as_deref().filter(|path| !path.is_empty()) {
+            command
+                .args(["-i", key_path])
+                .args(["-o", "IdentitiesOnly=yes"]);
+        }
+        // `--` keeps a destination starting with `-` from being read as an option.
+        command
+            .arg("--")
+            .arg(tunnel.destination())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let child = command.spawn().map_err(|err| {
+            ConnectionError::new(
+                "Could not start the ssh client for the tunnel.",
+                err.to_string(),
+            )
+        })?;
+        let mut forward = Self { child, local_port };
+        forward.wait_until_ready(timeout).await?;
+        Ok(forward)
+    }
+
+    pub(crate) fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    async fn wait_until_ready(&mut self, timeout: Duration) -> Result<(), ConnectionError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.child.try_wait() {
+                Ok(Some(status)) => {
+                    let mut stderr = String::new();
+                    if let Some(mut pipe) = self.child.stderr.take() {
+                        let _ = pipe.read_to_string(&mut stderr).await;
+                    }
+                    let detail = match stderr.trim() {
+                        "" => format!("ssh exited with {status}"),
+                        message => message.to_string(),
+                    };
+                    return Err(ConnectionError::new("SSH tunnel failed.", detail));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    return Err(ConnectionError::new("SSH tunnel failed.", err.to_string()));
+                }
+            }
+            if TcpStream::connect((Ipv4Addr::LOCALHOST, self.local_port))
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(ConnectionError::new(
+                    "SSH tunnel timed out.",
+                    format!(
+                        "the forwarded port was not ready within {} s",
+                        timeout.as_secs()
+                    ),
+                ));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
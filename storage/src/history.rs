@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use dbmiru_core::{Result, profiles::ProfileId};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_HISTORY_ENTRIES: usize = 500;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub profile_id: ProfileId,
+    pub sql: String,
+    pub executed_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(config_dir: &Path) -> Self {
+        let path = config_dir.join("history.json");
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => {
+                let entries: Vec<HistoryEntry> = serde_json::from_str(&contents)?;
+                Ok(entries)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(entries)?;
+        fs::write(&self.path, serialized)?;
+        Ok(())
+    }
+
+    pub fn append(
+        &self,
+        entries: &mut Vec<HistoryEntry>,
+        profile_id: ProfileId,
+        sql: &str,
+    ) -> Result<()> {
+        let sql = sql.trim();
+        let previous = entries
+            .iter()
+            .rev()
+            .find(|entry| entry.profile_id == profile_id);
+        if sql.is_empty() || previous.is_some_and(|entry| entry.sql == sql) {
+            return Ok(());
+        }
+        entries.push(HistoryEntry {
+            profile_id,
+            sql: sql.to_string(),
+            executed_at: Utc::now(),
+        });
+        if entries.len() > MAX_HISTORY_ENTRIES {
+            let excess = entries.len() - MAX_HISTORY_ENTRIES;
+            entries.drain(..excess);
+        }
+        self.save(entries)
+    }
+}
@@ -1,5 +1,15 @@
+pub mod history;
 pub mod profiles;
 pub mod secrets;
+pub mod session;
+pub mod settings;
+pub mod snippets;
+pub mod window;
 
-pub use profiles::ProfileStore;
+pub use history::{HistoryEntry, HistoryStore};
+pub use profiles::{ProfileImport, ProfileStore};
 pub use secrets::SecretStore;
+pub use session::{SessionState, SessionStore, SessionTab};
+pub use settings::{RowDensity, Settings, SettingsStore, ThemeMode};
+pub use snippets::{Snippet, SnippetId, SnippetStore};
+pub use window::{WindowState, WindowStateStore};
@@ -4,6 +4,14 @@ use std::{
 };
 
 use dbmiru_core::{Result, profiles::ConnectionProfile};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Default)]
+pub struct ProfileImport {
+    pub imported: usize,
+    pub errors: Vec<String>,
+}
 
 #[derive(Clone, Debug)]
 pub struct ProfileStore {
@@ -32,4 +40,117 @@ impl ProfileStore {
         fs::write(&self.path, serialized)?;
         Ok(())
     }
+
+    pub fn export(&self, profiles: &[ConnectionProfile], path: &Path) -> Result<usize> {
+        let exported: Vec<ConnectionProfile> = profiles
+            .iter()
+            .cloned()
+            .map(|mut profile| {
+                profile.remember_password = false;
+                profile.last_schema = None;
+                profile.last_table = None;
+                profile
+            })
+            .collect();
+        fs::write(path, serde_json::to_string_pretty(&exported)?)?;
+        Ok(exported.len())
+    }
+
+    pub fn import(
+        &self,
+        profiles: &mut Vec<ConnectionProfile>,
+        path: &Path,
+    ) -> Result<ProfileImport> {
+        let contents = fs::read_to_string(path)?;
+        let entries: Vec<Value> = serde_json::from_str(&contents)?;
+        let mut import = ProfileImport::default();
+        for (index, mut entry) in entries.into_iter().enumerate() {
+            if let Some(object) = entry.as_object_mut() {
+                object.insert("id".into(), Value::String(Uuid::new_v4().to_string()));
+            }
+            match serde_json::from_value::<ConnectionProfile>(entry) {
+                Ok(profile) if profile.name.trim().is_empty() || profile.host.trim().is_empty() => {
+                    import
+                        .errors
+                        .push(format!("profile {}: name and host are required", index + 1));
+                }
+                Ok(profile) if let Some(field) = option_like_field(&profile) => {
+                    import.errors.push(format!(
+                        "profile {}: {field} must not start with \"-\"",
+                        index + 1
+                    ));
+                }
+                Ok(mut profile) => {
+                    profile.remember_password = false;
+                    profiles.push(profile);
+                    import.imported += 1;
+                }
+                Err(err) => import.errors.push(format!("profile {}: {err}", index + 1)),
+            }
+        }
+        if import.imported > 0 {
+            self.save(profiles)?;
+        }
+        Ok(import)
+    }
+}
+
+// Imported files skip the profile form, so values that would reach the `ssh`
+// command line as options are rejected here too.
+fn option_like_field(profile: &ConnectionProfile) -> Option<&'static str> {
+    let starts_with_dash = |value: &str| value.trim_start().starts_with('-');
+    if starts_with_dash(&profile.host) {
+        return Some("host");
+    }
+    let tunnel = profile.ssh_tunnel.as_ref()?;
+    if starts_with_dash(&tunnel.host) {
+        Some("SSH host")
+    } else if starts_with_dash(&tunnel.username) {
+        Some("SSH user")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_json(json: &str) -> (ProfileImport, Vec<ConnectionProfile>) {
+        let dir = std::env::temp_dir().join(format!("dbmiru-import-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("import.json");
+        fs::write(&path, json).unwrap();
+        let mut profiles = Vec::new();
+        let import = ProfileStore::new(&dir)
+            .import(&mut profiles, &path)
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        (import, profiles)
+    }
+
+    #[test]
+    fn import_rejects_option_like_ssh_fields() {
+        let (import, profiles) = import_json(
+            r#"[
+                {"name": "ok", "host": "db", "port": 5432, "database": "app", "username": "u",
+                 "ssh_tunnel": {"host": "bastion", "port": 22, "username": "deploy"}},
+                {"name": "a", "host": "-oProxyCommand=x", "port": 5432, "database": "app", "username": "u"},
+                {"name": "b", "host": "db", "port": 5432, "database": "app", "username": "u",
+                 "ssh_tunnel": {"host": "-oProxyCommand=x", "port": 22, "username": "deploy"}},
+                {"name": "c", "host": "db", "port": 5432, "database": "app", "username": "u",
+                 "ssh_tunnel": {"host": "bastion", "port": 22, "username": "-oProxyCommand=x"}}
+            ]"#,
+        );
+        assert_eq!(import.imported, 1);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(
+            import.errors,
+            vec![
+                "profile 2: host must not start with \"-\"",
+                "profile 3: SSH host must not start with \"-\"",
+                "profile 4: SSH user must not start with \"-\"",
+            ]
+        );
+    }
 }
@@ -0,0 +1,80 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dbmiru_core::{Result, profiles::ProfileId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionTab {
+    #[default]
+    SchemaBrowser,
+    SqlEditor,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SessionState {
+    #[serde(default)]
+    pub active_tab: SessionTab,
+    // Profile whose SQL was in the editor on exit (`None` before any connect).
+    #[serde(default)]
+    pub editor_profile: Option<ProfileId>,
+    #[serde(default)]
+    pub unscoped_sql: String,
+    #[serde(default)]
+    pub profile_sql: HashMap<ProfileId, String>,
+}
+
+impl SessionState {
+    pub fn sql_for(&self, profile_id: Option<ProfileId>) -> &str {
+        match profile_id {
+            Some(profile_id) => self
+                .profile_sql
+                .get(&profile_id)
+                .map(String::as_str)
+                .unwrap_or_default(),
+            None => &self.unscoped_sql,
+        }
+    }
+
+    pub fn set_sql(&mut self, profile_id: Option<ProfileId>, sql: String) {
+        match profile_id {
+            Some(profile_id) if sql.is_empty() => {
+                self.profile_sql.remove(&profile_id);
+            }
+            Some(profile_id) => {
+                self.profile_sql.insert(profile_id, sql);
+            }
+            None => self.unscoped_sql = sql,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SessionStore {
+    path: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(config_dir: &Path) -> Self {
+        let path = config_dir.join("session.json");
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<SessionState> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(SessionState::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, state: &SessionState) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(state)?;
+        fs::write(&self.path, serialized)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dbmiru_core::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+fn default_auto_close_brackets() -> bool {
+    true
+}
+
+fn default_copy_null_as_empty() -> bool {
+    true
+}
+
+fn default_type_colors() -> bool {
+    true
+}
+
+pub const DEFAULT_CELL_MAX_CHARS: usize = 200;
+
+fn default_cell_max_chars() -> usize {
+    DEFAULT_CELL_MAX_CHARS
+}
+
+pub const DEFAULT_SIDEBAR_WIDTH: f32 = 320.;
+pub const DEFAULT_SCHEMA_LIST_WIDTH: f32 = 200.;
+pub const DEFAULT_TABLE_LIST_WIDTH: f32 = 240.;
+pub const DEFAULT_EDITOR_HEIGHT: f32 = 200.;
+
+fn default_sidebar_width() -> f32 {
+    DEFAULT_SIDEBAR_WIDTH
+}
+
+fn default_schema_list_width() -> f32 {
+    DEFAULT_SCHEMA_LIST_WIDTH
+}
+
+fn default_table_list_width() -> f32 {
+    DEFAULT_TABLE_LIST_WIDTH
+}
+
+fn default_editor_height() -> f32 {
+    DEFAULT_EDITOR_HEIGHT
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub theme: ThemeMode,
+    #[serde(default)]
+    pub collapsed_groups: Vec<String>,
+    #[serde(default)]
+    pub show_table_sizes: bool,
+    #[serde(default = "default_auto_close_brackets")]
+    pub auto_close_brackets: bool,
+    #[serde(default = "default_cell_max_chars")]
+    pub cell_max_chars: usize,
+    #[serde(default)]
+    pub row_density: RowDensity,
+    #[serde(default)]
+    pub thousands_separators: bool,
+    #[serde(default = "default_type_colors")]
+    pub type_colors: bool,
+    // Result cells wrap onto a few lines; off keeps every row to a single line.
+    #[serde(default)]
+    pub wrap_cells: bool,
+    // Clicking a NULL grid cell copies an empty string; when off it copies nothing.
+    #[serde(default = "default_copy_null_as_empty")]
+    pub copy_null_as_empty: bool,
+    #[serde(default = "default_sidebar_width")]
+    pub sidebar_width: f32,
+    #[serde(default = "default_schema_list_width")]
+    pub schema_list_width: f32,
+    #[serde(default = "default_table_list_width")]
+    pub table_list_width: f32,
+    #[serde(default = "default_editor_height")]
+    pub editor_height: f32,
+    // Minutes without input after which the session is disconnected; 0 turns it off.
+    #[serde(default)]
+    pub idle_disconnect_minutes: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: ThemeMode::default(),
+            collapsed_groups: Vec::new(),
+            show_table_sizes: false,
+            auto_close_brackets: default_auto_close_brackets(),
+            cell_max_chars: DEFAULT_CELL_MAX_CHARS,
+            row_density: RowDensity::default(),
+            thousands_separators: false,
+            type_colors: true,
+            wrap_cells: false,
+            copy_null_as_empty: true,
+            sidebar_width: DEFAULT_SIDEBAR_WIDTH,
+            schema_list_width: DEFAULT_SCHEMA_LIST_WIDTH,
+            table_list_width: DEFAULT_TABLE_LIST_WIDTH,
+            editor_height: DEFAULT_EDITOR_HEIGHT,
+            idle_disconnect_minutes: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new(config_dir: &Path) -> Self {
+        let path = config_dir.join("settings.json");
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Settings> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => {
+                let settings: Settings = serde_json::from_str(&contents)?;
+                Ok(settings)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, settings: &Settings) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(settings)?;
+        fs::write(&self.path, serialized)?;
+        Ok(())
+    }
+}
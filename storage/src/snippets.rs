@@ -0,0 +1,124 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dbmiru_core::{Result, profiles::ProfileId};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub type SnippetId = Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snippet {
+    pub id: SnippetId,
+    pub profile_id: ProfileId,
+    pub name: String,
+    pub sql: String,
+}
+
+impl Snippet {
+    pub fn new(profile_id: ProfileId, name: impl Into<String>, sql: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            profile_id,
+            name: name.into(),
+            sql: sql.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportedSnippet {
+    name: String,
+    sql: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SnippetStore {
+    path: PathBuf,
+}
+
+impl SnippetStore {
+    pub fn new(config_dir: &Path) -> Self {
+        let path = config_dir.join("snippets.json");
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Vec<Snippet>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => {
+                let snippets: Vec<Snippet> = serde_json::from_str(&contents)?;
+                Ok(snippets)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, snippets: &[Snippet]) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(snippets)?;
+        fs::write(&self.path, serialized)?;
+        Ok(())
+    }
+
+    pub fn add(&self, snippets: &mut Vec<Snippet>, snippet: Snippet) -> Result<()> {
+        snippets.push(snippet);
+        self.save(snippets)
+    }
+
+    pub fn update(
+        &self,
+        snippets: &mut [Snippet],
+        id: SnippetId,
+        name: &str,
+        sql: &str,
+    ) -> Result<()> {
+        if let Some(snippet) = snippets.iter_mut().find(|snippet| snippet.id == id) {
+            snippet.name = name.to_string();
+            snippet.sql = sql.to_string();
+        }
+        self.save(snippets)
+    }
+
+    pub fn delete(&self, snippets: &mut Vec<Snippet>, id: SnippetId) -> Result<()> {
+        snippets.retain(|snippet| snippet.id != id);
+        self.save(snippets)
+    }
+
+    pub fn export(
+        &self,
+        snippets: &[Snippet],
+        profile_id: ProfileId,
+        path: &Path,
+    ) -> Result<usize> {
+        let exported: Vec<ExportedSnippet> = snippets
+            .iter()
+            .filter(|snippet| snippet.profile_id == profile_id)
+            .map(|snippet| ExportedSnippet {
+                name: snippet.name.clone(),
+                sql: snippet.sql.clone(),
+            })
+            .collect();
+        fs::write(path, serde_json::to_string_pretty(&exported)?)?;
+        Ok(exported.len())
+    }
+
+    pub fn import(
+        &self,
+        snippets: &mut Vec<Snippet>,
+        profile_id: ProfileId,
+        path: &Path,
+    ) -> Result<usize> {
+        let contents = fs::read_to_string(path)?;
+        let imported: Vec<ExportedSnippet> = serde_json::from_str(&contents)?;
+        let count = imported.len();
+        snippets.extend(
+            imported
+                .into_iter()
+                .map(|snippet| Snippet::new(profile_id, snippet.name, snippet.sql)),
+        );
+        self.save(snippets)?;
+        Ok(count)
+    }
+}
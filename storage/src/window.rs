@@ -0,0 +1,46 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dbmiru_core::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct WindowStateStore {
+    path: PathBuf,
+}
+
+impl WindowStateStore {
+    pub fn new(config_dir: &Path) -> Self {
+        let path = config_dir.join("window.json");
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Option<WindowState>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => {
+                let state: WindowState = serde_json::from_str(&contents)?;
+                Ok(Some(state))
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, state: &WindowState) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(state)?;
+        fs::write(&self.path, serialized)?;
+        Ok(())
+    }
+}